@@ -0,0 +1,102 @@
+# Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+"""
+Compares two logs written by `rustc -Znll-compare-log=PATH`, typically
+by two different compilers for the same crate, and reports the first
+section in which they differ.
+
+    python nll-compare-logs.py OLD.log NEW.log
+
+A log is a sequence of bodies, each of the form
+
+    body <item path>
+    version <compiler version> (format <n>)
+    section <name>
+    <lines>
+    ...
+    end body
+
+The bodies are matched up by item path, in the order of OLD.log. The
+version lines are expected to differ and are only shown. Exits with
+status 0 if the logs agree, and 1 otherwise.
+"""
+
+import difflib
+import sys
+
+
+def parse_log(path):
+    """Returns a list of `(item path, version, [(section, [line])])`."""
+    bodies = []
+    body = None
+    with open(path) as f:
+        for lineno, line in enumerate(f, 1):
+            line = line.rstrip('\n')
+            if line.startswith('body '):
+                body = (line[len('body '):], None, [])
+            elif body is None:
+                sys.exit('{}:{}: expected `body`'.format(path, lineno))
+            elif line.startswith('version '):
+                body = (body[0], line[len('version '):], body[2])
+            elif line.startswith('section '):
+                body[2].append((line[len('section '):], []))
+            elif line == 'end body':
+                bodies.append(body)
+                body = None
+            elif not body[2]:
+                sys.exit('{}:{}: expected `section`'.format(path, lineno))
+            else:
+                body[2][-1][1].append(line)
+    if body is not None:
+        sys.exit('{}: unterminated body `{}`'.format(path, body[0]))
+    return bodies
+
+
+def first_divergence(old_bodies, new_bodies):
+    """Returns a description of the first difference, or None."""
+    new_by_path = dict((body[0], body) for body in new_bodies)
+    for path, old_version, old_sections in old_bodies:
+        if path not in new_by_path:
+            return 'body `{}` is missing from the new log'.format(path)
+        _, new_version, new_sections = new_by_path.pop(path)
+        old_dict = dict(old_sections)
+        new_dict = dict(new_sections)
+        names = [name for name, _ in old_sections]
+        names += [name for name, _ in new_sections if name not in old_dict]
+        for name in names:
+            old_lines = old_dict.get(name)
+            new_lines = new_dict.get(name)
+            if old_lines == new_lines:
+                continue
+            header = 'first divergence: body `{}`, section `{}`\n'.format(path, name)
+            header += '  old: {}\n  new: {}\n'.format(old_version, new_version)
+            diff = difflib.unified_diff(old_lines or [], new_lines or [],
+                                        'old', 'new', lineterm='')
+            return header + '\n'.join(diff)
+    if new_by_path:
+        extra = sorted(new_by_path.keys())[0]
+        return 'body `{}` is missing from the old log'.format(extra)
+    return None
+
+
+def main():
+    if len(sys.argv) != 3:
+        sys.exit('usage: {} OLD.log NEW.log'.format(sys.argv[0]))
+    divergence = first_divergence(parse_log(sys.argv[1]), parse_log(sys.argv[2]))
+    if divergence is None:
+        print('the logs agree')
+        return 0
+    print(divergence)
+    return 1
+
+
+if __name__ == '__main__':
+    sys.exit(main())
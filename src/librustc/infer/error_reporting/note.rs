@@ -136,6 +136,10 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                 err.span_note(span,
                               "...so that references are valid when the destructor runs");
             }
+            infer::ClosureRequirement(span) => {
+                err.span_note(span,
+                              "...so that the requirements of the closure body are satisfied");
+            }
             infer::CompareImplMethodObligation { span, .. } => {
                 err.span_note(span,
                               "...so that the definition in impl matches the definition from the \
@@ -442,6 +446,16 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                                                  "");
                 err
             }
+            infer::ClosureRequirement(span) => {
+                let mut err = self.tcx.sess.struct_span_err(span,
+                                                            "closure requires a lifetime \
+                                                             relationship that does not hold");
+                self.tcx.note_and_explain_region(region_scope_tree, &mut err,
+                    "the closure requires data valid for ", sup, "");
+                self.tcx.note_and_explain_region(region_scope_tree, &mut err,
+                    "to outlive ", sub, "");
+                err
+            }
             infer::CompareImplMethodObligation { span,
                                                  item_name,
                                                  impl_item_def_id,
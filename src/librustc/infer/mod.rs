@@ -286,6 +286,11 @@ pub enum SubregionOrigin<'tcx> {
     // Region constraint arriving from destructor safety
     SafeDestructor(Span),
 
+    // Region constraint imposed on a closure's creator by the region
+    // requirements of the closure; the span points at the statement
+    // inside the closure body that gave rise to the requirement
+    ClosureRequirement(Span),
+
     // Comparing the signature and requirements of an impl method against
     // the containing trait.
     CompareImplMethodObligation {
@@ -362,6 +367,18 @@ pub enum NLLRegionVariableOrigin {
     // elsewhere. This origin indices we've got one of those.
     FreeRegion,
 
+    // A variable that stands in for an occurrence of `ReEmpty` in
+    // the constraints of an NLL body; it is not a free region, so it
+    // starts out empty.
+    Empty,
+
+    // A variable that stands in for a skolemized region (the
+    // placeholder for the bound region of a higher-ranked obligation,
+    // like the `'x` in `for<'x> &'x T: Trait`) that shows up in the
+    // constraints of an NLL body. Like a free region, it could be any
+    // region, so we know nothing it outlives.
+    Placeholder(ty::BoundRegion),
+
     Inferred(::mir::visit::TyContext),
 }
 
@@ -1586,6 +1603,7 @@ impl<'tcx> SubregionOrigin<'tcx> {
             AddrOf(a) => a,
             AutoBorrow(a) => a,
             SafeDestructor(a) => a,
+            ClosureRequirement(a) => a,
             CompareImplMethodObligation { span, .. } => span,
         }
     }
@@ -1599,6 +1617,9 @@ impl<'tcx> SubregionOrigin<'tcx> {
             traits::ObligationCauseCode::ReferenceOutlivesReferent(ref_type) =>
                 SubregionOrigin::ReferenceOutlivesReferent(ref_type, cause.span),
 
+            traits::ObligationCauseCode::ClosureRequirement =>
+                SubregionOrigin::ClosureRequirement(cause.span),
+
             traits::ObligationCauseCode::CompareImplMethodObligation { item_name,
                                                                        impl_item_def_id,
                                                                        trait_item_def_id, } =>
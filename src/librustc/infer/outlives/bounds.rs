@@ -9,6 +9,7 @@
 // except according to those terms.
 
 use infer::InferCtxt;
+use rustc_data_structures::fx::FxHashSet;
 use syntax::ast;
 use syntax::codemap::Span;
 use traits::FulfillmentContext;
@@ -23,7 +24,7 @@ use ty::wf;
 /// case they are called implied bounds). They are fed to the
 /// `OutlivesEnv` which in turn is supplied to the region checker and
 /// other parts of the inference system.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum OutlivesBound<'tcx> {
     RegionSubRegion(ty::Region<'tcx>, ty::Region<'tcx>),
     RegionSubParam(ty::Region<'tcx>, ty::ParamTy),
@@ -58,6 +59,47 @@ impl<'cx, 'gcx, 'tcx> InferCtxt<'cx, 'gcx, 'tcx> {
         body_id: ast::NodeId,
         ty: Ty<'tcx>,
         span: Span,
+    ) -> Vec<OutlivesBound<'tcx>> {
+        let mut fulfill_cx = FulfillmentContext::new();
+        let implied_bounds =
+            self.implied_outlives_bounds_in(&mut fulfill_cx, param_env, body_id, ty, span);
+        self.select_implied_bounds_obligations(&mut fulfill_cx);
+        implied_bounds
+    }
+
+    /// Like `implied_outlives_bounds`, but for several types at once,
+    /// returning the bounds for each type in the same order as
+    /// `tys`. Callers that need the bounds of many types (e.g., of
+    /// all the inputs and the output of a fn) should prefer this, as
+    /// the obligations registered along the way are only solved once,
+    /// at the end.
+    pub fn implied_outlives_bounds_for_tys(
+        &self,
+        param_env: ty::ParamEnv<'tcx>,
+        body_id: ast::NodeId,
+        tys: &[Ty<'tcx>],
+        span: Span,
+    ) -> Vec<Vec<OutlivesBound<'tcx>>> {
+        let mut fulfill_cx = FulfillmentContext::new();
+        let implied_bounds = tys.iter()
+            .map(|&ty| {
+                self.implied_outlives_bounds_in(&mut fulfill_cx, param_env, body_id, ty, span)
+            })
+            .collect();
+        self.select_implied_bounds_obligations(&mut fulfill_cx);
+        implied_bounds
+    }
+
+    /// Computes the implied bounds of `ty`, registering any
+    /// obligations involving inference variables in `fulfill_cx`
+    /// (see below for why).
+    fn implied_outlives_bounds_in(
+        &self,
+        fulfill_cx: &mut FulfillmentContext<'tcx>,
+        param_env: ty::ParamEnv<'tcx>,
+        body_id: ast::NodeId,
+        ty: Ty<'tcx>,
+        span: Span,
     ) -> Vec<OutlivesBound<'tcx>> {
         let tcx = self.tcx;
 
@@ -66,13 +108,28 @@ impl<'cx, 'gcx, 'tcx> InferCtxt<'cx, 'gcx, 'tcx> {
         // process it next. Currently (at least) these resulting
         // predicates are always guaranteed to be a subset of the original
         // type, so we need not fear non-termination.
+        //
+        // We may be asked for the same U many times, though: for
+        // `<U as Trait<U>>::Assoc`, each of the two `U`s yields `U: WF`,
+        // and if `U` is itself such a projection, this doubles with
+        // every level of nesting. So we process each type only once;
+        // the bounds that it implies would be the same each time.
+        //
+        // (Note that `wf::obligations` already descends into the
+        // types nested in `ty` itself, e.g. the `Wrapper<'a, T>` in
+        // `Vec<Wrapper<'a, T>>`, and gives the where-clauses of each
+        // ADT it finds; only the trait refs of projections are left
+        // to us.)
         let mut wf_types = vec![ty];
+        let mut visited = FxHashSet();
 
         let mut implied_bounds = vec![];
 
-        let mut fulfill_cx = FulfillmentContext::new();
-
         while let Some(ty) = wf_types.pop() {
+            if !visited.insert(ty) {
+                continue;
+            }
+
             // Compute the obligations for `ty` to be well-formed. If `ty` is
             // an unresolved inference variable, just substituted an empty set
             // -- because the return type here is going to be things we *add*
@@ -148,14 +205,16 @@ impl<'cx, 'gcx, 'tcx> InferCtxt<'cx, 'gcx, 'tcx> {
             }));
         }
 
-        // Ensure that those obligations that we had to solve
-        // get solved *here*.
+        implied_bounds
+    }
+
+    /// Ensure that those obligations that we had to solve while
+    /// computing implied bounds get solved *here*.
+    fn select_implied_bounds_obligations(&self, fulfill_cx: &mut FulfillmentContext<'tcx>) {
         match fulfill_cx.select_all_or_error(self) {
             Ok(()) => (),
             Err(errors) => self.report_fulfillment_errors(&errors, None),
         }
-
-        implied_bounds
     }
 
     /// When we have an implied bound that `T: 'a`, we can further break
@@ -234,6 +234,18 @@ declare_lint! {
     "detect coercion to !"
 }
 
+declare_lint! {
+    pub UNSAFE_REGION_ERRORS,
+    Deny,
+    "region errors due only to code within `unsafe` blocks (under `-Z nll-relaxed-unsafe`)"
+}
+
+declare_lint! {
+    pub REDUNDANT_LIFETIME_BOUNDS,
+    Allow,
+    "lifetime bounds that are implied by other bounds (checked by the NLL borrow checker)"
+}
+
 /// Does nothing as a lint pass, but registers some `Lint`s
 /// which are used by other parts of the compiler.
 #[derive(Copy, Clone)]
@@ -277,7 +289,9 @@ impl LintPass for HardwiredLints {
             DEPRECATED,
             UNUSED_UNSAFE,
             UNUSED_MUT,
-            COERCE_NEVER
+            COERCE_NEVER,
+            UNSAFE_REGION_ERRORS,
+            REDUNDANT_LIFETIME_BOUNDS
         )
     }
 }
@@ -18,6 +18,7 @@ use rustc_data_structures::indexed_vec::{IndexVec, Idx};
 use rustc_data_structures::control_flow_graph::dominators::{Dominators, dominators};
 use rustc_data_structures::control_flow_graph::{GraphPredecessors, GraphSuccessors};
 use rustc_data_structures::control_flow_graph::ControlFlowGraph;
+use rustc_data_structures::transitive_relation::TransitiveRelation;
 use rustc_serialize as serialize;
 use hir::def::CtorKind;
 use hir::def_id::DefId;
@@ -711,7 +712,10 @@ pub enum TerminatorKind<'tcx> {
     Yield {
         /// The value to return
         value: Operand<'tcx>,
-        /// Where to resume to
+        /// Where to resume to. The result of the `yield` expression
+        /// is written by an ordinary assignment at the start of this
+        /// block, so dataflow sees it as an initialization (and a
+        /// write killing borrows of the destination) like any other.
         resume: BasicBlock,
         /// Cleanup to be done if the generator is dropped at this suspend point
         drop: Option<BasicBlock>,
@@ -1034,6 +1038,13 @@ pub enum StatementKind<'tcx> {
     /// Write the discriminant for a variant to the enum Place.
     SetDiscriminant { place: Place<'tcx>, variant_index: usize },
 
+    /// Read the place, for the sake of the borrow checker only: this
+    /// ensures that the place is still valid (e.g., that the
+    /// scrutinee of a `match` is not mutated by a guard), but it has
+    /// no effect on the generated code. These statements are removed
+    /// once borrowck has run.
+    FakeRead(FakeReadCause, Place<'tcx>),
+
     /// Start a live range for the storage of the local.
     StorageLive(Local),
 
@@ -1060,6 +1071,22 @@ pub enum StatementKind<'tcx> {
     Nop,
 }
 
+/// Why a `FakeRead` statement was emitted.
+#[derive(Copy, Clone, RustcEncodable, RustcDecodable, Debug, PartialEq, Eq)]
+pub enum FakeReadCause {
+    /// The scrutinee of a `match`, which must not be changed while
+    /// the arms are being selected.
+    ForMatch,
+
+    /// The initializer of a `let` with a pattern, which is read even
+    /// if the pattern binds nothing (e.g., `let _ = x;`).
+    ForLet,
+
+    /// A variable that a closure captures by unique immutable borrow,
+    /// which must not be mutably borrowed when the closure is created.
+    ForCapture,
+}
+
 /// The `ValidationOp` describes what happens with each of the operands of a
 /// `Validate` statement.
 #[derive(Copy, Clone, RustcEncodable, RustcDecodable, PartialEq, Eq)]
@@ -1118,6 +1145,7 @@ impl<'tcx> Debug for Statement<'tcx> {
             Assign(ref place, ref rv) => write!(fmt, "{:?} = {:?}", place, rv),
             // (reuse lifetime rendering policy from ppaux.)
             EndRegion(ref ce) => write!(fmt, "EndRegion({})", ty::ReScope(*ce)),
+            FakeRead(ref cause, ref place) => write!(fmt, "FakeRead({:?}, {:?})", cause, place),
             Validate(ref op, ref places) => write!(fmt, "Validate({:?}, {:?})", op, places),
             StorageLive(ref place) => write!(fmt, "StorageLive({:?})", place),
             StorageDead(ref place) => write!(fmt, "StorageDead({:?})", place),
@@ -1831,8 +1859,8 @@ pub struct GeneratorLayout<'tcx> {
 /// instance of the closure is created, the corresponding free regions
 /// can be extracted from its type and constrained to have the given
 /// outlives relationship.
-#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
-pub struct ClosureRegionRequirements {
+#[derive(Clone, RustcEncodable, RustcDecodable)]
+pub struct ClosureRegionRequirements<'gcx> {
     /// The number of external regions defined on the closure.  In our
     /// example above, it would be 3 -- one for `'static`, then `'1`
     /// and `'2`. This is just used for a sanity check later on, to
@@ -1842,23 +1870,252 @@ pub struct ClosureRegionRequirements {
 
     /// Requirements between the various free regions defined in
     /// indices.
-    pub outlives_requirements: Vec<ClosureOutlivesRequirement>,
+    pub outlives_requirements: Vec<ClosureOutlivesRequirement<'gcx>>,
 }
 
-/// Indicates an outlives constraint between two free-regions declared
-/// on the closure.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, RustcEncodable, RustcDecodable)]
-pub struct ClosureOutlivesRequirement {
-    // This region ...
-    pub free_region: ty::RegionVid,
+/// Indicates an outlives constraint between a type or between two
+/// free-regions declared on the closure.
+#[derive(Copy, Clone, RustcEncodable, RustcDecodable)]
+pub struct ClosureOutlivesRequirement<'tcx> {
+    // This region or type ...
+    pub subject: ClosureOutlivesSubject<'tcx>,
 
     // .. must outlive this one.
     pub outlived_free_region: ty::RegionVid,
 
-    // If not, report an error here.
+    // If not, report an error here. This is the span of the statement
+    // within the closure body that gave rise to the requirement; the
+    // creator uses it to point into the closure when it reports that
+    // the requirement does not hold.
     pub blame_span: Span,
 }
 
+/// The subject of a `ClosureOutlivesRequirement` -- that is, the thing
+/// that must outlive some region.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, RustcEncodable, RustcDecodable)]
+pub enum ClosureOutlivesSubject<'tcx> {
+    /// Subject is a type, typically a type parameter, but could also
+    /// be a projection. The type contains no regions: the closure
+    /// only propagates a failed type test if its creator can state
+    /// it without naming any of the closure's regions, and any type
+    /// parameters are those of the closure, which its creator
+    /// substitutes with the closure substs.
+    Ty(Ty<'tcx>),
+
+    /// Subject is a free region from the closure. Indicates a requirement
+    /// like `'a: 'b` being propagated to the closure creator.
+    Region(ty::RegionVid),
+}
+
+impl<'gcx> ClosureRegionRequirements<'gcx> {
+    /// Checks that every region named by the requirements is one of
+    /// the `num_external_vids` external regions of the closure. The
+    /// creator indexes the free regions of the closure type with
+    /// these vids, so a value loaded from a corrupted or out-of-date
+    /// cache must be rejected before it is applied.
+    pub fn validate(&self) -> Result<(), InvalidClosureRegionRequirement> {
+        for (index, outlives_requirement) in self.outlives_requirements.iter().enumerate() {
+            let mut regions = vec![outlives_requirement.outlived_free_region];
+            if let ClosureOutlivesSubject::Region(region) = outlives_requirement.subject {
+                regions.push(region);
+            }
+            for &region in &regions {
+                if region.index() >= self.num_external_vids {
+                    return Err(InvalidClosureRegionRequirement { index, region });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the requirements that are implied by the others:
+    /// duplicates, `'1: '1`, and pairs like `'1: '3` when `'1: '2`
+    /// and `'2: '3` are also required. The closure finds the same
+    /// requirement once per constraint path that needs it, and each
+    /// one would otherwise be stored in the metadata and checked by
+    /// the creator. We drop requirements starting from the back, so
+    /// of several duplicates the first is kept and errors point at the
+    /// same statement as before.
+    ///
+    /// Of the requirements on types, only exact duplicates are
+    /// dropped: whether `T: '1` and `'1: '2` imply `T: '2` is up to
+    /// the creator.
+    pub fn minimize(&mut self) {
+        // A requirement is only dropped if the ones that remain imply
+        // it, so this also does the right thing for cycles: given
+        // `'1: '2`, `'2: '1`, `'1: '3` and `'2: '3`, either of the
+        // last two implies the other, but only one of them can go.
+        for index in (0..self.outlives_requirements.len()).rev() {
+            let requirement = self.outlives_requirements[index];
+            let sub = requirement.outlived_free_region;
+
+            let implied = match requirement.subject {
+                ClosureOutlivesSubject::Region(sup) => {
+                    let mut others = TransitiveRelation::new();
+                    for (other_index, other) in self.outlives_requirements.iter().enumerate() {
+                        if let ClosureOutlivesSubject::Region(other_sup) = other.subject {
+                            if other_index != index {
+                                others.add(other_sup, other.outlived_free_region);
+                            }
+                        }
+                    }
+                    sup == sub || others.contains(&sup, &sub)
+                }
+
+                ClosureOutlivesSubject::Ty(_) => {
+                    self.outlives_requirements[..index].iter().any(|other| {
+                        other.subject == requirement.subject && other.outlived_free_region == sub
+                    })
+                }
+            };
+
+            if implied {
+                self.outlives_requirements.remove(index);
+            }
+        }
+    }
+
+    /// The requirements in the order in which they are printed. Types
+    /// have no order of their own, so the requirements on regions come
+    /// first, and those on types are sorted by how they print.
+    pub fn sorted_outlives_requirements(&self) -> Vec<&ClosureOutlivesRequirement<'gcx>> {
+        let mut outlives_requirements: Vec<_> = self.outlives_requirements.iter().collect();
+        outlives_requirements.sort_by_key(|requirement| {
+            let subject = match requirement.subject {
+                ClosureOutlivesSubject::Region(region) => (0, region.index(), String::new()),
+                ClosureOutlivesSubject::Ty(ty) => (1, 0, format!("{:?}", ty)),
+            };
+            (subject, requirement.outlived_free_region, requirement.blame_span)
+        });
+        outlives_requirements
+    }
+}
+
+/// The error returned by `ClosureRegionRequirements::validate`: the
+/// requirement at `index` names `region`, which is not an external
+/// region of the closure.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidClosureRegionRequirement {
+    pub index: usize,
+    pub region: ty::RegionVid,
+}
+
+/// The canonical textual form of a set of closure region
+/// requirements, used by both `Debug` and `Display` so that logs and
+/// test output agree: a line giving the number of external vids,
+/// followed by one line per requirement, in sorted order.
+impl<'gcx> fmt::Display for ClosureRegionRequirements<'gcx> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "external vids: {}", self.num_external_vids)?;
+
+        for outlives_requirement in self.sorted_outlives_requirements() {
+            write!(fmt, "\n{}", outlives_requirement)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'gcx> Debug for ClosureRegionRequirements<'gcx> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+/// Formats the requirement as `'_#1r: '_#3r @ span`, or `T: '_#3r @
+/// span` for a requirement on a type.
+impl<'tcx> fmt::Display for ClosureOutlivesRequirement<'tcx> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "{:?}: {:?} @ {:?}",
+            self.subject,
+            self.outlived_free_region,
+            self.blame_span
+        )
+    }
+}
+
+impl<'tcx> Debug for ClosureOutlivesRequirement<'tcx> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+impl<'tcx> Debug for ClosureOutlivesSubject<'tcx> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match *self {
+            ClosureOutlivesSubject::Ty(ty) => write!(fmt, "{:?}", ty),
+            ClosureOutlivesSubject::Region(region) => write!(fmt, "{:?}", region),
+        }
+    }
+}
+
+/// The result of the MIR borrow check of a body; see the
+/// `mir_borrowck` query.
+#[derive(Clone, RustcEncodable, RustcDecodable)]
+pub struct BorrowCheckResult<'gcx> {
+    /// If the body is a closure, the requirements that it imposes on
+    /// its creator.
+    pub closure_requirements: Option<ClosureRegionRequirements<'gcx>>,
+
+    /// The universal regions of the body, if NLL ran on it.
+    pub universal_regions_summary: Option<Rc<UniversalRegionsSummary<'gcx>>>,
+}
+
+/// A summary of the universal regions of a MIR body -- the regions
+/// that the body does not infer but takes as given, like the lifetime
+/// parameters of a fn -- as computed by the NLL borrow checker. The
+/// regions are identified by the index that the borrow checker gave
+/// them, so the summary can be used outside of the borrow checker.
+///
+/// The regions are ordered by classification: the global regions
+/// (just `'static`) come first, then the external regions (only
+/// closures have any), then the local ones.
+///
+/// The types in the summary refer to the universal regions by the
+/// `ty::Region` they were created from, so that the summary is
+/// independent of any inference context and can be hashed and cached
+/// by the incremental system. Regions without such a region (like
+/// the anonymous late-bound regions of a fn) are erased.
+#[derive(Clone, Debug, RustcEncodable, RustcDecodable)]
+pub struct UniversalRegionsSummary<'tcx> {
+    /// The universal regions are `0..num_universals`.
+    pub num_universals: usize,
+
+    /// The external regions are `first_extern_index..first_local_index`.
+    pub first_extern_index: usize,
+
+    /// The local regions are `first_local_index..num_universals`.
+    pub first_local_index: usize,
+
+    /// Each pair `(a, b)` of distinct universal regions such that
+    /// `a: b` is known to hold, in sorted order.
+    pub outlives: Vec<(ty::RegionVid, ty::RegionVid)>,
+
+    /// The universal regions that can be named in the source, with
+    /// their names, in index order.
+    pub named_regions: Vec<(ty::RegionVid, Name)>,
+
+    /// Each universal region that was created from a `ty::Region`
+    /// (`'static`, the early-bound regions and the named late-bound
+    /// ones), with that region, in index order.
+    pub indices: Vec<(ty::RegionVid, ty::Region<'tcx>)>,
+
+    /// The type of the body: its fn type, or its closure or generator
+    /// type.
+    pub defining_ty: Ty<'tcx>,
+
+    /// The input types of the body, as seen from within it.
+    pub input_tys: Vec<Ty<'tcx>>,
+
+    /// The return type of the body, as seen from within it.
+    pub output_ty: Ty<'tcx>,
+
+    /// Each pair `(r, T)` stating that `T: r` can be assumed to hold
+    /// in the body, from the where-clauses and implied bounds.
+    pub region_bound_pairs: Vec<(ty::Region<'tcx>, Ty<'tcx>)>,
+}
+
 /*
  * TypeFoldable implementations for MIR types
  */
@@ -1956,6 +2213,7 @@ impl<'tcx> TypeFoldable<'tcx> for Statement<'tcx> {
                 place: place.fold_with(folder),
                 variant_index,
             },
+            FakeRead(cause, ref place) => FakeRead(cause, place.fold_with(folder)),
             StorageLive(ref local) => StorageLive(local.fold_with(folder)),
             StorageDead(ref local) => StorageDead(local.fold_with(folder)),
             InlineAsm { ref asm, ref outputs, ref inputs } => InlineAsm {
@@ -1987,7 +2245,8 @@ impl<'tcx> TypeFoldable<'tcx> for Statement<'tcx> {
 
         match self.kind {
             Assign(ref place, ref rval) => { place.visit_with(visitor) || rval.visit_with(visitor) }
-            SetDiscriminant { ref place, .. } => place.visit_with(visitor),
+            SetDiscriminant { ref place, .. } |
+            FakeRead(_, ref place) => place.visit_with(visitor),
             StorageLive(ref local) |
             StorageDead(ref local) => local.visit_with(visitor),
             InlineAsm { ref outputs, ref inputs, .. } =>
@@ -2274,3 +2533,71 @@ impl<'tcx> TypeFoldable<'tcx> for Literal<'tcx> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rustc_data_structures::indexed_vec::Idx;
+    use syntax_pos::DUMMY_SP;
+    use ty::RegionVid;
+    use super::{ClosureOutlivesRequirement, ClosureOutlivesSubject, ClosureRegionRequirements};
+    use super::InvalidClosureRegionRequirement;
+
+    fn requirements(num_external_vids: usize, pairs: &[(usize, usize)])
+                    -> ClosureRegionRequirements<'static> {
+        ClosureRegionRequirements {
+            num_external_vids,
+            outlives_requirements: pairs.iter().map(|&(a, b)| ClosureOutlivesRequirement {
+                subject: ClosureOutlivesSubject::Region(RegionVid::new(a)),
+                outlived_free_region: RegionVid::new(b),
+                blame_span: DUMMY_SP,
+            }).collect(),
+        }
+    }
+
+    #[test]
+    fn closure_region_requirements_in_range() {
+        assert_eq!(requirements(3, &[]).validate(), Ok(()));
+        assert_eq!(requirements(3, &[(1, 2), (2, 0)]).validate(), Ok(()));
+    }
+
+    #[test]
+    fn closure_region_requirements_out_of_range() {
+        assert_eq!(
+            requirements(3, &[(1, 2), (2, 3)]).validate(),
+            Err(InvalidClosureRegionRequirement { index: 1, region: RegionVid::new(3) })
+        );
+        assert_eq!(
+            requirements(0, &[(0, 0)]).validate(),
+            Err(InvalidClosureRegionRequirement { index: 0, region: RegionVid::new(0) })
+        );
+    }
+
+    #[test]
+    fn closure_region_requirements_minimize() {
+        let pairs = |requirements: &ClosureRegionRequirements| -> Vec<(usize, usize)> {
+            requirements.outlives_requirements.iter().map(|r| match r.subject {
+                ClosureOutlivesSubject::Region(region) => {
+                    (region.index(), r.outlived_free_region.index())
+                }
+                ClosureOutlivesSubject::Ty(_) => bug!("requirement on a type"),
+            }).collect()
+        };
+
+        // Duplicates, reflexive pairs and transitively implied pairs go.
+        let mut redundant = requirements(5, &[(1, 2), (1, 2), (2, 3), (3, 3), (1, 3), (3, 4),
+                                              (1, 4), (2, 4)]);
+        redundant.minimize();
+        assert_eq!(pairs(&redundant), vec![(1, 2), (2, 3), (3, 4)]);
+
+        // `(1, 3)` and `(2, 3)` each imply the other through the cycle,
+        // but only one of them can go.
+        let mut cycle = requirements(4, &[(1, 2), (2, 1), (1, 3), (2, 3)]);
+        cycle.minimize();
+        assert_eq!(pairs(&cycle), vec![(1, 2), (2, 1), (1, 3)]);
+
+        // Nothing to remove.
+        let mut minimal = requirements(4, &[(1, 2), (2, 3), (3, 1)]);
+        minimal.minimize();
+        assert_eq!(pairs(&minimal), vec![(1, 2), (2, 3), (3, 1)]);
+    }
+}
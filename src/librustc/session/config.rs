@@ -1188,6 +1188,45 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "choose which RELRO level to use"),
     nll: bool = (false, parse_bool, [UNTRACKED],
                  "run the non-lexical lifetimes MIR pass"),
+    nll_dump_mir_regions: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+        "how much region information to include in the NLL MIR dump (see -Z dump-mir): \
+         0 = none, 1 = the universal regions (the default), 2 = also the types of \
+         locals with their region variables, 3 = also the inferred region values"),
+    nll_dump_cause: bool = (false, parse_bool, [UNTRACKED],
+        "explain why the regions involved in NLL errors are live"),
+    nll_dump_region_graph: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "write the outlives relation among the universal regions of each MIR body \
+         as a graphviz file into the given directory"),
+    nll_facts: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "write the facts about the universal regions of each MIR body (their \
+         classification, outlives relation and region-bound pairs) as tab-separated \
+         files into the given directory, for use by an external region solver"),
+    nll_verify_universal_regions_fast_path: bool = (false, parse_bool, [UNTRACKED],
+        "compute the universal regions of region-free MIR bodies the slow way too, \
+         and check that the result matches the fast path"),
+    nll_verify_fold_fast_path: bool = (false, parse_bool, [UNTRACKED],
+        "fold the regions of region-free types in NLL the slow way too, and check \
+         that no region is found (only in builds with debug assertions)"),
+    nll_relaxed_unsafe: bool = (false, parse_bool, [UNTRACKED],
+        "report NLL region errors due only to code within `unsafe` blocks through \
+         the `unsafe_region_errors` lint rather than as hard errors"),
+    nll_compare_log: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "append a canonical log of the NLL region inference of each MIR body to the \
+         given file, for comparing compiler versions"),
+    nll_signature_borrows_report: bool = (false, parse_bool, [UNTRACKED],
+        "print, for each fn, the borrows whose region reaches its return points, and \
+         the regions of its signature that they have to outlive"),
+    nll_only: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "apply the NLL dump and trace options (-Z nll-dump-mir-regions, -Z nll-dump-cause, \
+         -Z nll-dump-region-graph, -Z nll-facts, -Z nll-compare-log and \
+         -Z nll-signature-borrows-report) only to the MIR bodies whose path contains \
+         the given string; the other bodies are still borrow-checked"),
+    nll_skip_unmatched: bool = (false, parse_bool, [UNTRACKED],
+        "do not borrow-check the MIR bodies that -Z nll-only does not match at all. \
+         This is unsound, and only meant to speed up compiler development"),
+    nll_shims: bool = (false, parse_bool, [UNTRACKED],
+        "also run the NLL region inference on the MIR shims (drop glue, `Clone` shims, \
+         fn-pointer shims) that translation builds; requires -Z nll"),
     trans_time_graph: bool = (false, parse_bool, [UNTRACKED],
         "generate a graphical HTML report of time spent in trans and LLVM"),
     thinlto: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -2744,6 +2783,30 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_graphviz = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_dump_mir_regions = Some(3);
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_dump_cause = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_dump_region_graph = Some(String::from("abc"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_facts = Some(String::from("abc"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_verify_universal_regions_fast_path = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_verify_fold_fast_path = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_relaxed_unsafe = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_compare_log = Some(String::from("abc"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_signature_borrows_report = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_only = Some(String::from("abc"));
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_skip_unmatched = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.nll_shims = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
         // Make sure changing a [TRACKED] option changes the hash
         opts = reference.clone();
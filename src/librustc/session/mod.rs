@@ -79,6 +79,16 @@ pub struct Session {
     /// (sub)diagnostics that have been set once, but should not be set again,
     /// in order to avoid redundantly verbose output (Issue #24690, #44953).
     pub one_time_diagnostics: RefCell<FxHashSet<(DiagnosticMessageId, Option<Span>, String)>>,
+    /// Region errors of the MIR borrow checker that point into macro
+    /// expansions, buffered while the bodies of the crate are checked so
+    /// that duplicates can be folded before they are emitted. `None`
+    /// when they are emitted right away.
+    pub buffered_region_errors: RefCell<Option<Vec<errors::Diagnostic>>>,
+    /// Bugs delayed by the MIR borrow checker, each tagged with a
+    /// category, buffered while the bodies of the crate are checked so
+    /// that they can be reported together. `None` when they are
+    /// delayed through `delay_span_bug` right away.
+    pub buffered_nll_bugs: RefCell<Option<Vec<(&'static str, errors::Diagnostic)>>>,
     pub plugin_llvm_passes: RefCell<Vec<String>>,
     pub plugin_attributes: RefCell<Vec<(String, AttributeType)>>,
     pub crate_types: RefCell<Vec<config::CrateType>>,
@@ -901,6 +911,8 @@ pub fn build_session_(sopts: config::Options,
         lint_store: RefCell::new(lint::LintStore::new()),
         buffered_lints: RefCell::new(Some(lint::LintBuffer::new())),
         one_time_diagnostics: RefCell::new(FxHashSet()),
+        buffered_region_errors: RefCell::new(None),
+        buffered_nll_bugs: RefCell::new(None),
         plugin_llvm_passes: RefCell::new(Vec::new()),
         plugin_attributes: RefCell::new(Vec::new()),
         crate_types: RefCell::new(Vec::new()),
@@ -193,6 +193,7 @@ impl<'a, 'tcx> Lift<'tcx> for traits::ObligationCauseCode<'a> {
             super::ReferenceOutlivesReferent(ty) => {
                 tcx.lift(&ty).map(super::ReferenceOutlivesReferent)
             }
+            super::ClosureRequirement => Some(super::ClosureRequirement),
             super::ObjectTypeBound(ty, r) => {
                 tcx.lift(&ty).and_then(|ty| {
                     tcx.lift(&r).and_then(|r| {
@@ -532,6 +533,7 @@ impl<'tcx> TypeFoldable<'tcx> for traits::ObligationCauseCode<'tcx> {
             super::ConstSized |
             super::SharedStatic |
             super::BlockTailExpression(_) |
+            super::ClosureRequirement |
             super::CompareImplMethodObligation { .. } => self.clone(),
 
             super::ProjectionWf(proj) => super::ProjectionWf(proj.fold_with(folder)),
@@ -580,6 +582,7 @@ impl<'tcx> TypeFoldable<'tcx> for traits::ObligationCauseCode<'tcx> {
             super::ConstSized |
             super::SharedStatic |
             super::BlockTailExpression(_) |
+            super::ClosureRequirement |
             super::CompareImplMethodObligation { .. } => false,
 
             super::ProjectionWf(proj) => proj.visit_with(visitor),
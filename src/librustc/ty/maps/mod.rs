@@ -193,7 +193,13 @@ define_maps! { <'tcx>
 
     /// Borrow checks the function body. If this is a closure, returns
     /// additional requirements that the closure's creator must verify.
-    [] fn mir_borrowck: MirBorrowCheck(DefId) -> Option<mir::ClosureRegionRequirements>,
+    [] fn mir_borrowck: MirBorrowCheck(DefId) -> mir::BorrowCheckResult<'tcx>,
+
+    /// The universal regions of the MIR body and what is known about
+    /// them, as computed by the NLL borrow checker. `None` if the
+    /// borrow checker did not run NLL on the body.
+    [] fn universal_regions_summary: UniversalRegionsSummary(DefId)
+        -> Option<Rc<mir::UniversalRegionsSummary<'tcx>>>,
 
     /// Gets a complete map from all types to their inherent impls.
     /// Not meant to be used directly outside of coherence.
@@ -8,8 +8,9 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use bitvec::BitMatrix;
+use bitvec::{BitMatrix, BitVector};
 use fx::FxHashMap;
+use indexed_vec::{Idx, IndexVec};
 use rustc_serialize::{Encodable, Encoder, Decodable, Decoder};
 use stable_hasher::{HashStable, StableHasher, StableHasherResult};
 use std::cell::RefCell;
@@ -149,6 +150,21 @@ impl<T: Clone + Debug + Eq + Hash + Clone> TransitiveRelation<T> {
         }
     }
 
+    /// Like `reachable_from`, but yields the things reachable from
+    /// `a` lazily, in the same order, rather than collecting them
+    /// into a vector.
+    pub fn reachable_from_iter<'a>(&'a self, a: &T) -> impl Iterator<Item = &'a T> + 'a {
+        let a = self.index(a);
+        self.elements
+            .iter()
+            .enumerate()
+            .filter(move |&(i, _)| match a {
+                Some(a) => self.with_closure(|closure| closure.contains(a.0, i)),
+                None => false,
+            })
+            .map(|(_, element)| element)
+    }
+
     /// Picks what I am referring to as the "postdominating"
     /// upper-bound for `a` and `b`. This is usually the least upper
     /// bound, but in cases where there is no single least upper
@@ -384,6 +400,30 @@ impl<T: Clone + Debug + Eq + Hash + Clone> TransitiveRelation<T> {
     }
 }
 
+impl<T: Idx + Hash> TransitiveRelation<T> {
+    /// Flattens the transitive closure into one bit vector per element
+    /// of the domain `0..num_elements`: the bit for `b` is set in the
+    /// row for `a` iff `a < b` (transitively). Elements that never
+    /// appear in the relation get an empty row. This is handy when the
+    /// relation is frozen and will be queried many times, since each
+    /// query then avoids the hashing and the `RefCell` of `contains`.
+    ///
+    /// Every element of the relation must have an index below
+    /// `num_elements`.
+    pub fn reachability_bits(&self, num_elements: usize) -> IndexVec<T, BitVector> {
+        let mut bits: IndexVec<T, BitVector> =
+            (0..num_elements).map(|_| BitVector::new(num_elements)).collect();
+        self.with_closure(|closure| {
+            for (i, &a) in self.elements.iter().enumerate() {
+                for j in closure.iter(i) {
+                    bits[a].insert(self.elements[j].index());
+                }
+            }
+        });
+        bits
+    }
+}
+
 /// Pare down is used as a step in the LUB computation. It edits the
 /// candidates array in place by removing any element j for which
 /// there exists an earlier element i<j such that i -> j. That is,
@@ -530,6 +570,52 @@ fn test_many_steps() {
     assert!(!relation.contains(&"b", &"f"));
 }
 
+#[test]
+fn test_reachable_from_iter() {
+    let mut relation = TransitiveRelation::new();
+    relation.add("a", "b");
+    relation.add("a", "c");
+    relation.add("b", "d");
+    relation.add("d", "e");
+
+    for x in &["a", "b", "c", "d", "e", "f"] {
+        let lazy: Vec<_> = relation.reachable_from_iter(x).collect();
+        assert_eq!(lazy, relation.reachable_from(x));
+    }
+    assert_eq!(relation.reachable_from_iter(&"b").collect::<Vec<_>>(), vec![&"d", &"e"]);
+}
+
+#[test]
+fn reachability_bits_agree_with_contains() {
+    // A tiny xorshift generator, so that the randomized relations are
+    // the same on every run.
+    let mut state: u32 = 0x2545_f491;
+    let mut next = |bound: usize| {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state as usize % bound
+    };
+
+    for _ in 0..100 {
+        let num_elements = 1 + next(16);
+        let mut relation = TransitiveRelation::new();
+        for _ in 0..next(3 * num_elements) {
+            let a = next(num_elements);
+            let b = next(num_elements);
+            relation.add(a, b);
+        }
+
+        let bits = relation.reachability_bits(num_elements);
+        for a in 0..num_elements {
+            for b in 0..num_elements {
+                assert_eq!(bits[a].contains(b), relation.contains(&a, &b),
+                           "disagreement on {} < {} in {:?}", a, b, relation);
+            }
+        }
+    }
+}
+
 #[test]
 fn mubs_triangle() {
     // a -> tcx
@@ -1060,7 +1060,7 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(control: &CompileController,
 
         time(time_passes,
              "MIR borrow checking",
-             || for def_id in tcx.body_owners() { tcx.mir_borrowck(def_id); });
+             || mir::mir_borrowck_crate(tcx));
 
         time(time_passes,
              "MIR effect checking",
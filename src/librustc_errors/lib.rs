@@ -456,6 +456,15 @@ impl Handler {
         diagnostic.set_span(sp.into());
         *self.delayed_span_bug.borrow_mut() = Some(diagnostic);
     }
+    /// Like `delay_span_bug`, but for a diagnostic that is already
+    /// built, e.g. one with notes.
+    pub fn delay_bug_diagnostic(&self, diagnostic: Diagnostic) {
+        if self.flags.treat_err_as_bug {
+            DiagnosticBuilder::new_diagnostic(self, diagnostic).emit();
+            panic!(ExplicitBug);
+        }
+        *self.delayed_span_bug.borrow_mut() = Some(diagnostic);
+    }
     pub fn span_bug_no_panic<S: Into<MultiSpan>>(&self, sp: S, msg: &str) {
         self.emit(&sp.into(), msg, Bug);
     }
@@ -0,0 +1,348 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use dataflow::indexes::BorrowIndex;
+use rustc::mir::{self, Location, Mir, Place};
+use rustc::mir::visit::{PlaceContext, Visitor};
+use rustc::ty::{self, Region, RegionKind, TyCtxt};
+use rustc::ty::RegionKind::ReScope;
+use rustc::util::nodemap::{FxHashMap, FxHashSet};
+use rustc_data_structures::indexed_vec::IndexVec;
+use syntax_pos::{ExpnFormat, Span};
+
+use std::fmt;
+use std::hash::Hash;
+
+/// The set of borrows (`Rvalue::Ref`) in a MIR body, together with
+/// the various maps used to find them again. This is computed once,
+/// up front, by `BorrowSet::build`, and then shared by the `Borrows`
+/// dataflow and by error reporting.
+pub struct BorrowSet<'tcx> {
+    /// The fundamental map relating bitvector indexes to the borrows
+    /// in the MIR.
+    pub(crate) borrows: IndexVec<BorrowIndex, BorrowData<'tcx>>,
+
+    /// Each borrow is also uniquely identified in the MIR by the
+    /// `Location` of the assignment statement in which it appears on
+    /// the right hand side; we map each such location to the
+    /// corresponding `BorrowIndex`.
+    pub(crate) location_map: FxHashMap<Location, BorrowIndex>,
+
+    /// Maps each location to the borrows that may be activated
+    /// there: that is, the borrows whose assigned place is used at
+    /// that location. Whether the activation actually happens still
+    /// depends on the borrow having been reserved on entry, which
+    /// is what the `ActiveBorrows` dataflow figures out.
+    pub(crate) activation_map: FxHashMap<Location, FxHashSet<BorrowIndex>>,
+
+    /// Every borrow in MIR is immediately stored into a place via an
+    /// assignment statement. This maps each such assigned place back
+    /// to its borrow-indexes.
+    pub(crate) assigned_map: FxHashMap<Place<'tcx>, FxHashSet<BorrowIndex>>,
+
+    /// Every borrow has a region; this maps each such regions back to
+    /// its borrow-indexes.
+    pub(crate) region_map: FxHashMap<Region<'tcx>, FxHashSet<BorrowIndex>>,
+
+    /// Maps each local to the borrows of data rooted in that local.
+    pub(crate) local_map: FxHashMap<mir::Local, FxHashSet<BorrowIndex>>,
+
+    /// Maps each `ReScope` region to the span of its `EndRegion`
+    /// statement.
+    pub(crate) region_span_map: FxHashMap<RegionKind, Span>,
+}
+
+// temporarily allow some dead fields: `kind` and `region` will be
+// needed by borrowck; `borrowed_place` will probably be a MovePathIndex when
+// that is extended to include borrowed data paths.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct BorrowData<'tcx> {
+    /// Location where the borrow is reserved, i.e. the assignment
+    /// statement holding the `Rvalue::Ref`.
+    pub(crate) reserve_location: Location,
+    pub(crate) kind: mir::BorrowKind,
+    pub(crate) region: Region<'tcx>,
+    pub(crate) borrowed_place: mir::Place<'tcx>,
+    pub(crate) assigned_place: mir::Place<'tcx>,
+    /// Span at which to point at the borrow in diagnostics; see
+    /// `diagnostic_span`.
+    pub(crate) diagnostic_span: Span,
+}
+
+impl<'tcx> fmt::Display for BorrowData<'tcx> {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self.kind {
+            mir::BorrowKind::Shared => "",
+            mir::BorrowKind::Unique => "uniq ",
+            mir::BorrowKind::Mut => "mut ",
+        };
+        let region = format!("{}", self.region);
+        let region = if region.len() > 0 { format!("{} ", region) } else { region };
+        write!(w, "&{}{}{:?}", region, kind, self.borrowed_place)
+    }
+}
+
+impl<'tcx> BorrowSet<'tcx> {
+    /// Gathers all the borrows in `mir`, in a single walk over it.
+    pub fn build<'a, 'gcx>(tcx: TyCtxt<'a, 'gcx, 'tcx>, mir: &'a Mir<'tcx>) -> Self {
+        let mut visitor = GatherBorrows {
+            tcx,
+            mir,
+            idx_vec: IndexVec::new(),
+            location_map: FxHashMap(),
+            assigned_map: FxHashMap(),
+            region_map: FxHashMap(),
+            local_map: FxHashMap(),
+            region_span_map: FxHashMap(),
+            place_uses: FxHashMap(),
+        };
+        visitor.visit_mir(mir);
+
+        // The uses of an assigned place may come before the borrow
+        // itself in visiting order (e.g. in a loop), so we can only
+        // match them up once the walk is over.
+        let mut activation_map = FxHashMap();
+        for (place, borrow_indexes) in &visitor.assigned_map {
+            if let Some(locations) = visitor.place_uses.get(place) {
+                for &location in locations {
+                    for &idx in borrow_indexes {
+                        insert(&mut activation_map, &location, idx);
+                    }
+                }
+            }
+        }
+
+        BorrowSet {
+            borrows: visitor.idx_vec,
+            location_map: visitor.location_map,
+            activation_map,
+            assigned_map: visitor.assigned_map,
+            region_map: visitor.region_map,
+            local_map: visitor.local_map,
+            region_span_map: visitor.region_span_map,
+        }
+    }
+
+    /// Returns the borrows that may be activated at `location`.
+    pub(crate) fn activations_at_location<'a>(&'a self, location: Location)
+                                              -> impl Iterator<Item = BorrowIndex> + 'a {
+        self.activation_map.get(&location).into_iter().flat_map(|set| set.iter().cloned())
+    }
+}
+
+struct GatherBorrows<'a, 'gcx: 'tcx, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &'a Mir<'tcx>,
+    idx_vec: IndexVec<BorrowIndex, BorrowData<'tcx>>,
+    location_map: FxHashMap<Location, BorrowIndex>,
+    assigned_map: FxHashMap<Place<'tcx>, FxHashSet<BorrowIndex>>,
+    region_map: FxHashMap<Region<'tcx>, FxHashSet<BorrowIndex>>,
+    local_map: FxHashMap<mir::Local, FxHashSet<BorrowIndex>>,
+    region_span_map: FxHashMap<RegionKind, Span>,
+
+    /// Every place that is used (in the sense of `is_potential_use`),
+    /// with the locations where it is used; later matched against
+    /// `assigned_map` to build the activation map.
+    place_uses: FxHashMap<Place<'tcx>, Vec<Location>>,
+}
+
+impl<'a, 'gcx, 'tcx> Visitor<'tcx> for GatherBorrows<'a, 'gcx, 'tcx> {
+    fn visit_assign(&mut self,
+                    block: mir::BasicBlock,
+                    assigned_place: &mir::Place<'tcx>,
+                    rvalue: &mir::Rvalue<'tcx>,
+                    location: mir::Location) {
+        fn root_local(mut p: &mir::Place<'_>) -> Option<mir::Local> {
+            loop { match p {
+                mir::Place::Projection(pi) => p = &pi.base,
+                mir::Place::Static(_) => return None,
+                mir::Place::Local(l) => return Some(*l)
+            }}
+        }
+
+        if let mir::Rvalue::Ref(region, kind, ref borrowed_place) = *rvalue {
+            // Borrows of unsafe places are not tracked, but we still
+            // walk the statement below to record its place uses.
+            if is_unsafe_place(self.tcx, self.mir, borrowed_place) {
+                return self.super_assign(block, assigned_place, rvalue, location);
+            }
+
+            let borrow = BorrowData {
+                reserve_location: location,
+                kind,
+                region,
+                borrowed_place: borrowed_place.clone(),
+                assigned_place: assigned_place.clone(),
+                diagnostic_span: diagnostic_span(self.tcx, self.mir.source_info(location).span),
+            };
+            let idx = self.idx_vec.push(borrow);
+            self.location_map.insert(location, idx);
+
+            insert(&mut self.assigned_map, assigned_place, idx);
+            insert(&mut self.region_map, &region, idx);
+            if let Some(local) = root_local(borrowed_place) {
+                insert(&mut self.local_map, &local, idx);
+            }
+        }
+
+        return self.super_assign(block, assigned_place, rvalue, location);
+    }
+
+    fn visit_rvalue(&mut self,
+                    rvalue: &mir::Rvalue<'tcx>,
+                    location: mir::Location) {
+        if let mir::Rvalue::Ref(region, kind, ref place) = *rvalue {
+            if is_unsafe_place(self.tcx, self.mir, place) {
+                return self.super_rvalue(rvalue, location);
+            }
+
+            // double-check that we already registered a BorrowData for this
+
+            let mut found_it = false;
+            for idx in &self.region_map[region] {
+                let bd = &self.idx_vec[*idx];
+                if bd.reserve_location == location &&
+                    bd.kind == kind &&
+                    bd.region == region &&
+                    bd.borrowed_place == *place
+                {
+                    found_it = true;
+                    break;
+                }
+            }
+            assert!(found_it, "Ref {:?} at {:?} missing BorrowData", rvalue, location);
+        }
+
+        return self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_statement(&mut self,
+                       block: mir::BasicBlock,
+                       statement: &mir::Statement<'tcx>,
+                       location: Location) {
+        if let mir::StatementKind::EndRegion(region_scope) = statement.kind {
+            self.region_span_map.insert(ReScope(region_scope), statement.source_info.span);
+        }
+        return self.super_statement(block, statement, location);
+    }
+
+    fn visit_place(&mut self,
+                   place: &mir::Place<'tcx>,
+                   context: PlaceContext<'tcx>,
+                   location: Location) {
+        if is_potential_use(context) {
+            self.place_uses.entry(place.clone()).or_insert(vec![]).push(location);
+        }
+
+        self.super_place(place, context, location);
+    }
+}
+
+/// Borrows created within the expansion of a macro from another
+/// crate (e.g. the borrow of the buffer in `write!(buf, ...)`) carry
+/// spans that point into the definition of that macro, which means
+/// nothing to the user. Unless `-Z external-macro-backtrace` is
+/// given, we walk out of such expansions to the outermost call site
+/// and point there instead.
+fn diagnostic_span(tcx: TyCtxt, span: Span) -> Span {
+    if tcx.sess.opts.debugging_opts.external_macro_backtrace {
+        return span;
+    }
+
+    let codemap = tcx.sess.codemap();
+    let mut span = span;
+    while let Some(info) = span.ctxt().outer().expn_info() {
+        let is_external_macro = match info.callee.format {
+            ExpnFormat::MacroBang(_) => match info.callee.span {
+                Some(def_site) => codemap.span_to_filename(def_site).is_macros(),
+                None => true,
+            },
+            _ => false,
+        };
+        if !is_external_macro {
+            break;
+        }
+        span = info.call_site;
+    }
+    span
+}
+
+fn insert<'a, K, V>(map: &'a mut FxHashMap<K, FxHashSet<V>>,
+                    k: &K,
+                    v: V)
+    where K: Clone+Eq+Hash, V: Eq+Hash
+{
+    map.entry(k.clone())
+        .or_insert(FxHashSet())
+        .insert(v);
+}
+
+/// return whether `context` should be considered a "use" of a
+/// place found in that context. "Uses" activate associated
+/// borrows (at least when such uses occur while the borrow also
+/// has a reservation at the time).
+pub(crate) fn is_potential_use(context: PlaceContext) -> bool {
+    match context {
+        // storage effects on an place do not activate it
+        PlaceContext::StorageLive | PlaceContext::StorageDead => false,
+
+        // validation effects do not activate an place
+        //
+        // FIXME: Should they? Is it just another read? Or can we
+        // guarantee it won't dereference the stored address? How
+        // "deep" does validation go?
+        PlaceContext::Validate => false,
+
+        // pure overwrites of an place do not activate it. (note
+        // PlaceContext::Call is solely about dest place)
+        PlaceContext::Store | PlaceContext::Call => false,
+
+        // reads of an place *do* activate it
+        PlaceContext::Move |
+        PlaceContext::Copy |
+        PlaceContext::Drop |
+        PlaceContext::Inspect |
+        PlaceContext::Borrow { .. } |
+        PlaceContext::Projection(..) => true,
+    }
+}
+
+pub(crate) fn is_unsafe_place<'a, 'gcx: 'tcx, 'tcx: 'a>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &'a Mir<'tcx>,
+    place: &mir::Place<'tcx>
+) -> bool {
+    use self::mir::Place::*;
+    use self::mir::ProjectionElem;
+
+    match *place {
+        Local(_) => false,
+        Static(ref static_) => tcx.is_static_mut(static_.def_id),
+        Projection(ref proj) => {
+            match proj.elem {
+                ProjectionElem::Field(..) |
+                ProjectionElem::Downcast(..) |
+                ProjectionElem::Subslice { .. } |
+                ProjectionElem::ConstantIndex { .. } |
+                ProjectionElem::Index(_) => {
+                    is_unsafe_place(tcx, mir, &proj.base)
+                }
+                ProjectionElem::Deref => {
+                    let ty = proj.base.ty(mir, tcx).to_ty(tcx);
+                    match ty.sty {
+                        ty::TyRawPtr(..) => true,
+                        _ => is_unsafe_place(tcx, mir, &proj.base),
+                    }
+                }
+            }
+        }
+    }
+}
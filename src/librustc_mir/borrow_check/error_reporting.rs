@@ -8,18 +8,27 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use syntax_pos::Span;
+use syntax::ast;
+use syntax_pos::{MultiSpan, Span};
+use rustc::hir;
+use rustc::hir::def::Def;
+use rustc::hir::intravisit::{self, NestedVisitorMap};
 use rustc::middle::region::ScopeTree;
-use rustc::mir::{BorrowKind, Field, Local, Location, Operand};
-use rustc::mir::{Place, ProjectionElem, Rvalue, Statement, StatementKind};
+use rustc::mir::{BorrowKind, FakeReadCause, Field, Local, Location, Operand};
+use rustc::mir::{Place, ProjectionElem, Rvalue, Statement, StatementKind, TerminatorKind};
+use rustc::mir::visit::{PlaceContext, Visitor};
 use rustc::ty::{self, RegionKind};
+use rustc::util::nodemap::FxHashSet;
 use rustc_data_structures::indexed_vec::Idx;
+use rustc_errors::DiagnosticBuilder;
 
+use std::iter;
 use std::rc::Rc;
 
 use super::{MirBorrowckCtxt, Context};
+use super::borrow_set::BorrowData;
 use super::{InitializationRequiringAction, PrefixSet};
-use dataflow::{ActiveBorrows, BorrowData, FlowAtLocation, MovingOutStatements};
+use dataflow::{ActiveBorrows, FlowAtLocation, MovingOutStatements};
 use dataflow::move_paths::MovePathIndex;
 use util::borrowck_errors::{BorrowckErrors, Origin};
 
@@ -69,16 +78,26 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                     desired_action.as_verb_in_past_tense()
                 ),
             );
+            // If only a part of `place` was moved out, like a field of
+            // one of its variants, say which.
+            let moved_place = &self.move_data.move_paths[mpi].place;
+            let partial_move = moved_place != place
+                && self.prefixes(moved_place, PrefixSet::All).any(|prefix| prefix == place);
+            let moved_msg = match self.describe_place(moved_place) {
+                Some(ref name) if partial_move => format!("`{}` was", name),
+                _ => "value".to_owned(),
+            };
             for moi in mois {
                 let move_msg = ""; //FIXME: add " (into closure)"
                 let move_span = self.mir.source_info(self.move_data.moves[*moi].source).span;
                 if span == move_span {
                     err.span_label(
                         span,
-                        format!("value moved{} here in previous iteration of loop", move_msg),
+                        format!("{} moved{} here in previous iteration of loop",
+                                moved_msg, move_msg),
                     );
                 } else {
-                    err.span_label(move_span, format!("value moved{} here", move_msg));
+                    err.span_label(move_span, format!("{} moved{} here", moved_msg, move_msg));
                 };
             }
             //FIXME: add note for closure
@@ -131,6 +150,34 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         err.emit();
     }
 
+    /// Like `report_use_while_mutably_borrowed`, but for a `FakeRead`
+    /// of the place, which the user never wrote: say what it stands
+    /// for.
+    pub(super) fn report_fake_read_while_mutably_borrowed(
+        &mut self,
+        _context: Context,
+        (place, span): (&Place<'tcx>, Span),
+        borrow: &BorrowData<'tcx>,
+        cause: FakeReadCause,
+    ) {
+        let mut err = self.tcx.cannot_use_when_mutably_borrowed(
+            span,
+            &self.describe_place(place).unwrap_or("_".to_owned()),
+            self.retrieve_borrow_span(borrow),
+            &self.describe_place(&borrow.borrowed_place).unwrap_or("_".to_owned()),
+            Origin::Mir,
+        );
+
+        let user = match cause {
+            FakeReadCause::ForMatch => "the `match`",
+            FakeReadCause::ForLet => "the `let`",
+            FakeReadCause::ForCapture => "the closure",
+        };
+        err.span_label(span, format!("value is immutably used by {} here", user));
+
+        err.emit();
+    }
+
     /// Finds the span of arguments of a closure (within `maybe_closure_span`) and its usage of
     /// the local assigned at `location`.
     /// This is done by searching in statements succeeding `location`
@@ -202,15 +249,24 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         gen_borrow_kind: BorrowKind,
         issued_borrow: &BorrowData,
         end_issued_loan_span: Option<Span>,
+        explicit_drop_span: Option<Span>,
+        last_use: Option<Location>,
+        question_mark_span: Option<Span>,
     ) {
-        let issued_span = self.retrieve_borrow_span(issued_borrow);
-
+        // When a closure captures the place, the borrow (or access) is
+        // the construction of the closure, and its span that of the
+        // whole closure expression.
+        let new_closure_expr_span = span;
         let new_closure_span = self.find_closure_span(span, context.loc);
         let span = new_closure_span.map(|(args, _)| args).unwrap_or(span);
-        let old_closure_span = self.find_closure_span(issued_span, issued_borrow.location);
+        let old_closure_expr_span = self.mir.source_info(issued_borrow.reserve_location).span;
+        let old_closure_span = self.find_closure_span(
+            old_closure_expr_span,
+            issued_borrow.reserve_location,
+        );
         let issued_span = old_closure_span
             .map(|(args, _)| args)
-            .unwrap_or(issued_span);
+            .unwrap_or(self.retrieve_borrow_span(issued_borrow));
 
         let desc_place = self.describe_place(place).unwrap_or("_".to_owned());
 
@@ -313,9 +369,121 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             );
         }
 
+        // The labels above land in the bodies of the closures, which
+        // makes it easy to miss that the borrow is held by the closure
+        // value itself, so say so.
+        if old_closure_span.is_some() {
+            err.span_note(
+                old_closure_expr_span,
+                "the borrow is held by the closure defined here",
+            );
+            if new_closure_span.is_some() {
+                err.span_note(
+                    new_closure_expr_span,
+                    "the access occurs in this other closure",
+                );
+            }
+        }
+
+        if let Some(drop_span) = explicit_drop_span {
+            err.span_note(
+                drop_span,
+                "the borrow is held until this explicit `drop` call; \
+                 moving the `drop` earlier would end the borrow sooner",
+            );
+        }
+
+        if let Some(question_mark_span) = question_mark_span {
+            err.span_label(
+                question_mark_span,
+                "the `?` operator returns early here, which requires the borrow to end",
+            );
+        }
+
+        if let (BorrowKind::Shared, BorrowKind::Mut, Some(last_use)) =
+            (gen_borrow_kind, issued_borrow.kind, last_use)
+        {
+            self.suggest_moving_last_use(context.loc, last_use, &mut err);
+        }
+
         err.emit();
     }
 
+    /// Given a conflict at `conflict` with a borrow whose last use is
+    /// the statement at `last_use` (later on the same straight-line
+    /// path), suggests moving that statement before the conflict, if
+    /// doing so provably does not change what it computes: it must
+    /// read no local that is written in between, write no local that
+    /// is read or written in between, and write no memory other than
+    /// its own locals.
+    fn suggest_moving_last_use(
+        &self,
+        conflict: Location,
+        last_use: Location,
+        err: &mut DiagnosticBuilder,
+    ) {
+        let mut between = LocalAccesses::default();
+        let mut callee = None;
+        let mut location = conflict;
+        while location != last_use {
+            let data = &self.mir[location.block];
+            if location.statement_index < data.statements.len() {
+                between.visit_statement(
+                    location.block,
+                    &data.statements[location.statement_index],
+                    location,
+                );
+                location = location.successor_within_block();
+            } else {
+                let terminator = data.terminator();
+                between.visit_terminator(location.block, terminator, location);
+                location = match terminator.kind {
+                    TerminatorKind::Goto { target } => target.start_location(),
+                    TerminatorKind::Call { ref func, destination: Some((_, target)), .. } => {
+                        if callee.is_none() {
+                            callee = Some((func.clone(), terminator.source_info.span));
+                        }
+                        target.start_location()
+                    }
+                    _ => return,
+                };
+            }
+        }
+
+        let statement = &self.mir[last_use.block].statements[last_use.statement_index];
+        let mut moved = LocalAccesses::default();
+        moved.visit_statement(last_use.block, statement, last_use);
+
+        // A write into memory (through a reference, or into a
+        // projection of a local) may alias anything read or written in
+        // between, and so may a read of memory that is written in
+        // between; moving such a statement could change what the
+        // program does.
+        let independent = !moved.writes_memory &&
+            !(moved.reads_memory && between.writes_memory) &&
+            moved.reads.is_disjoint(&between.writes) &&
+            moved.writes.is_disjoint(&between.reads) &&
+            moved.writes.is_disjoint(&between.writes);
+        if !independent {
+            return;
+        }
+
+        let (target, target_span) = match callee {
+            Some((Operand::Constant(ref constant), span)) => match constant.ty.sty {
+                ty::TyFnDef(def_id, _) => {
+                    (format!("the call to `{}`", self.tcx.item_path_str(def_id)), span)
+                }
+                _ => (String::from("the call"), span),
+            },
+            Some((_, span)) => (String::from("the call"), span),
+            None => (String::from("the conflicting borrow"), self.mir.source_info(conflict).span),
+        };
+
+        let mut spans = MultiSpan::from_span(statement.source_info.span);
+        spans.push_span_label(target_span, String::new());
+        err.span_help(spans, &format!("consider moving this statement before {}", target));
+    }
+
     pub(super) fn report_borrowed_value_does_not_live_long_enough(
         &mut self,
         _: Context,
@@ -348,7 +516,14 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 unreachable!("root_place is an unreachable???")
         };
 
-        let borrow_span = self.mir.source_info(borrow.location).span;
+        if let (&RegionKind::ReVar(_), &Place::Local(local)) = (borrow.region, root_place) {
+            let owner = borrows.borrow_owner(borrow);
+            if self.report_dropped_before_earlier_declared_borrower(local, owner, drop_span) {
+                return;
+            }
+        }
+
+        let borrow_span = self.retrieve_borrow_span(borrow);
         let proper_span = match *root_place {
             Place::Local(local) => self.mir.local_decls[local].source_info.span,
             _ => drop_span,
@@ -369,7 +544,8 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             (RegionKind::ReEmpty, Some(name)) |
             (RegionKind::ReVar(_), Some(name)) => {
                 self.report_unscoped_local_value_does_not_live_long_enough(
-                    name, &scope_tree, &borrow, drop_span, borrow_span, proper_span, end_span);
+                    name, &scope_tree, &borrow, drop_span, borrow_span, proper_span, end_span,
+                    borrows);
             },
             (RegionKind::ReEarlyBound(_), None) |
             (RegionKind::ReFree(_), None) |
@@ -377,7 +553,8 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             (RegionKind::ReEmpty, None) |
             (RegionKind::ReVar(_), None) => {
                 self.report_unscoped_temporary_value_does_not_live_long_enough(
-                    &scope_tree, &borrow, drop_span, borrow_span, proper_span, end_span);
+                    &scope_tree, &borrow, drop_span, borrow_span, proper_span, end_span,
+                    borrows);
             },
             (RegionKind::ReLateBound(_, _), _) |
             (RegionKind::ReSkolemized(_, _), _) |
@@ -387,6 +564,122 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         }
     }
 
+    /// Handles the common case where the local `borrowed` is dropped
+    /// while still borrowed only because the local holding the
+    /// borrow was declared before it, in the same block, and so is
+    /// dropped after it:
+    ///
+    ///     let mut a = Foo(None);
+    ///     let b = 22;
+    ///     a.0 = Some(&b);
+    ///
+    /// Here we suggest swapping the two declarations, if they are
+    /// adjacent and neither initializer refers to the other local.
+    /// `owner` is the local that holds the borrow (see
+    /// `ActiveBorrows::borrow_owner`); this only applies if it is the
+    /// earlier declared local. Returns false, without reporting
+    /// anything, if this is not such a case.
+    fn report_dropped_before_earlier_declared_borrower(
+        &mut self,
+        borrowed: Local,
+        owner: Option<Local>,
+        drop_span: Span,
+    ) -> bool {
+        let borrowed_decl = &self.mir.local_decls[borrowed];
+        let borrowed_name = match borrowed_decl.name {
+            Some(name) if borrowed_decl.is_user_variable => name,
+            _ => return false,
+        };
+
+        // The borrower must be a variable declared before `borrowed`
+        // whose destructor may still use the borrow.
+        let gcx = self.tcx.global_tcx();
+        let candidates: Vec<_> = self.mir.local_decls.iter_enumerated().filter(|&(local, decl)| {
+            local != borrowed && decl.is_user_variable && decl.name.is_some() &&
+                decl.source_info.span.lo() < borrowed_decl.source_info.span.lo() &&
+                decl.ty.has_erasable_regions() &&
+                gcx.lift(&self.tcx.erase_regions(&decl.ty))
+                    .map_or(false, |ty| ty.needs_drop(gcx, self.param_env))
+        }).map(|(local, _)| local).collect();
+
+        let body_id = self.tcx.hir.body_owned_by(self.node_id);
+        let mut finder = LetStatementFinder {
+            spans: candidates.iter()
+                .map(|&local| self.mir.local_decls[local].source_info.span)
+                .chain(iter::once(borrowed_decl.source_info.span))
+                .collect(),
+            found: vec![],
+        };
+        intravisit::walk_body(&mut finder, self.tcx.hir.body(body_id));
+
+        // Look for the block declaring `borrowed`, and check that
+        // exactly one candidate is declared earlier in it.
+        let borrowed_let = match finder.found.iter().find(|l| {
+            l.pat_span == borrowed_decl.source_info.span
+        }) {
+            Some(borrowed_let) => borrowed_let,
+            None => return false,
+        };
+        let mut borrower_lets = finder.found.iter().filter(|l| {
+            l.block == borrowed_let.block && l.index < borrowed_let.index
+        });
+        let borrower_let = match (borrower_lets.next(), borrower_lets.next()) {
+            (Some(borrower_let), None) => borrower_let,
+            _ => return false,
+        };
+        let borrower = match candidates.iter().find(|&&local| {
+            self.mir.local_decls[local].source_info.span == borrower_let.pat_span
+        }) {
+            Some(&borrower) => borrower,
+            None => return false,
+        };
+        if owner != Some(borrower) {
+            return false;
+        }
+        let borrower_name = self.mir.local_decls[borrower].name.unwrap();
+
+        let mut err = self.tcx.path_dropped_before_earlier_declared_borrower(
+            drop_span,
+            &format!("`{}`", borrowed_name),
+            &format!("`{}`", borrower_name),
+            Origin::Mir,
+        );
+        err.span_label(
+            borrower_let.pat_span,
+            format!("`{}` is declared here, before `{}`", borrower_name, borrowed_name),
+        );
+        err.span_label(
+            borrowed_let.pat_span,
+            format!("`{}` is declared here", borrowed_name),
+        );
+        err.span_label(
+            drop_span,
+            format!("`{}` dropped here while still borrowed by `{}`", borrowed_name, borrower_name),
+        );
+
+        let adjacent = borrowed_let.index == borrower_let.index + 1;
+        let independent = !borrower_let.init_mentions.contains(&borrowed_let.pat_id) &&
+            !borrowed_let.init_mentions.contains(&borrower_let.pat_id);
+        if adjacent && independent {
+            let codemap = self.tcx.sess.codemap();
+            let between = borrower_let.stmt_span.between(borrowed_let.stmt_span);
+            if let (Ok(first), Ok(middle), Ok(second)) = (
+                codemap.span_to_snippet(borrower_let.stmt_span),
+                codemap.span_to_snippet(between),
+                codemap.span_to_snippet(borrowed_let.stmt_span),
+            ) {
+                err.span_suggestion(
+                    borrower_let.stmt_span.to(borrowed_let.stmt_span),
+                    "reorder the declarations",
+                    format!("{}{}{}", second, middle, first),
+                );
+            }
+        }
+
+        err.emit();
+        true
+    }
+
     fn report_scoped_local_value_does_not_live_long_enough(
         &mut self, name: &String, _scope_tree: &Rc<ScopeTree>, _borrow: &BorrowData<'tcx>,
         drop_span: Span, borrow_span: Span, _proper_span: Span, end_span: Option<Span>
@@ -420,7 +713,8 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
 
     fn report_unscoped_local_value_does_not_live_long_enough(
         &mut self, name: &String, scope_tree: &Rc<ScopeTree>, borrow: &BorrowData<'tcx>,
-        drop_span: Span, borrow_span: Span, _proper_span: Span, _end_span: Option<Span>
+        drop_span: Span, borrow_span: Span, _proper_span: Span, _end_span: Option<Span>,
+        borrows: &ActiveBorrows<'cx, 'gcx, 'tcx>
     ) {
         let mut err = self.tcx.path_does_not_live_long_enough(borrow_span,
                                                               &format!("`{}`", name),
@@ -430,12 +724,14 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         self.tcx.note_and_explain_region(scope_tree, &mut err,
                                          "borrowed value must be valid for ",
                                          borrow.region, "...");
+        borrows.explain_universal_liveness(borrow, &mut err);
         err.emit();
     }
 
     fn report_unscoped_temporary_value_does_not_live_long_enough(
         &mut self, scope_tree: &Rc<ScopeTree>, borrow: &BorrowData<'tcx>,
-        drop_span: Span, _borrow_span: Span, proper_span: Span, _end_span: Option<Span>
+        drop_span: Span, _borrow_span: Span, proper_span: Span, _end_span: Option<Span>,
+        borrows: &ActiveBorrows<'cx, 'gcx, 'tcx>
     ) {
         let mut err = self.tcx.path_does_not_live_long_enough(proper_span,
                                                               "borrowed value",
@@ -445,6 +741,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         self.tcx.note_and_explain_region(scope_tree, &mut err,
                                          "borrowed value must be valid for ",
                                          borrow.region, "...");
+        borrows.explain_universal_liveness(borrow, &mut err);
         err.emit();
     }
 
@@ -651,7 +948,153 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
     }
 
     // Retrieve span of given borrow from the current MIR representation
+    /// Returns the span to point at for `borrow` in diagnostics, which
+    /// is outside of any external macro it was created in.
     fn retrieve_borrow_span(&self, borrow: &BorrowData) -> Span {
-        self.mir.source_info(borrow.location).span
+        borrow.diagnostic_span
+    }
+}
+
+/// The locals read and written by some MIR statements, and whether
+/// they read or write memory that is not a local of their own. Writing
+/// through a reference counts as reading the reference and writing
+/// memory; writing into a projection of a local (e.g., a field) counts
+/// as writing both the local and memory, since the projection may
+/// overlap a borrow held elsewhere. Borrowing a local mutably counts
+/// as writing it.
+#[derive(Default)]
+struct LocalAccesses {
+    reads: FxHashSet<Local>,
+    writes: FxHashSet<Local>,
+    reads_memory: bool,
+    writes_memory: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for LocalAccesses {
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext<'tcx>, _: Location) {
+        let is_write = match context {
+            PlaceContext::Store |
+            PlaceContext::Call |
+            PlaceContext::Borrow { kind: BorrowKind::Mut, .. } |
+            PlaceContext::Borrow { kind: BorrowKind::Unique, .. } => true,
+            _ => false,
+        };
+        let mut place = place;
+        let mut through_deref = false;
+        let mut projected = false;
+        loop {
+            match *place {
+                Place::Local(local) => {
+                    match context {
+                        PlaceContext::StorageLive | PlaceContext::StorageDead => {}
+                        _ if is_write && !through_deref => {
+                            self.writes.insert(local);
+                        }
+                        _ => {
+                            self.reads.insert(local);
+                        }
+                    }
+                    if through_deref {
+                        if is_write {
+                            self.writes_memory = true;
+                        } else {
+                            self.reads_memory = true;
+                        }
+                    } else if is_write && projected {
+                        self.writes_memory = true;
+                    }
+                    return;
+                }
+                Place::Static(_) => {
+                    if is_write {
+                        self.writes_memory = true;
+                    } else {
+                        self.reads_memory = true;
+                    }
+                    return;
+                }
+                Place::Projection(ref proj) => {
+                    projected = true;
+                    match proj.elem {
+                        ProjectionElem::Deref => through_deref = true,
+                        ProjectionElem::Index(local) => {
+                            self.reads.insert(local);
+                        }
+                        _ => {}
+                    }
+                    place = &proj.base;
+                }
+            }
+        }
+    }
+}
+
+/// A `let` statement declaring one of the variables we are looking for.
+struct LetStatement {
+    /// The block containing the statement, and its index there.
+    block: ast::NodeId,
+    index: usize,
+    stmt_span: Span,
+    /// The binding pattern; its span is also the span of the
+    /// corresponding MIR local.
+    pat_id: ast::NodeId,
+    pat_span: Span,
+    /// The local variables mentioned in the initializer.
+    init_mentions: Vec<ast::NodeId>,
+}
+
+/// Finds the `let` statements that declare the variables whose
+/// binding patterns have the given spans.
+struct LetStatementFinder {
+    spans: Vec<Span>,
+    found: Vec<LetStatement>,
+}
+
+impl<'v> intravisit::Visitor<'v> for LetStatementFinder {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'v> {
+        NestedVisitorMap::None
+    }
+
+    fn visit_block(&mut self, block: &'v hir::Block) {
+        for (index, stmt) in block.stmts.iter().enumerate() {
+            if let hir::StmtDecl(ref decl, _) = stmt.node {
+                if let hir::DeclLocal(ref local) = decl.node {
+                    if self.spans.contains(&local.pat.span) {
+                        let mut init_mentions = vec![];
+                        if let Some(ref init) = local.init {
+                            let mut visitor = LocalMentions { ids: &mut init_mentions };
+                            intravisit::walk_expr(&mut visitor, init);
+                        }
+                        self.found.push(LetStatement {
+                            block: block.id,
+                            index,
+                            stmt_span: stmt.span,
+                            pat_id: local.pat.id,
+                            pat_span: local.pat.span,
+                            init_mentions,
+                        });
+                    }
+                }
+            }
+        }
+        intravisit::walk_block(self, block);
+    }
+}
+
+/// Collects the local variables mentioned in an expression.
+struct LocalMentions<'a> {
+    ids: &'a mut Vec<ast::NodeId>,
+}
+
+impl<'a, 'v> intravisit::Visitor<'v> for LocalMentions<'a> {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'v> {
+        NestedVisitorMap::None
+    }
+
+    fn visit_path(&mut self, path: &'v hir::Path, _: ast::NodeId) {
+        if let Def::Local(node_id) = path.def {
+            self.ids.push(node_id);
+        }
+        intravisit::walk_path(self, path);
     }
 }
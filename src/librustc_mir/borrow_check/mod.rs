@@ -19,7 +19,7 @@ use rustc::ty::maps::Providers;
 use rustc::mir::{AssertMessage, BasicBlock, BorrowKind, Local, Location, Place};
 use rustc::mir::{Mir, Mutability, Operand, Projection, ProjectionElem, Rvalue};
 use rustc::mir::{Field, Statement, StatementKind, Terminator, TerminatorKind};
-use rustc::mir::ClosureRegionRequirements;
+use rustc::mir::{BorrowCheckResult, FakeReadCause};
 
 use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::indexed_set::{IdxSetBuf};
@@ -34,22 +34,28 @@ use dataflow::{DataflowAnalysis, DataflowResultsConsumer};
 use dataflow::{FlowAtLocation, FlowsAtLocation};
 use dataflow::{MaybeInitializedLvals, MaybeUninitializedLvals};
 use dataflow::{EverInitializedLvals, MovingOutStatements};
-use dataflow::{Borrows, BorrowData, ReserveOrActivateIndex};
+use dataflow::{Borrows, ReserveOrActivateIndex};
 use dataflow::{ActiveBorrows, Reservations};
 use dataflow::indexes::{BorrowIndex};
-use dataflow::move_paths::{IllegalMoveOriginKind, MoveError};
+use dataflow::has_rustc_mir_with;
 use dataflow::move_paths::{HasMoveData, LookupResult, MoveData, MovePathIndex};
+use transform::rustc_peek;
 use util::borrowck_errors::{BorrowckErrors, Origin};
 
 use std::iter;
+use std::rc::Rc;
 
+use self::borrow_set::{BorrowData, BorrowSet};
 use self::flows::Flows;
 use self::prefixes::PrefixSet;
 use self::MutateMode::{JustWrite, WriteAndRead};
 
+pub(crate) mod borrow_set;
 mod error_reporting;
 mod flows;
+mod move_errors;
 mod prefixes;
+mod signature_borrows;
 
 use std::borrow::Cow;
 
@@ -58,14 +64,30 @@ pub(crate) mod nll;
 pub fn provide(providers: &mut Providers) {
     *providers = Providers {
         mir_borrowck,
+        universal_regions_summary: nll::universal_regions_summary,
         ..*providers
     };
 }
 
+/// Borrow-checks every body of the local crate. The region errors that
+/// point into macro expansions are buffered meanwhile, so that those
+/// repeated by identical closures (as stamped out by a macro) are
+/// reported only once, and so are the delayed bugs, so that they can
+/// be reported together if no error is.
+pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    nll::error_buffer::buffer_region_errors(tcx.sess);
+    nll::delayed_bugs::buffer_delayed_bugs(tcx.sess);
+    for def_id in tcx.body_owners() {
+        tcx.mir_borrowck(def_id);
+    }
+    nll::error_buffer::flush_region_errors(tcx.sess);
+    nll::delayed_bugs::flush_delayed_bugs(tcx.sess);
+}
+
 fn mir_borrowck<'a, 'tcx>(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     def_id: DefId,
-) -> Option<ClosureRegionRequirements> {
+) -> BorrowCheckResult<'tcx> {
     let input_mir = tcx.mir_validated(def_id);
     debug!("run query mir_borrowck: {}", tcx.item_path_str(def_id));
 
@@ -73,26 +95,117 @@ fn mir_borrowck<'a, 'tcx>(
         !tcx.has_attr(def_id, "rustc_mir_borrowck") && !tcx.sess.opts.borrowck_mode.use_mir()
             && !tcx.sess.opts.debugging_opts.nll
     } {
-        return None;
+        return BorrowCheckResult {
+            closure_requirements: None,
+            universal_regions_summary: None,
+        };
+    }
+
+    if tcx.sess.opts.debugging_opts.nll_skip_unmatched && !nll::matches_nll_only(tcx, def_id) {
+        debug!("mir_borrowck: skipping {:?}, which -Z nll-only does not match", def_id);
+        return BorrowCheckResult {
+            closure_requirements: None,
+            universal_regions_summary: None,
+        };
+    }
+
+    if tcx.has_attr(def_id, "rustc_nll_delay_bug") {
+        nll::delayed_bugs::delay_nll_bug(
+            tcx.sess,
+            "forced",
+            tcx.def_span(def_id),
+            "delayed by `#[rustc_nll_delay_bug]`",
+        );
     }
 
-    let opt_closure_req = tcx.infer_ctxt().enter(|infcx| {
+    let result = tcx.infer_ctxt().enter(|infcx| {
         let input_mir: &Mir = &input_mir.borrow();
         do_mir_borrowck(&infcx, input_mir, def_id)
     });
     debug!("mir_borrowck done");
 
-    opt_closure_req
+    result
+}
+
+/// Runs the NLL region inference on the MIR of the shim `instance`,
+/// for `-Z nll-shims`. Shims are only built during translation, long
+/// after the crate has been borrow-checked, and they have no HIR, so
+/// the rest of the borrow checker, which reports its errors in terms
+/// of the HIR, does not run on them.
+pub fn check_shim<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    instance: ty::InstanceDef<'tcx>,
+    input_mir: &Mir<'tcx>,
+) {
+    debug!("check_shim({:?})", instance);
+    tcx.infer_ctxt().enter(|infcx| do_check_shim(&infcx, instance, input_mir));
+}
+
+fn do_check_shim<'a, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    instance: ty::InstanceDef<'gcx>,
+    input_mir: &Mir<'gcx>,
+) {
+    let tcx = infcx.tcx;
+    let def_id = instance.def_id();
+    let param_env = tcx.param_env(def_id);
+
+    let mut mir: Mir<'tcx> = input_mir.clone();
+    let free_regions = nll::replace_regions_in_shim(infcx, instance, param_env, &mut mir);
+    let mir = &mir;
+
+    // We only want the regions: should the shim move out of some
+    // place that the borrow checker would not allow, that is for
+    // whoever built the shim to worry about.
+    let move_data = match MoveData::gather_moves(mir, tcx) {
+        Ok(move_data) | Err((move_data, _)) => move_data,
+    };
+    let mdpe = MoveDataParamEnv {
+        move_data: move_data,
+        param_env: param_env,
+    };
+
+    let dead_unwinds = IdxSetBuf::new_empty(mir.basic_blocks().len());
+    let mut flow_inits = FlowAtLocation::new(do_dataflow(
+        tcx,
+        mir,
+        ast::DUMMY_NODE_ID,
+        &[],
+        &dead_unwinds,
+        MaybeInitializedLvals::new(tcx, mir, &mdpe),
+        |bd, i| DebugFormatted::new(&bd.move_data().move_paths[i]),
+    ));
+
+    nll::compute_regions(
+        infcx,
+        def_id,
+        free_regions,
+        mir,
+        param_env,
+        &mut flow_inits,
+        &mdpe.move_data,
+        false,
+    );
 }
 
 fn do_mir_borrowck<'a, 'gcx, 'tcx>(
     infcx: &InferCtxt<'a, 'gcx, 'tcx>,
     input_mir: &Mir<'gcx>,
     def_id: DefId,
-) -> Option<ClosureRegionRequirements> {
+) -> BorrowCheckResult<'gcx> {
     let tcx = infcx.tcx;
     let attributes = tcx.get_attrs(def_id);
     let param_env = tcx.param_env(def_id);
+
+    // The constructors of tuple structs and tuple variants have
+    // synthesized MIR, but neither a body nor typeck tables.
+    let is_ctor = match tcx.def_key(def_id).disambiguated_data.data {
+        DefPathData::StructCtor |
+        DefPathData::EnumVariant(_) => true,
+        _ => false,
+    };
+    let tables = if is_ctor { None } else { Some(tcx.typeck_tables_of(def_id)) };
+    let dumps_enabled = nll::matches_nll_only(tcx, def_id);
     let id = tcx.hir
         .as_local_node_id(def_id)
         .expect("do_mir_borrowck: non-local DefId");
@@ -107,39 +220,25 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
         let mir = &mut mir;
 
         // Replace all regions with fresh inference variables.
-        Some(nll::replace_regions_in_mir(infcx, def_id, param_env, mir))
+        Some(nll::replace_regions_in_mir(infcx, def_id, param_env, tables, dumps_enabled, mir))
     };
     let mir = &mir;
 
+    // If the body failed to type-check, its MIR is just a stand-in
+    // (possibly without a single reachable block), and any errors we
+    // would report would be spurious.
+    if tables.map_or(false, |tables| tables.tainted_by_errors) {
+        debug!("do_mir_borrowck: {:?} is tainted by errors", def_id);
+        return BorrowCheckResult {
+            closure_requirements: None,
+            universal_regions_summary: None,
+        };
+    }
+
     let move_data: MoveData<'tcx> = match MoveData::gather_moves(mir, tcx) {
         Ok(move_data) => move_data,
         Err((move_data, move_errors)) => {
-            for move_error in move_errors {
-                let (span, kind): (Span, IllegalMoveOriginKind) = match move_error {
-                    MoveError::UnionMove { .. } => {
-                        unimplemented!("dont know how to report union move errors yet.")
-                    }
-                    MoveError::IllegalMove {
-                        cannot_move_out_of: o,
-                    } => (o.span, o.kind),
-                };
-                let origin = Origin::Mir;
-                let mut err = match kind {
-                    IllegalMoveOriginKind::Static => {
-                        tcx.cannot_move_out_of(span, "static item", origin)
-                    }
-                    IllegalMoveOriginKind::BorrowedContent => {
-                        tcx.cannot_move_out_of(span, "borrowed content", origin)
-                    }
-                    IllegalMoveOriginKind::InteriorOfTypeWithDestructor { container_ty: ty } => {
-                        tcx.cannot_move_out_of_interior_of_drop(span, ty, origin)
-                    }
-                    IllegalMoveOriginKind::InteriorOfSliceOrArray { ty, is_index } => {
-                        tcx.cannot_move_out_of_interior_noncopy(span, ty, is_index, origin)
-                    }
-                };
-                err.emit();
-            }
+            move_errors::report_move_errors(infcx, mir, param_env, move_errors);
             move_data
         }
     };
@@ -148,11 +247,7 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
         move_data: move_data,
         param_env: param_env,
     };
-    let body_id = match tcx.def_key(def_id).disambiguated_data.data {
-        DefPathData::StructCtor |
-        DefPathData::EnumVariant(_) => None,
-        _ => Some(tcx.hir.body_owned_by(id))
-    };
+    let body_id = if is_ctor { None } else { Some(tcx.hir.body_owned_by(id)) };
 
     let dead_unwinds = IdxSetBuf::new_empty(mir.basic_blocks().len());
     let mut flow_inits = FlowAtLocation::new(do_dataflow(
@@ -193,8 +288,8 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
     ));
 
     // If we are in non-lexical mode, compute the non-lexical lifetimes.
-    let (opt_regioncx, opt_closure_req) = if let Some(free_regions) = free_regions {
-        let (regioncx, opt_closure_req) = nll::compute_regions(
+    let (opt_regioncx, opt_closure_req, opt_summary) = if let Some(free_regions) = free_regions {
+        let (regioncx, opt_closure_req, summary) = nll::compute_regions(
             infcx,
             def_id,
             free_regions,
@@ -202,20 +297,24 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
             param_env,
             &mut flow_inits,
             &mdpe.move_data,
+            dumps_enabled,
         );
-        (Some(regioncx), opt_closure_req)
+        (Some(regioncx), opt_closure_req, Some(Rc::new(summary)))
     } else {
         assert!(!tcx.sess.opts.debugging_opts.nll);
-        (None, None)
+        (None, None, None)
     };
     let flow_inits = flow_inits; // remove mut
 
+    let borrow_set = BorrowSet::build(tcx, mir);
+
     let mut mbcx = MirBorrowckCtxt {
         tcx: tcx,
         mir: mir,
         node_id: id,
         move_data: &mdpe.move_data,
         param_env: param_env,
+        borrow_set: &borrow_set,
         locals_are_invalidated_at_exit: match tcx.hir.body_owner_kind(id) {
             hir::BodyOwnerKind::Const |
             hir::BodyOwnerKind::Static(_) => false,
@@ -224,9 +323,16 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
         storage_dead_or_drop_error_reported_l: FxHashSet(),
         storage_dead_or_drop_error_reported_s: FxHashSet(),
         reservation_error_reported: FxHashSet(),
+        fake_read_error_reported: FxHashSet(),
     };
 
-    let borrows = Borrows::new(tcx, mir, opt_regioncx, def_id, body_id);
+    if let Some(ref regioncx) = opt_regioncx {
+        if dumps_enabled && tcx.sess.opts.debugging_opts.nll_signature_borrows_report {
+            mbcx.report_signature_borrows(regioncx, def_id);
+        }
+    }
+
+    let borrows = Borrows::new(tcx, mir, opt_regioncx, def_id, body_id, &borrow_set);
     let flow_reservations = do_dataflow(
         tcx,
         mir,
@@ -242,7 +348,7 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
             // it is okay for the kill-sets to hold activation bits.
             DebugFormatted::new(&(i.kind(), rs.location(i)))
         });
-    let flow_active_borrows = {
+    let mut flow_active_borrows = {
         let reservations_on_entry = flow_reservations.0.sets.entry_set_state();
         let reservations = flow_reservations.0.operator;
         let a = DataflowAnalysis::new_with_entry_sets(mir,
@@ -256,6 +362,10 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
         FlowAtLocation::new(results)
     };
 
+    if has_rustc_mir_with(&attributes, "rustc_peek_borrows").is_some() {
+        rustc_peek::sanity_check_borrows_via_rustc_peek(tcx, mir, &mut flow_active_borrows);
+    }
+
     let mut state = Flows::new(
         flow_active_borrows,
         flow_inits,
@@ -266,7 +376,10 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
 
     mbcx.analyze_results(&mut state); // entry point for DataflowResultsConsumer
 
-    opt_closure_req
+    BorrowCheckResult {
+        closure_requirements: opt_closure_req,
+        universal_regions_summary: opt_summary,
+    }
 }
 
 #[allow(dead_code)]
@@ -276,6 +389,8 @@ pub struct MirBorrowckCtxt<'cx, 'gcx: 'tcx, 'tcx: 'cx> {
     node_id: ast::NodeId,
     move_data: &'cx MoveData<'tcx>,
     param_env: ParamEnv<'gcx>,
+    /// The borrows in `mir`, shared with the `Borrows` dataflow.
+    borrow_set: &'cx BorrowSet<'tcx>,
     /// This keeps track of whether local variables are free-ed when the function
     /// exits even without a `StorageDead`, which appears to be the case for
     /// constants.
@@ -297,6 +412,10 @@ pub struct MirBorrowckCtxt<'cx, 'gcx: 'tcx, 'tcx: 'cx> {
     /// but it is currently inconvenient to track down the BorrowIndex
     /// at the time we detect and report a reservation error.
     reservation_error_reported: FxHashSet<Place<'tcx>>,
+    /// This field keeps track of the places and spans of `FakeRead`s
+    /// that were reported as conflicting, so that the borrow of the
+    /// same place by the closure being captured is not reported again.
+    fake_read_error_reported: FxHashSet<(Place<'tcx>, Span)>,
 }
 
 // Check that:
@@ -402,6 +521,21 @@ impl<'cx, 'gcx, 'tcx> DataflowResultsConsumer<'cx, 'tcx> for MirBorrowckCtxt<'cx
                     self.consume_operand(context, (input, span), flow_state);
                 }
             }
+            StatementKind::FakeRead(cause, ref place) => {
+                // A fake read only checks that nobody else has
+                // mutable access to the place right now; it neither
+                // moves nor reads through it.
+                let errors = self.access_place(
+                    ContextKind::FakeRead.new(location),
+                    (place, span),
+                    (Shallow(None), Read(ReadKind::FakeRead(cause))),
+                    LocalMutationIsAllowed::No,
+                    flow_state,
+                );
+                if errors.conflict_error {
+                    self.fake_read_error_reported.insert((place.clone(), span));
+                }
+            }
             StatementKind::EndRegion(ref _rgn) => {
                 // ignored when consuming results (update to
                 // flow_state already handled).
@@ -541,8 +675,7 @@ impl<'cx, 'gcx, 'tcx> DataflowResultsConsumer<'cx, 'tcx> for MirBorrowckCtxt<'cx
                 // Often, the storage will already have been killed by an explicit
                 // StorageDead, but we don't always emit those (notably on unwind paths),
                 // so this "extra check" serves as a kind of backup.
-                let domain = flow_state.borrows.operator();
-                let data = domain.borrows();
+                let data = &self.borrow_set.borrows;
                 flow_state.borrows.with_elems_outgoing(|borrows| {
                     for i in borrows {
                         let borrow = &data[i.borrow_index()];
@@ -622,6 +755,7 @@ enum ReadOrWrite {
 enum ReadKind {
     Borrow(BorrowKind),
     Copy,
+    FakeRead(FakeReadCause),
 }
 
 /// Kind of write access to a value
@@ -653,7 +787,6 @@ enum LocalMutationIsAllowed {
 
 struct AccessErrorsReported {
     mutability_error: bool,
-    #[allow(dead_code)]
     conflict_error: bool
 }
 
@@ -710,6 +843,18 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             }
         }
 
+        match rw {
+            Reservation(WriteKind::MutableBorrow(BorrowKind::Unique)) |
+            Write(WriteKind::MutableBorrow(BorrowKind::Unique)) => {
+                if self.fake_read_error_reported.contains(&(place_span.0.clone(), place_span.1)) {
+                    debug!("skipping access_place for unique borrow of place: {:?} \
+                            already reported by its `FakeRead`", place_span.0);
+                    return AccessErrorsReported { mutability_error: false, conflict_error: true };
+                }
+            }
+            _ => {}
+        }
+
         let mutability_error =
             self.check_access_permissions(place_span, rw, is_local_mutation_allowed);
         let conflict_error =
@@ -763,11 +908,32 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                             error_reported = true;
                             this.report_use_while_mutably_borrowed(context, place_span, borrow)
                         }
+                        ReadKind::FakeRead(cause) => {
+                            error_reported = true;
+                            this.report_fake_read_while_mutably_borrowed(
+                                context,
+                                place_span,
+                                borrow,
+                                cause,
+                            )
+                        }
                         ReadKind::Borrow(bk) => {
                             let end_issued_loan_span = flow_state
                                 .borrows
                                 .operator()
                                 .opt_region_end_span(&borrow.region);
+                            let explicit_drop_span = flow_state
+                                .borrows
+                                .operator()
+                                .opt_explicit_drop_span(&borrow);
+                            let last_use = flow_state
+                                .borrows
+                                .operator()
+                                .opt_last_use_after(&borrow, context.loc);
+                            let question_mark_span = flow_state
+                                .borrows
+                                .operator()
+                                .opt_question_mark_return_span(&borrow);
                             error_reported = true;
                             this.report_conflicting_borrow(
                                 context,
@@ -775,6 +941,9 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                                 bk,
                                 &borrow,
                                 end_issued_loan_span,
+                                explicit_drop_span,
+                                last_use,
+                                question_mark_span,
                             )
                         }
                     }
@@ -805,6 +974,18 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                                 .borrows
                                 .operator()
                                 .opt_region_end_span(&borrow.region);
+                            let explicit_drop_span = flow_state
+                                .borrows
+                                .operator()
+                                .opt_explicit_drop_span(&borrow);
+                            let last_use = flow_state
+                                .borrows
+                                .operator()
+                                .opt_last_use_after(&borrow, context.loc);
+                            let question_mark_span = flow_state
+                                .borrows
+                                .operator()
+                                .opt_question_mark_return_span(&borrow);
 
                             error_reported = true;
                             this.report_conflicting_borrow(
@@ -813,6 +994,9 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                                 bk,
                                 &borrow,
                                 end_issued_loan_span,
+                                explicit_drop_span,
+                                last_use,
+                                question_mark_span,
                             )
                         }
                         WriteKind::StorageDeadOrDrop => {
@@ -937,12 +1121,25 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                     LocalMutationIsAllowed::No,
                     flow_state,
                 );
-                self.check_if_path_is_moved(
-                    context,
-                    InitializationRequiringAction::Use,
-                    (place, span),
-                    flow_state,
-                );
+
+                // Moving out of the fields of a variant leaves the
+                // discriminant of the enum in place, so reading it
+                // only requires the enum itself to be initialized.
+                if let Rvalue::Discriminant(..) = *rvalue {
+                    self.check_if_full_path_is_moved(
+                        context,
+                        InitializationRequiringAction::Use,
+                        (place, span),
+                        flow_state,
+                    );
+                } else {
+                    self.check_if_path_is_moved(
+                        context,
+                        InitializationRequiringAction::Use,
+                        (place, span),
+                        flow_state,
+                    );
+                }
             }
 
             Rvalue::BinaryOp(_bin_op, ref operand1, ref operand2) |
@@ -1092,8 +1289,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         // Two-phase borrow support: For each activation that is newly
         // generated at this statement, check if it interferes with
         // another borrow.
-        let domain = flow_state.borrows.operator();
-        let data = domain.borrows();
+        let data = &self.borrow_set.borrows;
         flow_state.borrows.each_gen_bit(|gen| {
             if gen.is_activation()
             {
@@ -1147,6 +1343,8 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         }
     }
 
+    /// Checks that `place` and all of its shallow suffixes are
+    /// initialized, i.e., that none of it has been moved out.
     fn check_if_path_is_moved(
         &mut self,
         context: Context,
@@ -1154,6 +1352,50 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         place_span: (&Place<'tcx>, Span),
         flow_state: &Flows<'cx, 'gcx, 'tcx>,
     ) {
+        if self.check_if_full_path_is_moved(context, desired_action, place_span, flow_state) {
+            return; // don't bother finding other problems.
+        }
+
+        // FIXME: analogous code in check_loans first maps `place` to
+        // its base_path ... but is that what we want here?
+        let place = self.base_path(place_span.0);
+
+        let maybe_uninits = &flow_state.uninits;
+        let curr_move_outs = &flow_state.move_outs;
+
+        // A move of any shallow suffix of `place` also interferes
+        // with an attempt to use `place`. This is scenario 3 of
+        // `check_if_full_path_is_moved`.
+        //
+        // (Distinct from handling of scenarios 1+2+4 there because
+        // `place` does not interfere with suffixes of its prefixes,
+        // e.g. `a.b.c` does not interfere with `a.b.d`)
+
+        debug!("check_if_path_is_moved part2 place: {:?}", place);
+        if let Some(mpi) = self.move_path_for_place(place) {
+            if let Some(child_mpi) = maybe_uninits.has_any_child_of(mpi) {
+                self.report_use_of_moved_or_uninitialized(
+                    context,
+                    desired_action,
+                    place_span,
+                    child_mpi,
+                    curr_move_outs,
+                );
+            }
+        }
+    }
+
+    /// Checks that `place` itself is initialized, without looking at
+    /// its suffixes: a use of `a` that only needs `a` to be there,
+    /// like reading the discriminant of an enum, is fine even after a
+    /// move of `(a as Some).0`. Returns true if an error was reported.
+    fn check_if_full_path_is_moved(
+        &mut self,
+        context: Context,
+        desired_action: InitializationRequiringAction,
+        place_span: (&Place<'tcx>, Span),
+        flow_state: &Flows<'cx, 'gcx, 'tcx>,
+    ) -> bool {
         // FIXME: analogous code in check_loans first maps `place` to
         // its base_path ... but is that what we want here?
         let place = self.base_path(place_span.0);
@@ -1197,7 +1439,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         //
         // This code covers scenarios 1, 2, and 4.
 
-        debug!("check_if_path_is_moved part1 place: {:?}", place);
+        debug!("check_if_full_path_is_moved place: {:?}", place);
         match self.move_path_closest_to(place) {
             Ok(mpi) => {
                 if maybe_uninits.contains(&mpi) {
@@ -1208,7 +1450,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                         mpi,
                         curr_move_outs,
                     );
-                    return; // don't bother finding other problems.
+                    return true;
                 }
             }
             Err(NoMovePathFound::ReachedStatic) => {
@@ -1221,26 +1463,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
               // to do such a query based on partial-init feature-gate.)
         }
 
-        // A move of any shallow suffix of `place` also interferes
-        // with an attempt to use `place`. This is scenario 3 above.
-        //
-        // (Distinct from handling of scenarios 1+2+4 above because
-        // `place` does not interfere with suffixes of its prefixes,
-        // e.g. `a.b.c` does not interfere with `a.b.d`)
-
-        debug!("check_if_path_is_moved part2 place: {:?}", place);
-        if let Some(mpi) = self.move_path_for_place(place) {
-            if let Some(child_mpi) = maybe_uninits.has_any_child_of(mpi) {
-                self.report_use_of_moved_or_uninitialized(
-                    context,
-                    desired_action,
-                    place_span,
-                    child_mpi,
-                    curr_move_outs,
-                );
-                return; // don't bother finding other problems.
-            }
-        }
+        false
     }
 
     /// Currently MoveData does not store entries for all places in
@@ -1422,7 +1645,9 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             Write(WriteKind::StorageDeadOrDrop) |
             Write(WriteKind::MutableBorrow(BorrowKind::Shared)) => {
                 if let Err(_place_err) = self.is_mutable(place, is_local_mutation_allowed) {
-                    self.tcx.sess.delay_span_bug(
+                    nll::delayed_bugs::delay_nll_bug(
+                        self.tcx.sess,
+                        "mutability",
                         span,
                         &format!(
                             "Accessing `{:?}` with the kind `{:?}` shouldn't be possible",
@@ -1438,7 +1663,8 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             Read(ReadKind::Borrow(BorrowKind::Unique)) |
             Read(ReadKind::Borrow(BorrowKind::Mut)) |
             Read(ReadKind::Borrow(BorrowKind::Shared)) |
-            Read(ReadKind::Copy) => {} // Access authorized
+            Read(ReadKind::Copy) |
+            Read(ReadKind::FakeRead(_)) => {} // Access authorized
         }
 
         error_reported
@@ -1765,6 +1991,13 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             }
         }
 
+        // Note that we compare the places exactly as they appear in the MIR,
+        // without trying to canonicalize them first. MIR construction already
+        // makes every autoderef explicit, so `r.f` and `(*r).f` are both built
+        // as the same `(*r).f` projection and always compare equal.
+        // Collapsing derefs here would instead be wrong: with `rr: &mut &mut S`,
+        // `*rr` is the reference itself while `**rr` is the `S` behind it, and
+        // those are different places that can be borrowed separately.
         let borrow_components = place_elements(borrow_place);
         let access_components = place_elements(access_place);
         debug!("places_conflict: components {:?} / {:?}",
@@ -1953,7 +2186,7 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         // FIXME: analogous code in check_loans first maps `place` to
         // its base_path.
 
-        let data = flow_state.borrows.operator().borrows();
+        let data = &self.borrow_set.borrows;
 
         // check for loan restricting path P being used. Accounts for
         // borrows of P, P.a.b, etc.
@@ -2019,6 +2252,7 @@ enum ContextKind {
     Assert,
     Yield,
     StorageDead,
+    FakeRead,
 }
 
 impl ContextKind {
@@ -0,0 +1,233 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reporting of the errors found while gathering the moves of a MIR
+//! body: moves out of places that may not be moved out of, like
+//! statics, borrowed content, or the elements of arrays.
+
+use rustc::hir::def_id::DefId;
+use rustc::infer::InferCtxt;
+use rustc::middle::const_val::ConstVal;
+use rustc::mir::{Constant, Literal, Local, LocalKind, Mir, Operand, Place, TerminatorKind};
+use rustc::traits;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc::ty::subst::Substs;
+use rustc_errors::DiagnosticBuilder;
+use syntax_pos::Span;
+
+use dataflow::move_paths::{IllegalMoveOriginKind, MoveError};
+use util::borrowck_errors::{BorrowckErrors, Origin};
+
+pub(super) fn report_move_errors<'a, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    move_errors: Vec<MoveError<'tcx>>,
+) {
+    let tcx = infcx.tcx;
+    for move_error in move_errors {
+        let origin = match move_error {
+            MoveError::UnionMove { .. } => {
+                unimplemented!("dont know how to report union move errors yet.")
+            }
+            MoveError::IllegalMove {
+                cannot_move_out_of: o,
+            } => o,
+        };
+        let span = origin.span;
+        let mut err = match origin.kind {
+            IllegalMoveOriginKind::Static => {
+                tcx.cannot_move_out_of(span, "static item", Origin::Mir)
+            }
+            IllegalMoveOriginKind::BorrowedContent => {
+                match overloaded_index_container(tcx, mir, &origin.place) {
+                    Some(container_ty) => {
+                        let mut err = tcx.cannot_move_out_of(span, "indexed content", Origin::Mir);
+                        let element_ty = origin.place.ty(mir, tcx).to_ty(tcx);
+                        if is_std_type(tcx, container_ty, "alloc", "Vec") {
+                            suggest_vec_removal(tcx, &mut err, span);
+                        }
+                        suggest_element_alternatives(infcx, param_env, &mut err, span, element_ty);
+                        err
+                    }
+                    None => tcx.cannot_move_out_of(span, "borrowed content", Origin::Mir),
+                }
+            }
+            IllegalMoveOriginKind::InteriorOfTypeWithDestructor { container_ty: ty } => {
+                tcx.cannot_move_out_of_interior_of_drop(span, ty, Origin::Mir)
+            }
+            IllegalMoveOriginKind::InteriorOfSliceOrArray { ty, is_index } => {
+                let mut err =
+                    tcx.cannot_move_out_of_interior_noncopy(span, ty, is_index, Origin::Mir);
+                // Only an `a[i]` expression can be rewritten; a move
+                // out of a slice pattern is left alone.
+                if is_index {
+                    let element_ty = origin.place.ty(mir, tcx).to_ty(tcx);
+                    suggest_element_alternatives(infcx, param_env, &mut err, span, element_ty);
+                }
+                err
+            }
+        };
+        err.emit();
+    }
+}
+
+/// If `place` is the `*tmp` that an overloaded `container[index]`
+/// expression desugars into, where `tmp` holds the result of the
+/// call to `Index::index` (or `IndexMut::index_mut`), returns the
+/// type of the container.
+fn overloaded_index_container<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+    place: &Place<'tcx>,
+) -> Option<Ty<'tcx>> {
+    let local = match *place {
+        Place::Projection(ref proj) => match proj.base {
+            Place::Local(local) if mir.local_kind(local) == LocalKind::Temp => local,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let (def_id, substs) = defining_call(mir, local)?;
+    let trait_def_id = tcx.trait_of_item(def_id)?;
+    let lang_items = tcx.lang_items();
+    if Some(trait_def_id) != lang_items.index_trait()
+        && Some(trait_def_id) != lang_items.index_mut_trait()
+    {
+        return None;
+    }
+    Some(substs.type_at(0))
+}
+
+/// Finds the call to a statically known function whose result is
+/// stored into `local`, if any.
+fn defining_call<'tcx>(
+    mir: &Mir<'tcx>,
+    local: Local,
+) -> Option<(DefId, &'tcx Substs<'tcx>)> {
+    for block_data in mir.basic_blocks() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(box Constant {
+                literal: Literal::Value { value },
+                ..
+            }),
+            destination: Some((Place::Local(dest), _)),
+            ..
+        } = block_data.terminator().kind
+        {
+            if dest == local {
+                if let ConstVal::Function(def_id, substs) = value.val {
+                    return Some((def_id, substs));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// True if `ty` is the type `name` defined in the crate `krate` of
+/// the standard library.
+fn is_std_type<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    ty: Ty<'tcx>,
+    krate: &str,
+    name: &str,
+) -> bool {
+    match ty.sty {
+        ty::TyAdt(adt_def, _) => {
+            tcx.item_name(adt_def.did) == name && tcx.crate_name(adt_def.did.krate) == krate
+        }
+        _ => false,
+    }
+}
+
+/// Splits the snippet of an index expression, like `v[i + 1]`, into
+/// the snippets of the indexed expression and of the index.
+fn split_index_snippet(snippet: &str) -> Option<(&str, &str)> {
+    if !snippet.ends_with(']') {
+        return None;
+    }
+    // Find the `[` matching the final `]`.
+    let mut depth = 0;
+    for (i, c) in snippet.char_indices().rev() {
+        match c {
+            ']' => depth += 1,
+            '[' => {
+                depth -= 1;
+                if depth == 0 {
+                    let base = snippet[..i].trim_right();
+                    let index = &snippet[i + 1..snippet.len() - 1];
+                    return if base.is_empty() { None } else { Some((base, index)) };
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn suggest_vec_removal<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    err: &mut DiagnosticBuilder,
+    span: Span,
+) {
+    let snippet = match tcx.sess.codemap().span_to_snippet(span) {
+        Ok(snippet) => snippet,
+        Err(_) => return,
+    };
+    if let Some((base, index)) = split_index_snippet(&snippet) {
+        err.span_suggestion(
+            span,
+            "to take the element out of the vector, remove it",
+            format!("{}.remove({})", base, index),
+        );
+        err.span_suggestion(
+            span,
+            "or, if the order of the other elements does not matter",
+            format!("{}.swap_remove({})", base, index),
+        );
+    }
+}
+
+/// Suggests ways to get at an element of type `element_ty` without
+/// moving it out of its container.
+fn suggest_element_alternatives<'a, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    err: &mut DiagnosticBuilder,
+    span: Span,
+    element_ty: Ty<'tcx>,
+) {
+    let tcx = infcx.tcx;
+    let snippet = match tcx.sess.codemap().span_to_snippet(span) {
+        Ok(snippet) => snippet,
+        Err(_) => return,
+    };
+
+    if is_std_type(tcx, element_ty, "core", "Option") {
+        err.span_suggestion(
+            span,
+            "consider taking the value, leaving `None` in its place",
+            format!("{}.take()", snippet),
+        );
+    }
+
+    let is_clone = tcx.lang_items().clone_trait().map_or(false, |clone_trait| {
+        traits::type_known_to_meet_bound(infcx, param_env, element_ty, clone_trait, span)
+    });
+    if is_clone {
+        err.span_suggestion(
+            span,
+            "consider cloning the value",
+            format!("{}.clone()", snippet),
+        );
+    }
+}
@@ -10,6 +10,7 @@
 
 use rustc::hir;
 use rustc::mir::{BasicBlock, BasicBlockData, Location, Place, Mir, Rvalue};
+use rustc::mir::{TerminatorKind, RETURN_PLACE};
 use rustc::mir::visit::Visitor;
 use rustc::mir::Place::Projection;
 use rustc::mir::{Local, PlaceProjection, ProjectionElem};
@@ -127,6 +128,25 @@ impl<'cg, 'cx, 'gcx, 'tcx> Visitor<'tcx> for ConstraintGeneration<'cg, 'cx, 'gcx
 
         self.super_rvalue(rvalue, location);
     }
+
+    /// The `Return` terminator reads the return place, which the
+    /// liveness computation does not see. That only matters if the
+    /// type of the return place has regions that are not universal,
+    /// as the hidden type of an `impl Trait` does: a borrow that flows
+    /// into it must then last until the return.
+    fn visit_terminator_kind(
+        &mut self,
+        block: BasicBlock,
+        kind: &TerminatorKind<'tcx>,
+        location: Location,
+    ) {
+        if let TerminatorKind::Return = *kind {
+            let return_ty = self.mir.local_decls[RETURN_PLACE].ty;
+            self.add_regular_live_constraint(return_ty, location);
+        }
+
+        self.super_terminator_kind(block, kind, location);
+    }
 }
 
 impl<'cx, 'cg, 'gcx, 'tcx> ConstraintGeneration<'cx, 'cg, 'gcx, 'tcx> {
@@ -328,6 +348,8 @@ impl<'cx, 'cg, 'gcx, 'tcx> ConstraintGeneration<'cx, 'cg, 'gcx, 'tcx> {
                         base_region.to_region_vid(),
                         borrow_region.to_region_vid(),
                         location.successor_within_block(),
+                        None,
+                        None,
                     );
                 }
             }
@@ -0,0 +1,100 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Buffering of the bugs that the MIR borrow checker delays with
+//! `delay_span_bug`, for situations that should only arise after an
+//! earlier error. The session only keeps the last delayed bug, so when
+//! several fire in one crate the first cause is lost. While the whole
+//! crate is borrow checked (see `borrow_check::check_crate`), the bugs
+//! are collected in `Session::buffered_nll_bugs` instead, tagged with a
+//! category, and reported together if no real error turns up by the
+//! end of the compilation.
+
+use rustc::session::Session;
+use rustc_errors::{Diagnostic, Level};
+use syntax_pos::MultiSpan;
+
+/// Delays a bug of the given `category`, to be reported only if the
+/// session does not report any error.
+pub(crate) fn delay_nll_bug<S: Into<MultiSpan>>(
+    sess: &Session,
+    category: &'static str,
+    span: S,
+    msg: &str,
+) {
+    if !sess.opts.debugging_opts.treat_err_as_bug {
+        if let Some(ref mut buffer) = *sess.buffered_nll_bugs.borrow_mut() {
+            let mut diagnostic = Diagnostic::new(Level::Bug, msg);
+            diagnostic.set_span(span);
+            buffer.push((category, diagnostic));
+            return;
+        }
+    }
+
+    sess.delay_span_bug(span, msg);
+}
+
+/// Starts buffering delayed bugs, until `flush_delayed_bugs`.
+pub(in borrow_check) fn buffer_delayed_bugs(sess: &Session) {
+    let mut buffered_nll_bugs = sess.buffered_nll_bugs.borrow_mut();
+    assert!(buffered_nll_bugs.is_none(), "delayed bugs are already buffered");
+    *buffered_nll_bugs = Some(vec![]);
+}
+
+/// Stops buffering delayed bugs. They are summarized as a single bug,
+/// with a note for each category giving the number of bugs and the
+/// first of them, which is delayed in turn: like any delayed bug, it
+/// is dropped if the session reports an error, even a later one, as
+/// the bugs are then most likely fallout of it.
+pub(in borrow_check) fn flush_delayed_bugs(sess: &Session) {
+    let buffer = match sess.buffered_nll_bugs.borrow_mut().take() {
+        Some(buffer) => buffer,
+        None => return,
+    };
+    if buffer.is_empty() {
+        return;
+    }
+
+    let mut categories: Vec<(&'static str, Diagnostic, usize)> = vec![];
+    for (category, diagnostic) in buffer {
+        match categories.iter().position(|&(c, ..)| c == category) {
+            Some(index) => categories[index].2 += 1,
+            None => categories.push((category, diagnostic, 1)),
+        }
+    }
+
+    let mut bug = Diagnostic::new(
+        Level::Bug,
+        &format!(
+            "the MIR borrow checker delayed {} in {} without reporting an error",
+            count_of(categories.iter().map(|&(_, _, count)| count).sum(), "bug", "bugs"),
+            count_of(categories.len(), "category", "categories"),
+        ),
+    );
+    bug.set_span(categories[0].1.span.clone());
+    for (category, diagnostic, count) in categories {
+        let note = format!(
+            "{}: {}, the first of which is: {}",
+            category,
+            count_of(count, "bug", "bugs"),
+            diagnostic.message(),
+        );
+        bug.span_note(diagnostic.span, &note);
+    }
+    sess.diagnostic().delay_bug_diagnostic(bug);
+}
+
+fn count_of(count: usize, singular: &str, plural: &str) -> String {
+    if count == 1 {
+        format!("1 {}", singular)
+    } else {
+        format!("{} {}", count, plural)
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Buffering of the region errors reported by the universal-region
+//! check. A macro that stamps out many identical closures makes every
+//! one of them report the same error, so while the whole crate is
+//! borrow checked (see `borrow_check::check_crate`), the errors that
+//! point into a macro expansion are collected in
+//! `Session::buffered_region_errors` and then emitted once per key,
+//! noting how many closures were folded.
+
+use rustc::session::Session;
+use rustc::util::nodemap::FxHashMap;
+use rustc_errors::{Diagnostic, DiagnosticBuilder, DiagnosticId};
+use std::collections::hash_map::Entry;
+use syntax_pos::{BytePos, Span};
+
+/// Identifies region errors that render the same. The primary span is
+/// compared by its source range only, leaving out its expansion
+/// context, so that the same closure stamped out repeatedly by a macro
+/// maps to a single key. The message carries the names of the
+/// regions involved.
+#[derive(PartialEq, Eq, Hash)]
+struct RegionErrorKey {
+    span: Option<(BytePos, BytePos)>,
+    macro_def: Option<(String, Option<Span>)>,
+    code: Option<String>,
+    message: String,
+}
+
+impl RegionErrorKey {
+    fn new(diagnostic: &Diagnostic) -> Self {
+        let primary_span = diagnostic.span.primary_span();
+        RegionErrorKey {
+            span: primary_span.map(|span| (span.lo(), span.hi())),
+            macro_def: primary_span.and_then(macro_def),
+            code: diagnostic.code.as_ref().map(|code| match *code {
+                DiagnosticId::Error(ref code) | DiagnosticId::Lint(ref code) => code.clone(),
+            }),
+            message: diagnostic.message(),
+        }
+    }
+}
+
+/// The name and definition site of the innermost macro that `span`
+/// was expanded from, if any.
+fn macro_def(span: Span) -> Option<(String, Option<Span>)> {
+    span.macro_backtrace()
+        .into_iter()
+        .next()
+        .map(|backtrace| (backtrace.macro_decl_name, backtrace.def_site_span))
+}
+
+/// Emits `err`, or buffers it if the region errors of the crate are
+/// being buffered and `err` points into a macro expansion. Errors
+/// outside of macros cannot be repeated by identical closures, so they
+/// are emitted right away, in the order in which they are found.
+pub(super) fn emit_region_error(sess: &Session, mut err: DiagnosticBuilder) {
+    if let Some(ref mut buffer) = *sess.buffered_region_errors.borrow_mut() {
+        if err.span.primary_span().and_then(macro_def).is_some() {
+            buffer.push((*err).clone());
+            err.cancel();
+            return;
+        }
+    }
+
+    err.emit();
+}
+
+/// Starts buffering region errors, until `flush_region_errors`.
+pub(in borrow_check) fn buffer_region_errors(sess: &Session) {
+    let mut buffered_region_errors = sess.buffered_region_errors.borrow_mut();
+    assert!(buffered_region_errors.is_none(), "region errors are already buffered");
+    *buffered_region_errors = Some(vec![]);
+}
+
+/// Stops buffering region errors and emits those buffered so far,
+/// once per key, in the order in which each key was first reported.
+pub(in borrow_check) fn flush_region_errors(sess: &Session) {
+    let buffer = match sess.buffered_region_errors.borrow_mut().take() {
+        Some(buffer) => buffer,
+        None => return,
+    };
+
+    let mut group_indices = FxHashMap();
+    let mut groups: Vec<(Diagnostic, usize)> = vec![];
+    for diagnostic in buffer {
+        match group_indices.entry(RegionErrorKey::new(&diagnostic)) {
+            Entry::Occupied(entry) => groups[*entry.get()].1 += 1,
+            Entry::Vacant(entry) => {
+                entry.insert(groups.len());
+                groups.push((diagnostic, 1));
+            }
+        }
+    }
+
+    for (mut diagnostic, count) in groups {
+        if count > 1 {
+            let macro_def = diagnostic.span.primary_span().and_then(macro_def);
+            if let Some((macro_decl_name, _)) = macro_def {
+                diagnostic.note(&format!(
+                    "this error originates from {} identical closures generated by \
+                     the macro `{}`",
+                    count,
+                    macro_decl_name
+                ));
+            }
+        }
+        DiagnosticBuilder::new_diagnostic(sess.diagnostic(), diagnostic).emit();
+    }
+}
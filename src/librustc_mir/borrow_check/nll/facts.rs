@@ -0,0 +1,81 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Writes what is known about the universal regions of a MIR body as
+//! facts for an external, datalog-style region solver (see `-Z
+//! nll-facts`). Each relation goes into a file of its own, named
+//! after the relation, with one fact per line and the columns
+//! separated by tabs. Regions are written as their vids (`'_#1r`).
+
+use rustc::hir::def_id::DefId;
+use rustc::infer::InferCtxt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+use super::universal_regions::UniversalRegions;
+
+/// Writes the facts about `universal_regions` into a directory of
+/// their own within `dir`, named after the body `def_id`.
+pub(super) fn write_universal_region_facts<'cx, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'cx, 'gcx, 'tcx>,
+    def_id: DefId,
+    dir: &str,
+    universal_regions: &UniversalRegions<'tcx>,
+) {
+    let item_path: String = infcx
+        .tcx
+        .item_path_str(def_id)
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    let dir = Path::new(dir).join(format!("{}.{}", item_path, def_id.index.as_usize()));
+
+    let result: io::Result<()> = do catch {
+        fs::create_dir_all(&dir)?;
+
+        let mut file = File::create(dir.join("universal_region.facts"))?;
+        for fr in universal_regions.universal_regions() {
+            write_fact(&mut file, &[&format!("{:?}", fr)])?;
+        }
+
+        // The outlives relation is written out in full, except for
+        // the facts `'a: 'a` that hold for every region.
+        let mut file = File::create(dir.join("outlives.facts"))?;
+        for fr in universal_regions.universal_regions() {
+            for r in universal_regions.regions_outlived_by(fr).filter(|&r| r != fr) {
+                write_fact(&mut file, &[&format!("{:?}", fr), &format!("{:?}", r)])?;
+            }
+        }
+
+        let mut file = File::create(dir.join("region_classification.facts"))?;
+        for fr in universal_regions.universal_regions() {
+            let classification = universal_regions.region_classification(fr).unwrap();
+            write_fact(&mut file, &[&format!("{:?}", fr), &format!("{:?}", classification)])?;
+        }
+
+        let mut file = File::create(dir.join("region_bound_pair.facts"))?;
+        for &(r, ref generic_kind) in &universal_regions.region_bound_pairs {
+            let fr = universal_regions.to_region_vid(r);
+            write_fact(&mut file, &[&format!("{:?}", fr), &format!("{}", generic_kind)])?;
+        }
+    };
+    if let Err(e) = result {
+        infcx.tcx.sess.warn(&format!(
+            "could not write universal region facts to `{}`: {}",
+            dir.display(),
+            e
+        ));
+    }
+}
+
+fn write_fact(file: &mut File, columns: &[&str]) -> io::Result<()> {
+    writeln!(file, "{}", columns.join("\t"))
+}
@@ -0,0 +1,188 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The outlives relations known to hold among the universal regions
+//! of a MIR body, as computed by `UniversalRegions::new` from the
+//! where-clauses and implied bounds. Region inference consults these
+//! directly when checking the final values of the universal regions.
+
+use rustc::ty::RegionVid;
+use rustc_data_structures::bitvec::BitVector;
+use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use rustc_data_structures::transitive_relation::TransitiveRelation;
+
+/// `'static` outlives every universal region, but we do not store the
+/// edges `'static: fr` in `outlives` and `inverse_outlives`: with many
+/// late-bound regions, they would make up most of both relations and
+/// of the time spent computing their closures. Instead, `freeze` adds
+/// them to `outlives_bits`, so `outlives` and `regions_outlived_by`
+/// answer as though they were there. Queries that walk the relations
+/// themselves must treat `'static` as the implicit upper bound of
+/// every region (see `UniversalRegions::non_local_upper_bound`). The
+/// reflexive edge `'static: 'static` is stored like any other.
+#[derive(Debug)]
+pub struct UniversalRegionRelations {
+    /// Stores the outlives relations that are known to hold from the
+    /// implied bounds, in-scope where clauses, and that sort of
+    /// thing.
+    outlives: TransitiveRelation<RegionVid>,
+
+    /// This is the `<=` relation; that is, if `a: b`, then `b <= a`,
+    /// and we store that here. This is useful when figuring out how
+    /// to express some local region in terms of external regions our
+    /// caller will understand.
+    inverse_outlives: TransitiveRelation<RegionVid>,
+
+    /// The transitive closure of `outlives`, one row per universal
+    /// region, computed by `freeze` once all relations are known.
+    /// Region inference asks `outlives` from within its propagation
+    /// loop, so we want those queries to be plain bit tests.
+    outlives_bits: IndexVec<RegionVid, BitVector>,
+}
+
+impl UniversalRegionRelations {
+    pub(super) fn new() -> Self {
+        UniversalRegionRelations {
+            outlives: TransitiveRelation::new(),
+            inverse_outlives: TransitiveRelation::new(),
+            outlives_bits: IndexVec::new(),
+        }
+    }
+
+    /// Precomputes the answers to `outlives` and
+    /// `regions_outlived_by`. Called once the relations for the
+    /// `num_universals` universal regions are complete; no relation
+    /// may be added afterwards.
+    ///
+    /// This is also where the implicit edges `fr_static: fr` come in:
+    /// `'static`, and any region known to outlive it, outlives every
+    /// universal region.
+    pub(super) fn freeze(&mut self, fr_static: RegionVid, num_universals: usize) {
+        let mut outlives_bits = self.outlives.reachability_bits(num_universals);
+        let mut everything = BitVector::new(num_universals);
+        for index in 0..num_universals {
+            everything.insert(index);
+        }
+        for bits in outlives_bits.iter_mut() {
+            if bits.contains(fr_static.index()) {
+                bits.insert_all(&everything);
+            }
+        }
+        outlives_bits[fr_static].insert_all(&everything);
+        self.outlives_bits = outlives_bits;
+    }
+
+    /// Records in the `outlives_relation` (and
+    /// `inverse_outlives_relation`) that `fr_a: fr_b`.
+    pub(super) fn relate_universal_regions(&mut self, fr_a: RegionVid, fr_b: RegionVid) {
+        debug!(
+            "relate_universal_regions: fr_a={:?} outlives fr_b={:?}",
+            fr_a,
+            fr_b
+        );
+        debug_assert!(self.outlives_bits.is_empty(), "relations are already frozen");
+        self.outlives.add(fr_a, fr_b);
+        self.inverse_outlives.add(fr_b, fr_a);
+    }
+
+    /// True if fr1 is known to outlive fr2.
+    ///
+    /// This will only ever be true for universally quantified regions.
+    pub fn outlives(&self, fr1: RegionVid, fr2: RegionVid) -> bool {
+        match self.outlives_bits.get(fr1) {
+            Some(bits) => fr2.index() < self.outlives_bits.len() && bits.contains(fr2.index()),
+            None => false,
+        }
+    }
+
+    /// Returns the free regions `x` such that `fr1: x` is known to
+    /// hold, in index order.
+    pub fn regions_outlived_by<'a>(
+        &'a self,
+        fr1: RegionVid,
+    ) -> impl Iterator<Item = RegionVid> + 'a {
+        self.outlives_bits
+            .get(fr1)
+            .into_iter()
+            .flat_map(|bits| bits.iter())
+            .map(RegionVid::new)
+    }
+
+    /// The `outlives` relation itself.
+    pub(super) fn outlives_relation(&self) -> &TransitiveRelation<RegionVid> {
+        &self.outlives
+    }
+
+    /// The `inverse_outlives` relation itself.
+    pub(super) fn inverse_outlives_relation(&self) -> &TransitiveRelation<RegionVid> {
+        &self.inverse_outlives
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc::ty::RegionVid;
+    use rustc_data_structures::indexed_vec::Idx;
+    use super::UniversalRegionRelations;
+
+    /// Builds the relations among `num_universals` regions, of which
+    /// region 0 is `'static`, from the reflexive edges and `edges`;
+    /// with `explicit_static`, also from the edges `'static: fr`.
+    fn relations(
+        num_universals: usize,
+        edges: &[(usize, usize)],
+        explicit_static: bool,
+    ) -> UniversalRegionRelations {
+        let fr_static = RegionVid::new(0);
+        let mut relations = UniversalRegionRelations::new();
+        for fr in (0..num_universals).map(RegionVid::new) {
+            relations.relate_universal_regions(fr, fr);
+            if explicit_static {
+                relations.relate_universal_regions(fr_static, fr);
+            }
+        }
+        for &(a, b) in edges {
+            relations.relate_universal_regions(RegionVid::new(a), RegionVid::new(b));
+        }
+        relations.freeze(fr_static, num_universals);
+        relations
+    }
+
+    #[test]
+    fn implicit_static_edges() {
+        // `'2: '1`, and `'3: 'static`, so `'3` outlives everything too.
+        let edges = [(2, 1), (3, 0)];
+        let implicit = relations(5, &edges, false);
+        let explicit = relations(5, &edges, true);
+
+        for fr1 in (0..5).map(RegionVid::new) {
+            for fr2 in (0..5).map(RegionVid::new) {
+                assert_eq!(
+                    implicit.outlives(fr1, fr2),
+                    explicit.outlives(fr1, fr2),
+                    "{:?}: {:?}",
+                    fr1,
+                    fr2
+                );
+            }
+            assert_eq!(
+                implicit.regions_outlived_by(fr1).collect::<Vec<_>>(),
+                explicit.regions_outlived_by(fr1).collect::<Vec<_>>()
+            );
+        }
+
+        let all: Vec<_> = (0..5).map(RegionVid::new).collect();
+        assert_eq!(implicit.regions_outlived_by(RegionVid::new(0)).collect::<Vec<_>>(), all);
+        assert_eq!(implicit.regions_outlived_by(RegionVid::new(3)).collect::<Vec<_>>(), all);
+        assert!(implicit.outlives(RegionVid::new(2), RegionVid::new(1)));
+        assert!(!implicit.outlives(RegionVid::new(1), RegionVid::new(2)));
+        assert!(!implicit.outlives(RegionVid::new(4), RegionVid::new(0)));
+    }
+}
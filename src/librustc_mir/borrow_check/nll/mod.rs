@@ -9,12 +9,16 @@
 // except according to those terms.
 
 use rustc::hir::def_id::DefId;
-use rustc::mir::{ClosureRegionRequirements, Mir};
+use rustc::mir::{ClosureRegionRequirements, Mir, UniversalRegionsSummary};
 use rustc::infer::InferCtxt;
-use rustc::ty::{self, RegionKind, RegionVid};
+use rustc::ty::{self, RegionKind, RegionVid, Ty, TyCtxt, TypeFlags, TypeFoldable};
 use rustc::util::nodemap::FxHashMap;
 use std::collections::BTreeSet;
-use std::io;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::rc::Rc;
+use syntax::ast;
 use transform::MirSource;
 use transform::type_check;
 use util::liveness::{self, LivenessMode, LivenessResult, LocalSet};
@@ -27,6 +31,11 @@ use util::pretty::{self, ALIGN};
 use self::mir_util::PassWhere;
 
 mod constraint_generation;
+pub(crate) mod delayed_bugs;
+pub(in borrow_check) mod error_buffer;
+mod facts;
+mod free_region_relations;
+mod redundant_bounds;
 mod subtype_constraint_generation;
 mod universal_regions;
 use self::universal_regions::UniversalRegions;
@@ -36,54 +45,197 @@ use self::region_infer::RegionInferenceContext;
 
 mod renumber;
 
+/// The `universal_regions_summary` query: the summary of the
+/// universal regions of `def_id` that `compute_regions` made while
+/// borrow-checking it.
+pub(crate) fn universal_regions_summary<'a, 'tcx>(
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    def_id: DefId,
+) -> Option<Rc<UniversalRegionsSummary<'tcx>>> {
+    debug!("universal_regions_summary(def_id={:?})", def_id);
+    tcx.mir_borrowck(def_id).universal_regions_summary
+}
+
+/// True if `-Z nll-only` is not given, or if the path of `def_id`
+/// contains its filter. The NLL dump and trace options only apply to
+/// the bodies for which this holds.
+pub(in borrow_check) fn matches_nll_only<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    def_id: DefId,
+) -> bool {
+    match tcx.sess.opts.debugging_opts.nll_only {
+        Some(ref filter) => tcx.item_path_str(def_id).contains(&filter[..]),
+        None => true,
+    }
+}
+
 /// Rewrites the regions in the MIR to use NLL variables, also
 /// scraping out the set of universal regions (e.g., region parameters)
 /// declared on the function. That set will need to be given to
-/// `compute_regions`.
+/// `compute_regions`. `tables` are the typeck tables of `def_id`, if
+/// it has any (constructors do not);
+/// `dumps_enabled` is false if `-Z nll-only` filters out `def_id`.
 pub(in borrow_check) fn replace_regions_in_mir<'cx, 'gcx, 'tcx>(
     infcx: &InferCtxt<'cx, 'gcx, 'tcx>,
     def_id: DefId,
     param_env: ty::ParamEnv<'tcx>,
+    tables: Option<&'gcx ty::TypeckTables<'gcx>>,
+    dumps_enabled: bool,
     mir: &mut Mir<'tcx>,
 ) -> UniversalRegions<'tcx> {
     debug!("replace_regions_in_mir(def_id={:?})", def_id);
 
     // Compute named region information. This also renumbers the inputs/outputs.
-    let universal_regions = UniversalRegions::new(infcx, def_id, param_env);
+    let universal_regions = UniversalRegions::new(infcx, def_id, param_env, tables);
+    redundant_bounds::lint_redundant_bounds(infcx.tcx, def_id, &universal_regions);
+
+    if dumps_enabled {
+        if let Some(ref dir) = infcx.tcx.sess.opts.debugging_opts.nll_dump_region_graph {
+            dump_region_graph(infcx, def_id, dir, &universal_regions);
+        }
+        if let Some(ref dir) = infcx.tcx.sess.opts.debugging_opts.nll_facts {
+            facts::write_universal_region_facts(infcx, def_id, dir, &universal_regions);
+        }
+    }
 
     // Replace all remaining regions with fresh inference variables.
-    renumber::renumber_mir(infcx, &universal_regions, mir);
+    let tainted_by_errors = tables.map_or(false, |tables| tables.tainted_by_errors);
+    renumber::renumber_mir(infcx, &universal_regions, mir, tainted_by_errors);
+
+    if dumps_enabled {
+        let source = MirSource::item(def_id);
+        mir_util::dump_mir(infcx.tcx, None, "renumber", &0, source, mir, |_, _| Ok(()));
+    }
+
+    universal_regions
+}
 
-    let source = MirSource::item(def_id);
-    mir_util::dump_mir(infcx.tcx, None, "renumber", &0, source, mir, |_, _| Ok(()));
+/// Like `replace_regions_in_mir`, but for the MIR of the shim
+/// `instance`, which has neither HIR nor typeck tables (see
+/// `UniversalRegions::new_for_shim`). The NLL dumps are not written
+/// for shims.
+pub(in borrow_check) fn replace_regions_in_shim<'cx, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'cx, 'gcx, 'tcx>,
+    instance: ty::InstanceDef<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    mir: &mut Mir<'tcx>,
+) -> UniversalRegions<'tcx> {
+    debug!("replace_regions_in_shim(instance={:?})", instance);
 
+    let universal_regions = UniversalRegions::new_for_shim(infcx, instance, param_env, mir);
+    renumber::renumber_mir(infcx, &universal_regions, mir, false);
     universal_regions
 }
 
+/// Writes the outlives relation among `universal_regions` into a
+/// graphviz file in `dir`, for `-Z nll-dump-region-graph`.
+fn dump_region_graph<'cx, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'cx, 'gcx, 'tcx>,
+    def_id: DefId,
+    dir: &str,
+    universal_regions: &UniversalRegions<'tcx>,
+) {
+    let item_path: String = infcx
+        .tcx
+        .item_path_str(def_id)
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    let file_name = format!("{}.{}.dot", item_path, def_id.index.as_usize());
+    let path = Path::new(dir).join(file_name);
+
+    let result: io::Result<()> = do catch {
+        fs::create_dir_all(dir)?;
+        let mut file = fs::File::create(&path)?;
+        universal_regions.dump_graphviz(&mut file)
+    };
+    if let Err(e) = result {
+        infcx.tcx.sess.warn(&format!(
+            "could not write region graph to `{}`: {}",
+            path.display(),
+            e
+        ));
+    }
+}
+
+/// Appends the canonical description of the region inference for
+/// `mir` to the file at `path`, for `-Z nll-compare-log`.
+fn write_compare_log<'cx, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'cx, 'gcx, 'tcx>,
+    def_id: DefId,
+    path: &str,
+    mir: &Mir<'tcx>,
+    regioncx: &RegionInferenceContext<'tcx>,
+) {
+    // Write the whole body at once, so that the descriptions of two
+    // bodies never end up interleaved.
+    let mut buffer = vec![];
+    let result: io::Result<()> = do catch {
+        regioncx.write_compare_log(infcx, mir, def_id, &mut buffer)?;
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(&buffer)
+    };
+    if let Err(e) = result {
+        infcx.tcx.sess.warn(&format!(
+            "could not write NLL comparison log to `{}`: {}",
+            path,
+            e
+        ));
+    }
+}
+
 /// Computes the (non-lexical) regions from the input MIR.
 ///
-/// This may result in errors being reported.
+/// This may result in errors being reported. The NLL dumps are only
+/// written if `dumps_enabled`; see `matches_nll_only`.
 pub(in borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
     infcx: &InferCtxt<'cx, 'gcx, 'tcx>,
     def_id: DefId,
-    universal_regions: UniversalRegions<'tcx>,
+    mut universal_regions: UniversalRegions<'tcx>,
     mir: &Mir<'tcx>,
     param_env: ty::ParamEnv<'gcx>,
     flow_inits: &mut FlowAtLocation<MaybeInitializedLvals<'cx, 'gcx, 'tcx>>,
     move_data: &MoveData<'tcx>,
+    dumps_enabled: bool,
 ) -> (
     RegionInferenceContext<'tcx>,
-    Option<ClosureRegionRequirements>,
+    Option<ClosureRegionRequirements<'gcx>>,
+    UniversalRegionsSummary<'gcx>,
 ) {
+    // Summarize the universal regions for the `universal_regions_summary`
+    // query, before the placeholders below, which are internal to
+    // this body, join them.
+    let universal_regions_summary = universal_regions.summary(infcx.tcx);
+
     // Run the MIR type-checker.
-    let mir_node_id = infcx.tcx.hir.as_local_node_id(def_id).unwrap();
-    let constraint_sets = &type_check::type_check(infcx, mir_node_id, param_env, mir);
+    //
+    // A shim has no HIR; its obligations get a dummy body id.
+    let mir_node_id = infcx.tcx.hir.as_local_node_id(def_id).unwrap_or(ast::DUMMY_NODE_ID);
+    let mut constraint_sets = type_check::type_check(
+        infcx,
+        mir_node_id,
+        param_env,
+        mir,
+        &universal_regions.region_bound_pairs,
+        universal_regions.anon_output_ty(),
+    );
+
+    // Trait selection may have left `ReEmpty` in the constraints, and
+    // higher-ranked obligations skolemized regions; give them region
+    // variables while we still can.
+    subtype_constraint_generation::replace_empty_regions(infcx, &mut constraint_sets);
+    subtype_constraint_generation::create_placeholder_regions(
+        infcx,
+        &mut universal_regions,
+        &constraint_sets,
+    );
 
     // Create the region inference context, taking ownership of the region inference
     // data that was contained in `infcx`.
     let var_origins = infcx.take_region_var_origins();
-    let mut regioncx = RegionInferenceContext::new(var_origins, universal_regions, mir);
-    subtype_constraint_generation::generate(&mut regioncx, mir, constraint_sets);
+    let mut regioncx =
+        RegionInferenceContext::new(var_origins, universal_regions, mir, dumps_enabled);
+    subtype_constraint_generation::generate(&mut regioncx, mir, &constraint_sets);
 
     // Compute what is live where.
     let liveness = &LivenessResults {
@@ -117,23 +269,41 @@ pub(in borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
 
     // Solve the region constraints.
     let closure_region_requirements = regioncx.solve(infcx, &mir, def_id);
+    if let Some(ref closure_region_requirements) = closure_region_requirements {
+        debug!("compute_regions: closure_region_requirements:\n{}", closure_region_requirements);
+    }
 
     // Dump MIR results into a file, if that is enabled. This let us
     // write unit-tests, as well as helping with debugging.
-    dump_mir_results(
+    if dumps_enabled {
+        dump_mir_results(
+            infcx,
+            liveness,
+            MirSource::item(def_id),
+            &mir,
+            &regioncx,
+            &closure_region_requirements,
+        );
+    }
+
+    // We also have a `#[rustc_nll]` annotation that causes us to dump
+    // information
+    dump_annotation(
         infcx,
-        liveness,
-        MirSource::item(def_id),
         &mir,
+        def_id,
         &regioncx,
+        &universal_regions_summary,
         &closure_region_requirements,
     );
 
-    // We also have a `#[rustc_nll]` annotation that causes us to dump
-    // information
-    dump_annotation(infcx, &mir, def_id, &regioncx, &closure_region_requirements);
+    if let Some(ref path) = infcx.tcx.sess.opts.debugging_opts.nll_compare_log {
+        if dumps_enabled {
+            write_compare_log(infcx, def_id, path, &mir, &regioncx);
+        }
+    }
 
-    (regioncx, closure_region_requirements)
+    (regioncx, closure_region_requirements, universal_regions_summary)
 }
 
 struct LivenessResults {
@@ -147,7 +317,7 @@ fn dump_mir_results<'a, 'gcx, 'tcx>(
     source: MirSource,
     mir: &Mir<'tcx>,
     regioncx: &RegionInferenceContext,
-    closure_region_requirements: &Option<ClosureRegionRequirements>,
+    closure_region_requirements: &Option<ClosureRegionRequirements<'gcx>>,
 ) {
     if !mir_util::dump_enabled(infcx.tcx, "nll", source) {
         return;
@@ -179,18 +349,67 @@ fn dump_mir_results<'a, 'gcx, 'tcx>(
         })
         .collect();
 
+    // How much region information to include; see `-Z nll-dump-mir-regions`.
+    let regions_level = infcx.tcx.sess.opts.debugging_opts.nll_dump_mir_regions.unwrap_or(1);
+
     mir_util::dump_mir(infcx.tcx, None, "nll", &0, source, mir, |pass_where, out| {
         match pass_where {
-            // Before the CFG, dump out the values for each region variable.
+            // Before the CFG, dump out the universal regions and, if
+            // requested, the values for each region variable.
             PassWhere::BeforeCFG => {
-                regioncx.dump_mir(out)?;
-
-                if let Some(closure_region_requirements) = closure_region_requirements {
+                if regions_level >= 1 {
+                    regioncx.universal_regions().annotate(out)?;
                     writeln!(out, "|")?;
-                    writeln!(out, "| Free Region Constraints")?;
-                    for_each_region_constraint(closure_region_requirements, &mut |msg| {
-                        writeln!(out, "| {}", msg)
-                    })?;
+                }
+
+                if regions_level >= 3 {
+                    regioncx.dump_mir(out)?;
+                }
+
+                if regions_level >= 1 {
+                    if let Some(closure_region_requirements) = closure_region_requirements {
+                        writeln!(out, "|")?;
+                        writeln!(out, "| Free Region Constraints")?;
+                        for line in closure_region_requirements.to_string().lines() {
+                            writeln!(out, "| {}", line)?;
+                        }
+                    }
+                }
+            }
+
+            // After the declaration of each local, dump out its type
+            // with the region variables in it and, if requested, their
+            // values.
+            PassWhere::AfterLocalDecl(local) => {
+                if regions_level >= 2 {
+                    let ty = mir.local_decls[local].ty;
+                    writeln!(
+                        out,
+                        "{:ALIGN$} | {:?}: {}",
+                        "",
+                        local,
+                        ty_with_region_vids(infcx.tcx, source.def_id, ty),
+                        ALIGN = ALIGN
+                    )?;
+                }
+
+                if regions_level >= 3 {
+                    let mut vids = BTreeSet::new();
+                    infcx.tcx.for_each_free_region(&mir.local_decls[local].ty, |r| {
+                        if let ty::ReVar(vid) = *r {
+                            vids.insert(vid);
+                        }
+                    });
+                    for vid in vids {
+                        writeln!(
+                            out,
+                            "{:ALIGN$} |     {:?} = {}",
+                            "",
+                            vid,
+                            regioncx.region_value_str(vid),
+                            ALIGN = ALIGN
+                        )?;
+                    }
                 }
             }
 
@@ -229,63 +448,247 @@ fn dump_mir_results<'a, 'gcx, 'tcx>(
     };
 }
 
+/// Renders `ty` for the MIR dump with each region variable shown as
+/// its vid (e.g., `&'_#2r u32`). Outside of `-Z verbose`, region
+/// variables are printed as nothing at all, so we first replace each
+/// of them with a stand-in early-bound region named after the vid.
+fn ty_with_region_vids<'cx, 'gcx, 'tcx>(
+    tcx: TyCtxt<'cx, 'gcx, 'tcx>,
+    def_id: DefId,
+    ty: Ty<'tcx>,
+) -> String {
+    if tcx.sess.verbose() {
+        return format!("{}", ty);
+    }
+
+    let ty = tcx.fold_regions(&ty, &mut false, |r, _| match *r {
+        ty::ReVar(vid) => tcx.mk_region(ty::ReEarlyBound(ty::EarlyBoundRegion {
+            def_id,
+            index: 0,
+            name: ast::Name::intern(&format!("{:?}", vid)),
+        })),
+        _ => r,
+    });
+    format!("{}", ty)
+}
+
 fn dump_annotation<'a, 'gcx, 'tcx>(
     infcx: &InferCtxt<'a, 'gcx, 'tcx>,
     mir: &Mir<'tcx>,
     mir_def_id: DefId,
     regioncx: &RegionInferenceContext,
-    closure_region_requirements: &Option<ClosureRegionRequirements>,
+    universal_regions_summary: &UniversalRegionsSummary,
+    closure_region_requirements: &Option<ClosureRegionRequirements<'gcx>>,
 ) {
     let tcx = infcx.tcx;
     let base_def_id = tcx.closure_base_def_id(mir_def_id);
-    if !tcx.has_attr(base_def_id, "rustc_regions") {
-        return;
+
+    if tcx.has_attr(base_def_id, "rustc_universal_regions") {
+        dump_universal_regions(
+            infcx,
+            mir,
+            regioncx,
+            universal_regions_summary,
+            closure_region_requirements,
+        );
     }
 
+    let attrs = tcx.get_attrs(base_def_id);
+    let sections = match attrs.iter().find(|attr| attr.check_name("rustc_regions")) {
+        Some(attr) => RegionsDumpSections::parse(infcx, attr),
+        None => return,
+    };
+
     // When the enclosing function is tagged with `#[rustc_regions]`,
     // we dump out various bits of state as warnings. This is useful
     // for verifying that the compiler is behaving as expected.  These
     // warnings focus on the closure region requirements -- for
     // viewing the intraprocedural state, the -Zdump-mir output is
-    // better.
-
-    if let Some(closure_region_requirements) = closure_region_requirements {
-        let mut err = tcx.sess
-            .diagnostic()
-            .span_note_diag(mir.span, "External requirements");
+    // better. For large functions, the attribute can list the
+    // sections to dump, e.g. `#[rustc_regions(requirements)]`.
+    let headline = if closure_region_requirements.is_some() {
+        "External requirements"
+    } else {
+        "No external requirements"
+    };
+    let mut err = tcx.sess.diagnostic().span_note_diag(mir.span, headline);
 
+    if sections.defining_ty {
         regioncx.annotate(&mut err);
+    }
 
-        err.note(&format!(
-            "number of external vids: {}",
-            closure_region_requirements.num_external_vids
-        ));
+    if sections.universal {
+        regioncx.annotate_universal_regions(universal_regions_summary, &mut err);
+    }
+
+    if sections.named {
+        regioncx.annotate_named_universal_regions(&mut err);
+    }
+
+    if sections.requirements {
+        if let Some(closure_region_requirements) = closure_region_requirements {
+            err.note(&format!(
+                "number of external vids: {}",
+                closure_region_requirements.num_external_vids
+            ));
+
+            // Dump the region constraints we are imposing *between* those
+            // newly created variables.
+            for_each_region_constraint(closure_region_requirements, &mut |msg| {
+                err.note(msg);
+                Ok(())
+            }).unwrap();
+        }
+    }
+
+    if let Some(ref filter) = sections.values {
+        regioncx.annotate_region_values(&mut err, filter);
+    }
+
+    err.emit();
+}
+
+/// The sections of the `#[rustc_regions]` dump. A bare
+/// `#[rustc_regions]` dumps the defining type and the closure region
+/// requirements; `#[rustc_regions(universal, values("'a"))]` dumps
+/// only the listed sections.
+struct RegionsDumpSections {
+    defining_ty: bool,
+    universal: bool,
+    requirements: bool,
+
+    /// Dump the regions declared with a name, as given by
+    /// `UniversalRegions::named_universal_regions`.
+    named: bool,
+
+    /// If `Some`, dump the inferred values of the regions named in
+    /// the list -- either by their debug representation, like
+    /// `'_#3r`, or by their name, like `'a` -- or of all regions if
+    /// the list is empty.
+    values: Option<Vec<String>>,
+}
 
-        // Dump the region constraints we are imposing *between* those
-        // newly created variables.
+impl RegionsDumpSections {
+    const SECTION_NAMES: &'static [&'static str] =
+        &["defining_ty", "universal", "requirements", "named", "values"];
+
+    fn parse(infcx: &InferCtxt<'_, '_, '_>, attr: &ast::Attribute) -> Self {
+        let items = match attr.meta_item_list() {
+            Some(items) => items,
+            None => {
+                return RegionsDumpSections {
+                    defining_ty: true,
+                    universal: false,
+                    requirements: true,
+                    named: false,
+                    values: None,
+                }
+            }
+        };
+
+        let mut sections = RegionsDumpSections {
+            defining_ty: false,
+            universal: false,
+            requirements: false,
+            named: false,
+            values: None,
+        };
+
+        let sess = infcx.tcx.sess;
+        for item in items {
+            let name = match item.name() {
+                Some(name) => name,
+                None => {
+                    sess.span_err(item.span(), "expected a section name in `#[rustc_regions]`");
+                    continue;
+                }
+            };
+
+            match &*name.as_str() {
+                "defining_ty" if item.is_word() => sections.defining_ty = true,
+                "universal" if item.is_word() => sections.universal = true,
+                "requirements" if item.is_word() => sections.requirements = true,
+                "named" if item.is_word() => sections.named = true,
+                "values" if item.is_word() => sections.values = Some(vec![]),
+                "values" if item.is_meta_item_list() => {
+                    let mut filter = vec![];
+                    for region in item.meta_item_list().unwrap() {
+                        match region.literal().map(|lit| &lit.node) {
+                            Some(&ast::LitKind::Str(name, _)) => {
+                                filter.push(name.as_str().to_string())
+                            }
+                            _ => sess.span_err(
+                                region.span(),
+                                "expected a region string, like `\"'a\"` or `\"'_#3r\"`",
+                            ),
+                        }
+                    }
+                    sections.values = Some(filter);
+                }
+                name if Self::SECTION_NAMES.contains(&name) => {
+                    sess.span_err(
+                        item.span(),
+                        &format!("malformed `{}` section in `#[rustc_regions]`", name),
+                    );
+                }
+                name => {
+                    sess.struct_span_err(
+                        item.span(),
+                        &format!("unknown `#[rustc_regions]` section `{}`", name),
+                    ).help(&format!(
+                        "the known sections are {}",
+                        Self::SECTION_NAMES
+                            .iter()
+                            .map(|name| format!("`{}`", name))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )).emit();
+                }
+            }
+        }
+
+        sections
+    }
+}
+
+/// When the enclosing function is tagged with
+/// `#[rustc_universal_regions]`, emit a note describing the universal
+/// regions of each MIR in the item, as well as the requirements (if
+/// any) that a closure propagates to its creator.
+fn dump_universal_regions<'a, 'gcx, 'tcx>(
+    infcx: &InferCtxt<'a, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+    regioncx: &RegionInferenceContext,
+    universal_regions_summary: &UniversalRegionsSummary,
+    closure_region_requirements: &Option<ClosureRegionRequirements<'gcx>>,
+) {
+    let mut err = infcx
+        .tcx
+        .sess
+        .diagnostic()
+        .span_note_diag(mir.span, "Universal regions");
+
+    regioncx.annotate(&mut err);
+    regioncx.annotate_universal_regions(universal_regions_summary, &mut err);
+
+    if let Some(closure_region_requirements) = closure_region_requirements {
         for_each_region_constraint(closure_region_requirements, &mut |msg| {
-            err.note(msg);
+            err.note(&format!("propagated to creator: {}", msg));
             Ok(())
         }).unwrap();
-
-        err.emit();
-    } else {
-        let mut err = tcx.sess
-            .diagnostic()
-            .span_note_diag(mir.span, "No external requirements");
-        regioncx.annotate(&mut err);
-        err.emit();
     }
+
+    err.emit();
 }
 
 fn for_each_region_constraint(
     closure_region_requirements: &ClosureRegionRequirements,
     with_msg: &mut FnMut(&str) -> io::Result<()>,
 ) -> io::Result<()> {
-    for req in &closure_region_requirements.outlives_requirements {
+    for req in closure_region_requirements.sorted_outlives_requirements() {
         with_msg(&format!(
             "where {:?}: {:?}",
-            req.free_region,
+            req.subject,
             req.outlived_free_region,
         ))?;
     }
@@ -311,6 +714,53 @@ impl ToRegionVid for RegionKind {
     }
 }
 
+/// True if folding the regions of `value` might change it, i.e., if
+/// it mentions any region that is not bound within it. The type flags
+/// record no `'static` or erased regions, so a value without any
+/// region flags must still be visited for those; that is much cheaper
+/// than folding it, though, as nothing gets interned.
+fn has_regions_to_fold<'tcx, T>(tcx: TyCtxt<'_, '_, 'tcx>, value: &T) -> bool
+where
+    T: TypeFoldable<'tcx>,
+{
+    let region_flags = TypeFlags::HAS_RE_INFER
+        | TypeFlags::HAS_RE_SKOL
+        | TypeFlags::HAS_RE_EARLY_BOUND
+        | TypeFlags::HAS_FREE_REGIONS;
+    if value.has_type_flags(region_flags) || value.has_escaping_regions() {
+        return true;
+    }
+
+    let mut has_regions = false;
+    tcx.for_each_free_region(value, |_| has_regions = true);
+    has_regions
+}
+
+/// Like `TyCtxt::fold_regions`, but returns `value` as is if it has
+/// no regions to fold (see `has_regions_to_fold`). With
+/// `-Z nll-verify-fold-fast-path`, debug builds fold such values all
+/// the same, to check that `f` would indeed never have been called.
+fn fold_regions_unless_region_free<'tcx, T, F>(
+    tcx: TyCtxt<'_, '_, 'tcx>,
+    value: &T,
+    mut f: F,
+) -> T
+where
+    T: TypeFoldable<'tcx>,
+    F: FnMut(ty::Region<'tcx>, u32) -> ty::Region<'tcx>,
+{
+    if has_regions_to_fold(tcx, value) {
+        return tcx.fold_regions(value, &mut false, f);
+    }
+
+    if cfg!(debug_assertions) && tcx.sess.opts.debugging_opts.nll_verify_fold_fast_path {
+        tcx.fold_regions(value, &mut false, |region, _| {
+            bug!("fold fast path skipped region `{:?}` in `{:?}`", region, value)
+        });
+    }
+    value.clone()
+}
+
 fn live_variable_set(regular: &LocalSet, drops: &LocalSet) -> String {
     // sort and deduplicate:
     let all_locals: BTreeSet<_> = regular.iter().chain(drops.iter()).collect();
@@ -0,0 +1,313 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `redundant_lifetime_bounds` lint. A declared bound `'a: 'b`
+//! is redundant if `'a: 'b` still follows from the other outlives
+//! facts of the body -- the remaining where-clauses and the bounds
+//! implied by the argument and return types -- once it is left out.
+//! The universal regions record each of those facts, so we can check
+//! this precisely with a walk over them.
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::hir::map as hir_map;
+use rustc::lint;
+use rustc::lint::builtin::REDUNDANT_LIFETIME_BOUNDS;
+use rustc::ty::{self, RegionVid, TyCtxt};
+use rustc::util::nodemap::FxHashMap;
+use std::collections::VecDeque;
+use std::iter;
+use syntax::ast;
+use syntax::codemap::CodeMap;
+use syntax_pos::{BytePos, Span};
+
+use super::universal_regions::{RegionBoundPairOrigin, UniversalRegions};
+
+/// Lints each lifetime bound declared on the item `mir_def_id` that
+/// is implied by the other outlives facts in `universal_regions`.
+pub(super) fn lint_redundant_bounds<'tcx>(
+    tcx: TyCtxt<'_, '_, 'tcx>,
+    mir_def_id: DefId,
+    universal_regions: &UniversalRegions<'tcx>,
+) {
+    // A closure has no generics of its own; the bounds it sees are
+    // those of the enclosing item, which is linted by itself.
+    if tcx.is_closure(mir_def_id) {
+        return;
+    }
+
+    let node_id = tcx.hir.as_local_node_id(mir_def_id).unwrap();
+    if tcx.lint_level_at_node(REDUNDANT_LIFETIME_BOUNDS, node_id).0 == lint::Allow {
+        return;
+    }
+
+    let generics = match item_generics(tcx, node_id) {
+        Some(generics) => generics,
+        None => return,
+    };
+
+    // `'static` is not a lifetime parameter, but `'a: 'static` is a
+    // bound all the same, so it gets a name here too.
+    let names: FxHashMap<RegionVid, ty::Region<'tcx>> =
+        iter::once(universal_regions.static_region())
+            .chain(universal_regions.named_universal_regions())
+            .map(|(r, vid, _)| (vid, r))
+            .collect();
+    let region_vid = |lifetime: &hir::Lifetime| match lifetime.name {
+        hir::LifetimeName::Static => Some(universal_regions.fr_static),
+        hir::LifetimeName::Name(name) => names
+            .iter()
+            .find(|&(_, r)| match **r {
+                ty::ReEarlyBound(data) => data.name == name,
+                _ => false,
+            })
+            .map(|(&vid, _)| vid),
+        _ => None,
+    };
+    let region_name = |vid: RegionVid| match names.get(&vid) {
+        Some(r) => format!("{}", r),
+        None => format!("{:?}", vid),
+    };
+
+    // The bounds declared with the lifetime parameters (`<'a: 'b>`)
+    // and those in the where-clause (`where 'a: 'b`).
+    let inline_bounds = generics.lifetimes.iter().flat_map(|def| {
+        (0..def.bounds.len()).map(move |index| DeclaredBound {
+            longer: &def.lifetime,
+            bounds: &def.bounds,
+            index,
+            predicate: None,
+        })
+    });
+    let where_bounds = generics
+        .where_clause
+        .predicates
+        .iter()
+        .enumerate()
+        .filter_map(|(i, predicate)| match *predicate {
+            hir::WherePredicate::RegionPredicate(ref predicate) => Some((i, predicate)),
+            _ => None,
+        })
+        .flat_map(|(i, predicate)| {
+            (0..predicate.bounds.len()).map(move |index| DeclaredBound {
+                longer: &predicate.lifetime,
+                bounds: &predicate.bounds,
+                index,
+                predicate: Some(i),
+            })
+        });
+
+    for declared in inline_bounds.chain(where_bounds) {
+        let (longer, shorter) = (declared.longer, &declared.bounds[declared.index]);
+        let (sup, sub) = match (region_vid(longer), region_vid(shorter)) {
+            (Some(sup), Some(sub)) => (sup, sub),
+            _ => continue,
+        };
+
+        // Leave out the fact that this bound contributed. If it is
+        // not there, the bound did not make it into the param env
+        // (e.g., because of an error), and we have nothing to say.
+        let declared_fact = universal_regions
+            .outlives_facts
+            .iter()
+            .position(|fact| {
+                fact.sup == sup && fact.sub == sub && match fact.origin {
+                    RegionBoundPairOrigin::FromWhereClause(_) => true,
+                    _ => false,
+                }
+            });
+        let declared_fact = match declared_fact {
+            Some(declared_fact) => declared_fact,
+            None => continue,
+        };
+
+        let path = match implying_path(universal_regions, sup, sub, declared_fact) {
+            Some(path) => path,
+            None => continue,
+        };
+
+        let mut reasons: Vec<String> = vec![];
+        for step in path {
+            let reason = match step {
+                Some(index) => {
+                    let fact = &universal_regions.outlives_facts[index];
+                    match fact.origin {
+                        RegionBoundPairOrigin::FromWhereClause(_) => format!(
+                            "the bound `{}: {}`",
+                            region_name(fact.sup),
+                            region_name(fact.sub),
+                        ),
+                        RegionBoundPairOrigin::ImpliedByInput(index) => {
+                            format!("the type of argument {}", index + 1)
+                        }
+                        RegionBoundPairOrigin::ImpliedByOutput => String::from("the return type"),
+                        RegionBoundPairOrigin::ImpliedByUpvar(index) => {
+                            format!("the type of captured variable {}", index + 1)
+                        }
+                    }
+                }
+                None => String::from("`'static` outliving every lifetime"),
+            };
+            if !reasons.contains(&reason) {
+                reasons.push(reason);
+            }
+        }
+        let reasons = match reasons.split_last() {
+            None => String::from("every lifetime outliving itself"),
+            Some((last, rest)) if rest.is_empty() => last.clone(),
+            Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+        };
+
+        let span = longer.span.to(shorter.span);
+        let mut err = tcx.struct_span_lint_node(
+            REDUNDANT_LIFETIME_BOUNDS,
+            node_id,
+            span,
+            "this lifetime bound is implied by other bounds and can be removed",
+        );
+        err.note(&format!(
+            "`{}: {}` is implied by {}",
+            region_name(sup),
+            region_name(sub),
+            reasons,
+        ));
+        let suggestion = removal_suggestion(tcx.sess.codemap(), generics, &declared);
+        if let Some((span, replacement)) = suggestion {
+            err.span_suggestion(span, "remove this bound", replacement);
+        }
+        err.emit();
+    }
+}
+
+/// The bound `bounds[index]` of the lifetime `longer`, as declared
+/// either with the lifetime parameter or in the where-clause, where it
+/// is part of the predicate at index `predicate`.
+struct DeclaredBound<'hir> {
+    longer: &'hir hir::Lifetime,
+    bounds: &'hir [hir::Lifetime],
+    index: usize,
+    predicate: Option<usize>,
+}
+
+/// How to remove `declared` from the source: the span to replace and
+/// its replacement. If other bounds remain, or for a lifetime
+/// parameter, we rewrite the declaration without the bound;
+/// otherwise, we drop the whole where-clause predicate, along with
+/// the comma that follows it or else the one before it. The only
+/// predicate of a where-clause without a trailing comma is left
+/// alone, as removing it would leave a bare `where`.
+fn removal_suggestion(
+    codemap: &CodeMap,
+    generics: &hir::Generics,
+    declared: &DeclaredBound<'_>,
+) -> Option<(Span, String)> {
+    let longer = declared.longer;
+    let last = declared.bounds.last().unwrap();
+    if declared.bounds.len() > 1 || declared.predicate.is_none() {
+        let remaining: Vec<String> = declared
+            .bounds
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != declared.index)
+            .map(|(_, bound)| bound.name.name().to_string())
+            .collect();
+        let replacement = if remaining.is_empty() {
+            longer.name.name().to_string()
+        } else {
+            format!("{}: {}", longer.name.name(), remaining.join(" + "))
+        };
+        return Some((longer.span.to(last.span), replacement));
+    }
+
+    let predicates = &generics.where_clause.predicates;
+    let i = declared.predicate.unwrap();
+    let span = longer.span.to(last.span);
+    let with_comma = span.with_hi(span.hi() + BytePos(1));
+    if codemap.span_to_snippet(with_comma).map_or(false, |s| s.ends_with(',')) {
+        Some((with_comma, String::new()))
+    } else if i > 0 {
+        let previous = where_predicate_span(&predicates[i - 1]);
+        Some((previous.between(span).to(span), String::new()))
+    } else {
+        None
+    }
+}
+
+fn where_predicate_span(predicate: &hir::WherePredicate) -> Span {
+    match *predicate {
+        hir::WherePredicate::BoundPredicate(ref predicate) => predicate.span,
+        hir::WherePredicate::RegionPredicate(ref predicate) => predicate.span,
+        hir::WherePredicate::EqPredicate(ref predicate) => predicate.span,
+    }
+}
+
+/// Searches for a chain of outlives facts from `sup` to `sub` that
+/// does not use the fact at index `excluded`. Returns the steps of
+/// the chain: the index of each fact used, or `None` for a step that
+/// relies on `'static` outliving every region.
+fn implying_path(
+    universal_regions: &UniversalRegions<'_>,
+    sup: RegionVid,
+    sub: RegionVid,
+    excluded: usize,
+) -> Option<Vec<Option<usize>>> {
+    let facts = &universal_regions.outlives_facts;
+    let fr_static = universal_regions.fr_static;
+
+    let mut predecessors: FxHashMap<RegionVid, (RegionVid, Option<usize>)> = FxHashMap();
+    let mut queue = VecDeque::new();
+    queue.push_back(sup);
+    while let Some(fr) = queue.pop_front() {
+        if fr == sub {
+            let mut path = vec![];
+            let mut fr = sub;
+            while fr != sup {
+                let (predecessor, step) = predecessors[&fr];
+                path.push(step);
+                fr = predecessor;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let static_successors = universal_regions
+            .universal_regions()
+            .filter(|_| fr == fr_static)
+            .map(|r| (r, None));
+        let successors = facts
+            .iter()
+            .enumerate()
+            .filter(|&(index, fact)| index != excluded && fact.sup == fr)
+            .map(|(index, fact)| (fact.sub, Some(index)))
+            .chain(static_successors);
+        for (successor, step) in successors {
+            if successor != sup && !predecessors.contains_key(&successor) {
+                predecessors.insert(successor, (fr, step));
+                queue.push_back(successor);
+            }
+        }
+    }
+
+    None
+}
+
+/// The generics of the item, trait item or impl item `node_id`, if
+/// it has any.
+pub(super) fn item_generics<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    node_id: ast::NodeId,
+) -> Option<&'gcx hir::Generics> {
+    match tcx.hir.get(node_id) {
+        hir_map::NodeItem(item) => item.node.generics(),
+        hir_map::NodeTraitItem(item) => Some(&item.generics),
+        hir_map::NodeImplItem(item) => Some(&item.generics),
+        _ => None,
+    }
+}
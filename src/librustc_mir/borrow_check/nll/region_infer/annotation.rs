@@ -15,6 +15,7 @@
 //! handle the part about dumping the inference context internal
 //! state.
 
+use rustc::mir::UniversalRegionsSummary;
 use rustc::ty;
 use rustc_errors::DiagnosticBuilder;
 use super::RegionInferenceContext;
@@ -45,4 +46,57 @@ impl<'gcx, 'tcx> RegionInferenceContext<'tcx> {
             }
         }
     }
+
+    /// Describe the universal regions of this MIR: how many there are
+    /// of each classification and the outlives relations that are
+    /// known to hold among them. Used by `#[rustc_universal_regions]`.
+    /// This describes `summary`, which is what the
+    /// `universal_regions_summary` query will hand out for this MIR.
+    pub(crate) fn annotate_universal_regions(
+        &self,
+        summary: &UniversalRegionsSummary,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        err.note(&format!(
+            "universal regions: {} global, {} external, {} local",
+            summary.first_extern_index,
+            summary.first_local_index - summary.first_extern_index,
+            summary.num_universals - summary.first_local_index
+        ));
+
+        // The summary lists the outlives pairs in sorted order, which
+        // keeps the output stable.
+        for &(fr, r) in &summary.outlives {
+            err.note(&format!("known outlives: {:?}: {:?}", fr, r));
+        }
+    }
+
+    /// Dump each region declared with a name, with where it was
+    /// declared. Used by `#[rustc_regions(named)]`.
+    pub(crate) fn annotate_named_universal_regions(&self, err: &mut DiagnosticBuilder<'_>) {
+        for (r, vid, origin) in self.universal_regions.named_universal_regions() {
+            err.note(&format!("named region {:?}: {} ({:?})", vid, r, origin));
+        }
+    }
+
+    /// Dump the inferred value of each region whose debug
+    /// representation (e.g., `'_#3r`) or name (e.g., `'a`) appears in
+    /// `filter`, or of every region if `filter` is empty. Used by
+    /// `#[rustc_regions(values)]`.
+    pub(crate) fn annotate_region_values(
+        &self,
+        err: &mut DiagnosticBuilder<'_>,
+        filter: &[String],
+    ) {
+        for r in self.regions() {
+            let vid_str = format!("{:?}", r);
+            let name_str = self.definitions[r].external_name.map(|name| format!("{}", name));
+            let selected = filter.is_empty() || filter.iter().any(|f| {
+                *f == vid_str || Some(f) == name_str.as_ref()
+            });
+            if selected {
+                err.note(&format!("value of {}: {}", vid_str, self.region_value_str(r)));
+            }
+        }
+    }
 }
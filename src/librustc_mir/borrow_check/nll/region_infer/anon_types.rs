@@ -0,0 +1,118 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The hidden type of an `impl Trait` return type may only capture
+//! the lifetimes that appear in its bounds. Once region inference is
+//! done, this module checks that the regions of each hidden type
+//! outlive the least of those bounds, and reports the universal
+//! regions that flow into the hidden type without doing so.
+
+use rustc::hir::def_id::DefId;
+use rustc::infer::InferCtxt;
+use rustc::ty::{self, RegionVid};
+
+use super::super::error_buffer;
+use super::{AnonTypeCapture, RegionInferenceContext};
+
+impl<'tcx> RegionInferenceContext<'tcx> {
+    /// Checks the regions captured by the hidden type of each `impl
+    /// Trait` (see `AnonTypeCapture`). A region `'h` of the hidden
+    /// type has to outlive the least bound `'b`; since every
+    /// universal region `'u` that flows into `'h` then has to outlive
+    /// `'b` as well, we report those for which `'u: 'b` is not known.
+    pub(super) fn check_anon_type_captures(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir_def_id: DefId,
+    ) {
+        let tcx = infcx.tcx;
+        for capture in &self.anon_type_captures {
+            // If the bounds do not have a least element, the type
+            // check of the `impl Trait` type already reported that.
+            let least_bound = match self.least_bound_region(&capture.bound_regions) {
+                Some(r) => r,
+                None => continue,
+            };
+
+            let mut hidden_regions = vec![];
+            tcx.for_each_free_region(&capture.hidden_ty, |r| {
+                if let ty::ReVar(vid) = *r {
+                    hidden_regions.push(vid);
+                }
+            });
+
+            for fr in self.universal_regions.universal_regions() {
+                if fr == self.universal_regions.fr_static
+                    || self.universal_regions.outlives(fr, least_bound)
+                {
+                    continue;
+                }
+
+                let dependencies = self.dependencies(fr);
+                let captured = hidden_regions
+                    .iter()
+                    .cloned()
+                    .find(|&h| h != fr && dependencies[h]);
+                if let Some(hidden_region) = captured {
+                    self.report_anon_type_capture(infcx, mir_def_id, capture, fr, hidden_region);
+                }
+            }
+        }
+    }
+
+    /// The region among `bound_regions` that all the others outlive,
+    /// or `'static` if there are no bounds. None if the bounds are
+    /// unrelated.
+    fn least_bound_region(&self, bound_regions: &[RegionVid]) -> Option<RegionVid> {
+        let mut least = self.universal_regions.fr_static;
+        for &r in bound_regions {
+            if self.universal_regions.outlives(r, least) {
+                continue;
+            } else if self.universal_regions.outlives(least, r) {
+                least = r;
+            } else {
+                return None;
+            }
+        }
+        Some(least)
+    }
+
+    fn report_anon_type_capture(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir_def_id: DefId,
+        capture: &AnonTypeCapture<'tcx>,
+        fr: RegionVid,
+        hidden_region: RegionVid,
+    ) {
+        let tcx = infcx.tcx;
+        let blame_span = self.blame_constraint(fr, hidden_region).span;
+        let fr_name = self.universal_regions.name_region(tcx, fr).name;
+
+        let mut err = struct_span_err!(
+            tcx.sess,
+            blame_span,
+            E0691,
+            "hidden type for `impl Trait` captures lifetime that does not appear in bounds"
+        );
+        err.span_label(
+            blame_span,
+            format!("the hidden type captures `{}` here", fr_name),
+        );
+        err.span_label(
+            tcx.def_span(capture.def_id),
+            format!("`{}` does not appear in the bounds of this `impl Trait`", fr_name),
+        );
+        if !self.label_anonymous_region(infcx, fr, &mut err) {
+            self.label_argument_mentioning(infcx, mir_def_id, fr, &mut err);
+        }
+        error_buffer::emit_region_error(tcx.sess, err);
+    }
+}
@@ -0,0 +1,168 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! With `-Znll-compare-log=PATH`, we append to `PATH`, for each MIR
+//! body, a canonical description of its region inference: the
+//! universal regions, the constraints, and the outcome of the final
+//! check of each universal region. The description leaves out what
+//! tends to change between compiler versions for reasons unrelated
+//! to region inference -- region vid numbering, absolute positions,
+//! MIR locations -- so that the logs that two compilers write for the
+//! same crate can be diffed mechanically, e.g. with
+//! `src/etc/nll-compare-logs.py`.
+
+use rustc::hir::def_id::DefId;
+use rustc::infer::InferCtxt;
+use rustc::mir::{Mir, StatementKind, TerminatorKind};
+use rustc::ty::RegionVid;
+use rustc::util::nodemap::FxHashMap;
+use rustc_data_structures::indexed_vec::Idx;
+use std::io::{self, Write};
+use syntax_pos::{Loc, Span};
+
+use super::{Constraint, RegionInferenceContext};
+
+/// Bumped whenever the format of the log changes, so that the
+/// comparison script can tell a format change from a difference in
+/// region inference.
+const FORMAT_VERSION: u32 = 2;
+
+/// Gives the regions of a body names that do not depend on how the
+/// region vids were numbered. The universal regions already come in
+/// a canonical order, so `'U<n>` is just the universal region with
+/// index `n`. The other regions are named `'R<n>` in the order in
+/// which they first show up in the (sorted) constraints.
+struct CanonicalNames {
+    num_universals: usize,
+    others: FxHashMap<RegionVid, usize>,
+}
+
+impl CanonicalNames {
+    fn name(&mut self, r: RegionVid) -> String {
+        if r.index() < self.num_universals {
+            format!("'U{}", r.index())
+        } else {
+            let next = self.others.len();
+            format!("'R{}", self.others.entry(r).or_insert(next))
+        }
+    }
+}
+
+impl<'tcx> RegionInferenceContext<'tcx> {
+    /// Writes the canonical description of this body (see the module
+    /// docs). Must be called after `solve`.
+    pub(crate) fn write_compare_log(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir: &Mir<'tcx>,
+        mir_def_id: DefId,
+        out: &mut Write,
+    ) -> io::Result<()> {
+        let codemap = infcx.tcx.sess.codemap();
+        let body_start = codemap.lookup_char_pos(mir.span.lo());
+        let position = |span: Span| {
+            relative_position(&body_start, codemap.lookup_char_pos(span.lo()))
+        };
+
+        writeln!(out, "body {}", infcx.tcx.item_path_str(mir_def_id))?;
+        writeln!(
+            out,
+            "version {} (format {})",
+            option_env!("CFG_VERSION").unwrap_or("unknown version"),
+            FORMAT_VERSION
+        )?;
+
+        let mut names = CanonicalNames {
+            num_universals: self.universal_regions.len(),
+            others: FxHashMap(),
+        };
+
+        writeln!(out, "section universal-regions")?;
+        for fr in self.universal_regions.universal_regions() {
+            writeln!(
+                out,
+                "{} {} {:?}",
+                names.name(fr),
+                self.region_name(fr),
+                self.universal_regions.region_classification(fr).unwrap()
+            )?;
+        }
+
+        // Sort the constraints by what does not depend on the region
+        // vids before naming the regions, so that the names come out
+        // the same no matter how the vids were numbered.
+        let mut constraints: Vec<(String, &'static str, &Constraint)> = self.constraints
+            .iter()
+            .map(|constraint| {
+                (position(constraint.span), constraint_category(mir, constraint), constraint)
+            })
+            .collect();
+        constraints.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+        let mut constraint_lines = vec![];
+        for &(ref position, category, constraint) in &constraints {
+            let sup = names.name(constraint.sup);
+            let sub = names.name(constraint.sub);
+            constraint_lines.push(format!("{}: {} at {} ({})", sup, sub, position, category));
+        }
+        constraint_lines.sort();
+        constraint_lines.dedup();
+
+        writeln!(out, "section constraints")?;
+        for line in constraint_lines {
+            writeln!(out, "{}", line)?;
+        }
+
+        writeln!(out, "section outcomes")?;
+        for fr in self.universal_regions.universal_regions() {
+            let mut offending: Vec<String> = self.offending_universal_regions(fr)
+                .into_iter()
+                .map(|r| names.name(r))
+                .collect();
+            if offending.is_empty() {
+                writeln!(out, "{} ok", names.name(fr))?;
+            } else {
+                offending.sort();
+                writeln!(out, "{} error: {}", names.name(fr), offending.join(", "))?;
+            }
+        }
+
+        writeln!(out, "end body")
+    }
+}
+
+/// Describes the position `loc` as `+<lines>:<column>`, relative to
+/// the first line of the body. Positions in other files, such as
+/// those of macro definitions, are all the same to us.
+fn relative_position(body_start: &Loc, loc: Loc) -> String {
+    if loc.file.name != body_start.file.name || loc.line < body_start.line {
+        return String::from("elsewhere");
+    }
+    format!("+{}:{}", loc.line - body_start.line, loc.col.0 + 1)
+}
+
+/// A coarse description of the kind of MIR statement that gave rise
+/// to `constraint`.
+fn constraint_category(mir: &Mir, constraint: &Constraint) -> &'static str {
+    if constraint.closure_blame_span.is_some() {
+        return "closure";
+    }
+    let block_data = &mir[constraint.point.block];
+    match block_data.statements.get(constraint.point.statement_index) {
+        Some(statement) => match statement.kind {
+            StatementKind::Assign(..) => "assignment",
+            _ => "statement",
+        },
+        None => match block_data.terminator().kind {
+            TerminatorKind::Call { .. } => "call",
+            TerminatorKind::Return => "return",
+            _ => "terminator",
+        },
+    }
+}
@@ -29,7 +29,8 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         for region in self.regions() {
             if self.definitions[region].is_universal {
                 let classification = self.universal_regions.region_classification(region).unwrap();
-                let outlived_by = self.universal_regions.regions_outlived_by(region);
+                let outlived_by: Vec<_> =
+                    self.universal_regions.regions_outlived_by(region).collect();
                 writeln!(
                     out,
                     "| {r:rw$} | {c:cw$} | {ob}",
@@ -84,6 +85,8 @@ impl<'tcx> RegionInferenceContext<'tcx> {
                 sub,
                 point,
                 span,
+                closure_blame_span: _,
+                declared_type: _,
             } = constraint;
             with_msg(&format!(
                 "{:?}: {:?} @ {:?} due to {:?}",
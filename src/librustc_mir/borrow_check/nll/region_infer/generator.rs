@@ -0,0 +1,143 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A generator keeps the locals that are live across a `yield` in its
+//! interior, and the types of those have to be expressible in terms
+//! of the generator type. A borrow of data that the generator body
+//! itself owns, held across a `yield`, has a region that is local to
+//! the body: no universal region of the generator can name it. Left
+//! alone, such a region would only trip assertions once the generator
+//! layout is computed, so we report it here instead.
+
+use rustc::infer::InferCtxt;
+use rustc::mir::{Local, LocalKind, Location, Mir, Place, ProjectionElem, Rvalue};
+use rustc::mir::{StatementKind, TerminatorKind};
+use rustc::ty::RegionVid;
+use rustc_data_structures::fx::FxHashSet;
+use syntax_pos::Span;
+
+use super::super::error_buffer;
+use super::super::ToRegionVid;
+use super::values::RegionElement;
+use super::RegionInferenceContext;
+
+/// A borrow, in the generator body, of data the body owns.
+struct LocalBorrow {
+    region: RegionVid,
+    span: Span,
+}
+
+impl<'tcx> RegionInferenceContext<'tcx> {
+    /// Checks that no borrow of data owned by the generator body is
+    /// held across a `yield`. A borrow is held across a `yield` if its
+    /// region contains the point where the generator resumes: that is
+    /// exactly when the region appears in the type of some local that
+    /// is live there, and hence in the generator interior.
+    ///
+    /// A borrow whose region has to outlive a universal region is
+    /// left to `check_universal_region`, which reports it already.
+    pub(super) fn check_generator_interior(&self, infcx: &InferCtxt<'_, '_, 'tcx>, mir: &Mir<'tcx>) {
+        if mir.yield_ty.is_none() {
+            return;
+        }
+
+        let inferred_values = self.inferred_values.as_ref().unwrap();
+        let borrows = local_borrows(mir);
+        if borrows.is_empty() {
+            return;
+        }
+
+        let mut reported = FxHashSet();
+        for (block, data) in mir.basic_blocks().iter_enumerated() {
+            let resume = match data.terminator().kind {
+                TerminatorKind::Yield { resume, .. } => resume,
+                _ => continue,
+            };
+            let yield_span = data.terminator().source_info.span;
+            let resume_point = Location {
+                block: resume,
+                statement_index: 0,
+            };
+
+            for borrow in &borrows {
+                if !inferred_values.contains(borrow.region, resume_point)
+                    || reported.contains(&borrow.region)
+                {
+                    continue;
+                }
+
+                let outlives_universal = inferred_values
+                    .elements_contained_in(borrow.region)
+                    .any(|element| match element {
+                        RegionElement::UniversalRegion(_) => true,
+                        RegionElement::Location(_) => false,
+                    });
+                if outlives_universal {
+                    continue;
+                }
+
+                debug!(
+                    "check_generator_interior: {:?} held across yield in {:?}",
+                    borrow.region,
+                    block
+                );
+                reported.insert(borrow.region);
+
+                let mut err = struct_span_err!(
+                    infcx.tcx.sess,
+                    borrow.span,
+                    E0626,
+                    "borrow may still be in use when the generator is suspended, \
+                     but the borrowed value does not live long enough"
+                );
+                err.span_label(borrow.span, "borrow occurs here");
+                err.span_label(yield_span, "possible yield occurs here");
+                error_buffer::emit_region_error(infcx.tcx.sess, err);
+            }
+        }
+    }
+}
+
+/// Collects the borrows of data that the body owns: those of a place
+/// based on a local (other than an argument, which for a generator
+/// holds its upvars) without going through a dereference.
+fn local_borrows(mir: &Mir<'_>) -> Vec<LocalBorrow> {
+    let mut borrows = vec![];
+    for data in mir.basic_blocks() {
+        for statement in &data.statements {
+            if let StatementKind::Assign(_, Rvalue::Ref(region, _, ref place)) = statement.kind {
+                let is_local = match owning_local(place) {
+                    Some(local) => mir.local_kind(local) != LocalKind::Arg,
+                    None => false,
+                };
+                if is_local {
+                    borrows.push(LocalBorrow {
+                        region: region.to_region_vid(),
+                        span: statement.source_info.span,
+                    });
+                }
+            }
+        }
+    }
+    borrows
+}
+
+/// The local that `place` is a part of, if it does not go through a
+/// dereference.
+fn owning_local(place: &Place<'_>) -> Option<Local> {
+    match *place {
+        Place::Local(local) => Some(local),
+        Place::Static(_) => None,
+        Place::Projection(ref proj) => match proj.elem {
+            ProjectionElem::Deref => None,
+            _ => owning_local(&proj.base),
+        },
+    }
+}
@@ -8,26 +8,50 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use super::universal_regions::UniversalRegions;
+use super::delayed_bugs::delay_nll_bug;
+use super::error_buffer;
+use super::universal_regions::{NameOrigin, UniversalRegions};
+use super::ToRegionVid;
+use rustc::hir;
 use rustc::hir::def_id::DefId;
+use rustc::hir::map as hir_map;
 use rustc::infer::InferCtxt;
 use rustc::infer::NLLRegionVariableOrigin;
+use rustc::infer::RegionObligation;
 use rustc::infer::RegionVariableOrigin;
 use rustc::infer::SubregionOrigin;
-use rustc::infer::region_constraints::VarOrigins;
-use rustc::mir::{ClosureOutlivesRequirement, ClosureRegionRequirements, Location, Mir};
-use rustc::ty::{self, RegionVid};
+use rustc::infer::region_constraints::{GenericKind, VarOrigins};
+use rustc::lint::builtin::UNSAFE_REGION_ERRORS;
+use rustc::mir::{ClosureOutlivesRequirement, ClosureOutlivesSubject, ClosureRegionRequirements};
+use rustc::mir::{Location, Mir, Place, ProjectionElem, StatementKind};
+use rustc::traits::{self, ObligationCause};
+use rustc::ty::{self, RegionVid, Ty};
+use rustc::ty::subst::Subst;
 use rustc_data_structures::indexed_vec::IndexVec;
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::DiagnosticBuilder;
 use std::fmt;
 use std::rc::Rc;
+use syntax::ast;
 use syntax_pos::Span;
+use transform::type_check::DeclaredType;
+use util::try_desugar::{question_mark_span, try_desugar_call, TryDesugarCall};
 
 mod annotation;
+mod anon_types;
+mod compare_log;
 mod dump_mir;
+mod generator;
 mod graphviz;
+mod object_lifetime_default;
+mod signature_borrows;
+mod suggest_bound;
+mod universal_causes;
+mod unsafe_blocks;
 mod values;
-use self::values::{RegionValueElements, RegionValues};
+use self::universal_causes::UniversalCause;
+use self::unsafe_blocks::UnsafeBlockSpans;
+use self::values::{RegionElement, RegionValueElements, RegionValues};
 
 pub struct RegionInferenceContext<'tcx> {
     /// Contains the definition for every region variable.  Region
@@ -55,6 +79,23 @@ pub struct RegionInferenceContext<'tcx> {
     /// Information about the universally quantified regions in scope
     /// on this function and their (known) relations to one another.
     universal_regions: UniversalRegions<'tcx>,
+
+    /// For each universal region, the facts that make it observable
+    /// in the body. These do not affect inference (universal regions
+    /// are live everywhere); they are only used to explain errors.
+    universal_causes: FxHashMap<RegionVid, Vec<UniversalCause<'tcx>>>,
+
+    /// The "type tests" we must check once the values are inferred:
+    /// requirements that some generic type outlives a region.
+    type_tests: Vec<TypeTest<'tcx>>,
+
+    /// The hidden types of the `impl Trait`s in the return type,
+    /// whose captures we must check once the values are inferred.
+    anon_type_captures: Vec<AnonTypeCapture<'tcx>>,
+
+    /// False if `-Z nll-only` filters out this body, in which case
+    /// `-Z nll-dump-cause` does not apply to it.
+    dumps_enabled: bool,
 }
 
 struct RegionDefinition<'tcx> {
@@ -70,8 +111,9 @@ struct RegionDefinition<'tcx> {
     /// course is also in the function signature).
     is_universal: bool,
 
-    /// If this is 'static or an early-bound region, then this is
-    /// `Some(X)` where `X` is the name of the region.
+    /// If this is 'static, an early-bound region or a late-bound
+    /// region declared with a name, then this is `Some(X)` where `X`
+    /// is the name of the region.
     external_name: Option<ty::Region<'tcx>>,
 }
 
@@ -92,6 +134,120 @@ pub struct Constraint {
 
     /// Where did this constraint arise?
     span: Span,
+
+    /// If this constraint was imposed by the region requirements of
+    /// a closure created in this MIR, the span of the statement
+    /// within the closure body that gave rise to the requirement.
+    closure_blame_span: Option<Span>,
+
+    /// If this constraint relates a value to a type declared outside
+    /// of the body, which one (see `label_object_lifetime_default`).
+    declared_type: Option<DeclaredType>,
+}
+
+/// A "type test" corresponds to an outlives constraint between a type
+/// and a lifetime, like `T: 'x` or `<T as Foo>::Bar: 'x`. They are
+/// translated from the `Verify` region constraints in the ordinary
+/// inference context.
+///
+/// These sorts of constraints are handled differently than ordinary
+/// constraints, at least at present. During type checking, the
+/// `InferCtxt::process_registered_region_obligations` method will
+/// attempt to convert a type test like `T: 'x` into an ordinary
+/// outlives constraint when possible (for example, `&'a T: 'b` will
+/// be converted into `'a: 'b` and registered as a `Constraint`).
+///
+/// In some cases, however, there are outlives relationships that are
+/// not converted into a region constraint, but rather into one of
+/// these "type tests". The distinction is that a type test does not
+/// influence the inference result, but instead just examines the
+/// values that we ultimately inferred for each region variable and
+/// checks that they meet certain extra criteria. If not, an error
+/// can be issued.
+///
+/// One reason for this is that these type tests typically boil down
+/// to a check like `'a: 'x` where `'a` is a universally quantified
+/// region -- and therefore not one whose value is really meant to be
+/// *inferred*, precisely (this is not always the case: one can have
+/// a type test like `<Foo as Trait<'?0>>::Bar: 'x`, where `'?0` is an
+/// inference variable). Another reason is that these type tests can
+/// involve *disjunction* -- that is, they can be satisfied in more
+/// than one way.
+#[derive(Clone, Debug)]
+pub struct TypeTest<'tcx> {
+    /// The type `T` that must outlive the region.
+    pub generic_kind: GenericKind<'tcx>,
+
+    /// The region `'x` that the type must outlive.
+    pub lower_bound: RegionVid,
+
+    /// The point where the outlives relation must hold.
+    pub point: Location,
+
+    /// Where did this constraint arise?
+    pub span: Span,
+
+    /// If this test was imposed by the region requirements of a
+    /// closure created in this MIR, the span of the statement within
+    /// the closure body that gave rise to the requirement.
+    pub closure_blame_span: Option<Span>,
+
+    /// A test which, if met by the region `'x`, proves that this type
+    /// constraint is satisfied.
+    pub test: RegionTest,
+}
+
+/// A "test" that can be applied to some "subject region" `'x`. These are used to
+/// describe type constraints. Tests do not presently affect the
+/// region values that get inferred for each variable; they only
+/// examine the results *after* inference.  This means they can
+/// conveniently include disjunction ("a or b must be true").
+#[derive(Clone, Debug)]
+pub enum RegionTest {
+    /// The subject region `'x` must be outlived by *some* region in
+    /// the given set of regions.
+    ///
+    /// This test comes from e.g. a where clause like `T: 'a + 'b`,
+    /// which implies that we know that `T: 'a` and that `T:
+    /// 'b`. Therefore, if we are trying to prove that `T: 'x`, we can
+    /// do so by showing that `'a: 'x` *or* `'b: 'x`.
+    IsOutlivedByAnyRegionIn(Vec<RegionVid>),
+
+    /// The subject region `'x` must be outlived by *all* regions in
+    /// the given set of regions.
+    ///
+    /// This test comes from e.g. a projection type like `T = <u32 as
+    /// Trait<'a, 'b>>::Foo`, which must outlive `'a` or `'b`, and
+    /// maybe both. Therefore we can prove that `T: 'x` if we know
+    /// that `'a: 'x` *and* `'b: 'x`.
+    IsOutlivedByAllRegionsIn(Vec<RegionVid>),
+
+    /// Any of the given tests are true.
+    ///
+    /// This arises from projections, for which there are multiple
+    /// ways to prove an outlives relationship.
+    Any(Vec<RegionTest>),
+
+    /// All of the given tests are true.
+    All(Vec<RegionTest>),
+}
+
+/// The hidden type of an `impl Trait` in the return type. Only the
+/// lifetimes that the `impl Trait` is bounded by (or, failing that,
+/// that it is given) may be captured by it: every region of the
+/// hidden type must outlive the least of `bound_regions` (or
+/// `'static`, if there are none). Like a type test, this does not
+/// influence inference; we only look at the result.
+#[derive(Clone, Debug)]
+pub struct AnonTypeCapture<'tcx> {
+    /// The `impl Trait` type.
+    pub def_id: DefId,
+
+    /// Its hidden type, in terms of the region variables of the MIR.
+    pub hidden_ty: Ty<'tcx>,
+
+    /// The universal regions that bound the hidden type.
+    pub bound_regions: Vec<RegionVid>,
 }
 
 impl<'tcx> RegionInferenceContext<'tcx> {
@@ -103,6 +259,7 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         var_origins: VarOrigins,
         universal_regions: UniversalRegions<'tcx>,
         mir: &Mir<'tcx>,
+        dumps_enabled: bool,
     ) -> Self {
         let num_region_variables = var_origins.len();
         let num_universal_regions = universal_regions.len();
@@ -121,7 +278,11 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             liveness_constraints: RegionValues::new(elements, num_region_variables),
             inferred_values: None,
             constraints: Vec::new(),
+            universal_causes: universal_causes::compute(mir, &universal_regions),
             universal_regions,
+            type_tests: Vec::new(),
+            anon_type_captures: Vec::new(),
+            dumps_enabled,
         };
 
         result.init_universal_regions();
@@ -151,7 +312,9 @@ impl<'tcx> RegionInferenceContext<'tcx> {
     /// outlives `'a` and hence contains R0 and R1.
     fn init_universal_regions(&mut self) {
         // Update the names (if any)
-        for (external_name, variable) in self.universal_regions.named_universal_regions() {
+        let (re_static, fr_static, _) = self.universal_regions.static_region();
+        self.definitions[fr_static].external_name = Some(re_static);
+        for (external_name, variable, _) in self.universal_regions.named_universal_regions() {
             self.definitions[variable].external_name = Some(external_name);
         }
 
@@ -173,6 +336,17 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             // Add `end(X)` into the set for X.
             self.liveness_constraints.add(variable, variable);
         }
+
+        // The placeholders for skolemized regions could be any region,
+        // so they too contain the entire CFG. They have no `end(X)` of
+        // their own, though: all we check is that they are not forced
+        // to outlive a universal region (see `check_placeholder_region`).
+        for variable in self.universal_regions.placeholder_regions() {
+            self.definitions[variable].is_universal = true;
+            for point_index in self.elements.all_point_indices() {
+                self.liveness_constraints.add(variable, point_index);
+            }
+        }
     }
 
     /// Returns an iterator over all the region indices.
@@ -188,6 +362,11 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         self.universal_regions.to_region_vid(r)
     }
 
+    /// Returns the universal regions in scope on the MIR.
+    pub(super) fn universal_regions(&self) -> &UniversalRegions<'tcx> {
+        &self.universal_regions
+    }
+
     /// Returns true if the region `r` contains the point `p`.
     ///
     /// Panics if called before `solve()` executes,
@@ -231,6 +410,8 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         sup: RegionVid,
         sub: RegionVid,
         point: Location,
+        closure_blame_span: Option<Span>,
+        declared_type: Option<DeclaredType>,
     ) {
         debug!("add_outlives({:?}: {:?} @ {:?}", sup, sub, point);
         assert!(self.inferred_values.is_none(), "values already inferred");
@@ -239,22 +420,54 @@ impl<'tcx> RegionInferenceContext<'tcx> {
             sup,
             sub,
             point,
+            closure_blame_span,
+            declared_type,
         });
     }
 
+    /// Add a "type test" that must be satisfied.
+    pub(super) fn add_type_test(&mut self, type_test: TypeTest<'tcx>) {
+        debug!("add_type_test({:?})", type_test);
+        assert!(self.inferred_values.is_none(), "values already inferred");
+        self.type_tests.push(type_test);
+    }
+
+    /// Add the hidden type of an `impl Trait`, whose captures must be
+    /// checked.
+    pub(super) fn add_anon_type_capture(&mut self, capture: AnonTypeCapture<'tcx>) {
+        debug!("add_anon_type_capture({:?})", capture);
+        assert!(self.inferred_values.is_none(), "values already inferred");
+        self.anon_type_captures.push(capture);
+    }
+
     /// Perform region inference.
-    pub(super) fn solve(
+    pub(super) fn solve<'gcx>(
         &mut self,
-        infcx: &InferCtxt<'_, '_, 'tcx>,
+        infcx: &InferCtxt<'_, 'gcx, 'tcx>,
         mir: &Mir<'tcx>,
         mir_def_id: DefId,
-    ) -> Option<ClosureRegionRequirements> {
+    ) -> Option<ClosureRegionRequirements<'gcx>> {
         assert!(self.inferred_values.is_none(), "values already inferred");
         let tcx = infcx.tcx;
 
         // Find the minimal regions that can solve the constraints. This is infallible.
         self.propagate_constraints(mir);
 
+        // If this is not a closure, then there is no caller to which we can
+        // "pass the buck". So if there are any outlives-requirements that are
+        // not satisfied, we just have to report a hard error for them.
+        let mut outlives_requirements = if tcx.is_closure(mir_def_id) {
+            Some(vec![])
+        } else {
+            None
+        };
+
+        // Now that we have the final region values, check that the
+        // types in the body outlive the regions they are required to.
+        self.check_type_tests(infcx, outlives_requirements.as_mut());
+        self.check_anon_type_captures(infcx, mir_def_id);
+        self.check_generator_interior(infcx, mir);
+
         // Now, see whether any of the constraints were too strong. In
         // particular, we want to check for a case where a universally
         // quantified region exceeded its bounds.  Consider:
@@ -277,124 +490,973 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         // Go through each of the universal regions `fr` and check that
         // they did not grow too large, accumulating any requirements
         // for our caller into the `outlives_requirements` vector.
-        let mut outlives_requirements = vec![];
         for (fr, _) in universal_definitions {
-            self.check_universal_region(infcx, fr, &mut outlives_requirements);
+            self.check_universal_region(
+                infcx,
+                mir,
+                mir_def_id,
+                fr,
+                outlives_requirements.as_mut(),
+            );
         }
 
-        // If this is not a closure, then there is no caller to which we can
-        // "pass the buck". So if there are any outlives-requirements that were
-        // not satisfied, we just have to report a hard error here.
-        if !tcx.is_closure(mir_def_id) {
-            for outlives_requirement in outlives_requirements {
-                self.report_error(
-                    infcx,
-                    outlives_requirement.free_region,
-                    outlives_requirement.outlived_free_region,
-                    outlives_requirement.blame_span,
-                );
-            }
-            return None;
+        // A placeholder that grew too large cannot be blamed on our
+        // creator, so those errors are always reported here.
+        for placeholder in self.universal_regions.placeholder_regions() {
+            self.check_placeholder_region(infcx, mir, mir_def_id, placeholder);
         }
 
+        let outlives_requirements = outlives_requirements?;
         let num_external_vids = self.universal_regions.num_global_and_external_regions();
 
-        Some(ClosureRegionRequirements {
+        // Our creator maps the regions of the requirements through
+        // the first `num_external_vids` regions of our closure type,
+        // so a local region here would silently stand for whatever
+        // region comes at its index there.
+        debug_assert!(
+            outlives_requirements.iter().all(|requirement| {
+                let nameable = |r| self.universal_regions.is_nameable_by_caller(r);
+                let subject_is_nameable = match requirement.subject {
+                    ClosureOutlivesSubject::Region(region) => nameable(region),
+                    ClosureOutlivesSubject::Ty(ty) => {
+                        let mut names_regions = false;
+                        tcx.for_each_free_region(&ty, |_| names_regions = true);
+                        !names_regions
+                    }
+                };
+                subject_is_nameable && nameable(requirement.outlived_free_region)
+            }),
+            "solve: a local region escaped into the closure requirements: {:?}",
+            outlives_requirements
+        );
+
+        let mut closure_region_requirements = ClosureRegionRequirements {
             num_external_vids,
             outlives_requirements,
-        })
+        };
+        closure_region_requirements.minimize();
+        Some(closure_region_requirements)
     }
 
     /// Check the final value for the free region `fr` to see if it
-    /// grew too large. In particular, examine what `end(X)` points
-    /// wound up in `fr`'s final value; for each `end(X)` where `X !=
-    /// fr`, we want to check that `fr: X`. If not, that's either an
-    /// error, or something we have to propagate to our creator.
+    /// grew too large. For each universal region `X` in `fr`'s value
+    /// that `fr` is not known to outlive (see
+    /// `offending_universal_regions`), we either report an error or
+    /// propagate the requirement `fr: X` to our creator.
     ///
     /// Things that are to be propagated are accumulated into the
-    /// `outlives_requirements` vector.
-    fn check_universal_region(
+    /// `outlives_requirements` vector. If that vector is `None`,
+    /// there is no creator to propagate to, and errors are reported
+    /// directly instead.
+    fn check_universal_region<'gcx>(
         &self,
-        infcx: &InferCtxt<'_, '_, 'tcx>,
+        infcx: &InferCtxt<'_, 'gcx, 'tcx>,
+        mir: &Mir<'tcx>,
+        mir_def_id: DefId,
         longer_fr: RegionVid,
-        outlives_requirements: &mut Vec<ClosureOutlivesRequirement>,
+        mut outlives_requirements: Option<&mut Vec<ClosureOutlivesRequirement<'gcx>>>,
     ) {
-        let inferred_values = self.inferred_values.as_ref().unwrap();
-
         debug!("check_universal_region(fr={:?})", longer_fr);
 
-        // Find every region `o` such that `fr: o`
-        // (because `fr` includes `end(o)`).
-        for shorter_fr in inferred_values.universal_regions_outlived_by(longer_fr) {
-            // If it is known that `fr: o`, carry on.
-            if self.universal_regions.outlives(longer_fr, shorter_fr) {
-                continue;
-            }
-
+        for shorter_fr in self.offending_universal_regions(longer_fr) {
             debug!(
-                "check_universal_region: fr={:?} does not outlive shorter_fr={:?}",
+                "check_universal_region: fr={:?} is not known to outlive {:?}",
                 longer_fr,
                 shorter_fr,
             );
 
-            let blame_span = self.blame_span(longer_fr, shorter_fr);
-
-            // Shrink `fr` until we find a non-local region (if we do).
-            // We'll call that `fr-` -- it's ever so slightly smaller than `fr`.
-            if let Some(fr_minus) = self.universal_regions.non_local_lower_bound(longer_fr) {
-                debug!("check_universal_region: fr_minus={:?}", fr_minus);
-
-                // Grow `shorter_fr` until we find a non-local
-                // regon. (We always will.)  We'll call that
-                // `shorter_fr+` -- it's ever so slightly larger than
-                // `fr`.
-                let shorter_fr_plus = self.universal_regions.non_local_upper_bound(shorter_fr);
-                debug!(
-                    "check_universal_region: shorter_fr_plus={:?}",
-                    shorter_fr_plus
-                );
-
-                // Push the constraint `fr-: shorter_fr+`
-                outlives_requirements.push(ClosureOutlivesRequirement {
-                    free_region: fr_minus,
-                    outlived_free_region: shorter_fr_plus,
-                    blame_span: blame_span,
-                });
-                return;
+            let blame_constraint = self.blame_constraint(longer_fr, shorter_fr);
+
+            if let Some(ref mut outlives_requirements) = outlives_requirements {
+                // Shrink `fr` until we find a non-local region (if we do).
+                // We'll call that `fr-` -- it's ever so slightly smaller than `fr`.
+                if let Some(fr_minus) = self.universal_regions.non_local_lower_bound(longer_fr) {
+                    debug!("check_universal_region: fr_minus={:?}", fr_minus);
+
+                    // Grow `shorter_fr` until we find a non-local
+                    // regon. (We always will.)  We'll call that
+                    // `shorter_fr+` -- it's ever so slightly larger than
+                    // `fr`.
+                    let shorter_fr_pluses = self.non_local_upper_bounds(shorter_fr);
+                    debug!(
+                        "check_universal_region: shorter_fr_pluses={:?}",
+                        shorter_fr_pluses
+                    );
+
+                    // Push the constraint `fr-: shorter_fr+` for each
+                    // `shorter_fr+`. If the constraint we blame was
+                    // itself imposed by a nested closure, blame the
+                    // statement inside that closure, since that is what
+                    // ultimately gave rise to it.
+                    //
+                    // Keep going afterwards: `fr` may have to outlive
+                    // several regions, e.g. one for a requirement of our
+                    // own and one that a nested closure imposed on us,
+                    // and our creator has to see all of them.
+                    for shorter_fr_plus in shorter_fr_pluses {
+                        debug_assert!(
+                            self.universal_regions.is_nameable_by_caller(fr_minus)
+                                && self.universal_regions.is_nameable_by_caller(shorter_fr_plus),
+                            "check_universal_region: requirement `{:?}: {:?}` names a local region",
+                            fr_minus,
+                            shorter_fr_plus
+                        );
+                        outlives_requirements.push(ClosureOutlivesRequirement {
+                            subject: ClosureOutlivesSubject::Region(fr_minus),
+                            outlived_free_region: shorter_fr_plus,
+                            blame_span: blame_constraint
+                                .closure_blame_span
+                                .unwrap_or(blame_constraint.span),
+                        });
+                    }
+                    continue;
+                }
             }
 
             // If we could not shrink `fr` to something smaller that
             // the external users care about, then we can't pass the
             // buck; just report an error.
-            self.report_error(infcx, longer_fr, shorter_fr, blame_span);
+            self.report_error(infcx, mir, mir_def_id, longer_fr, shorter_fr, blame_constraint);
+        }
+    }
+
+    /// Check the final value of `placeholder`, which stands for the
+    /// bound region of a higher-ranked obligation. As the obligation
+    /// must hold whatever that region is, the placeholder is not
+    /// known to outlive any universal region, and each one it was
+    /// forced to outlive is an error (this is what the leak check
+    /// does for the ordinary region inference).
+    fn check_placeholder_region(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir: &Mir<'tcx>,
+        mir_def_id: DefId,
+        placeholder: RegionVid,
+    ) {
+        debug!("check_placeholder_region(placeholder={:?})", placeholder);
+
+        for shorter_fr in self.offending_universal_regions(placeholder) {
+            let blame_constraint = self.blame_constraint(placeholder, shorter_fr);
+            self.report_error(
+                infcx,
+                mir,
+                mir_def_id,
+                placeholder,
+                shorter_fr,
+                blame_constraint,
+            );
         }
     }
 
+    /// Once regions have been propagated, this method is used to see
+    /// whether any of the type tests -- requirements that some
+    /// generic type outlives a region -- are violated, reporting an
+    /// error for each one that is.
+    ///
+    /// In a closure body, a failed test that our creator can state is
+    /// instead accumulated into `outlives_requirements`, the way
+    /// `check_universal_region` does for region requirements; see
+    /// `try_propagate_type_test`.
+    fn check_type_tests<'gcx>(
+        &self,
+        infcx: &InferCtxt<'_, 'gcx, 'tcx>,
+        mut outlives_requirements: Option<&mut Vec<ClosureOutlivesRequirement<'gcx>>>,
+    ) {
+        let inferred_values = self.inferred_values.as_ref().unwrap();
+
+        for type_test in &self.type_tests {
+            debug!("check_type_test: {:?}", type_test);
+
+            // Every type in scope of the body -- the generic
+            // parameters (including `Self` in a trait default
+            // method) and projections of them -- outlives the body
+            // itself. So if the lower bound did not grow to include
+            // any universal region, the test holds without
+            // consulting the bounds at all.
+            let lower_bound_is_local = inferred_values
+                .elements_contained_in(type_test.lower_bound)
+                .all(|element| match element {
+                    RegionElement::Location(_) => true,
+                    RegionElement::UniversalRegion(_) => false,
+                });
+            if lower_bound_is_local {
+                continue;
+            }
+
+            if self.eval_region_test(type_test.lower_bound, &type_test.test) {
+                continue;
+            }
+
+            if let Some(ref mut outlives_requirements) = outlives_requirements {
+                if self.try_propagate_type_test(infcx, type_test, outlives_requirements) {
+                    continue;
+                }
+            }
+
+            self.report_type_test_error(infcx, type_test);
+        }
+    }
+
+    /// Tries to pass the failed `type_test` (`T: 'x`) on to our
+    /// creator, pushing `T: 'X+` onto `outlives_requirements`
+    /// for each universal region `'X` in `'x`, where `'X+` is the
+    /// smallest region of our creator's that is known to outlive
+    /// `'X` (see `check_universal_region`). Proving those is enough
+    /// to prove `T: 'x`, though it may be more than is needed.
+    ///
+    /// Returns false if the test cannot be propagated and must be
+    /// reported here, namely if `T` names any regions: our creator
+    /// could not tell which of its own regions they stand for.
+    fn try_propagate_type_test<'gcx>(
+        &self,
+        infcx: &InferCtxt<'_, 'gcx, 'tcx>,
+        type_test: &TypeTest<'tcx>,
+        outlives_requirements: &mut Vec<ClosureOutlivesRequirement<'gcx>>,
+    ) -> bool {
+        let tcx = infcx.tcx;
+        let inferred_values = self.inferred_values.as_ref().unwrap();
+
+        // `has_free_regions` would miss region variables, as the type
+        // flags record them separately, so visit the regions instead.
+        let generic_ty = type_test.generic_kind.to_ty(tcx);
+        let mut names_regions = false;
+        tcx.for_each_free_region(&generic_ty, |_| names_regions = true);
+        if names_regions {
+            debug!("try_propagate_type_test: `{:?}` names regions", generic_ty);
+            return false;
+        }
+        let generic_ty = match tcx.lift_to_global(&generic_ty) {
+            Some(generic_ty) => generic_ty,
+            None => return false,
+        };
+
+        let mut requirements = vec![];
+        for element in inferred_values.elements_contained_in(type_test.lower_bound) {
+            let fr = match element {
+                RegionElement::UniversalRegion(fr) => fr,
+                RegionElement::Location(_) => continue,
+            };
+
+            for fr_plus in self.non_local_upper_bounds(fr) {
+                debug!("try_propagate_type_test: `{:?}: {:?}`", generic_ty, fr_plus);
+                requirements.push(ClosureOutlivesRequirement {
+                    subject: ClosureOutlivesSubject::Ty(generic_ty),
+                    outlived_free_region: fr_plus,
+                    blame_span: type_test.closure_blame_span.unwrap_or(type_test.span),
+                });
+            }
+        }
+
+        outlives_requirements.extend(requirements);
+        true
+    }
+
+    /// The non-local regions that a requirement on the local region
+    /// `fr` is handed to our creator as. Usually this is just
+    /// `non_local_upper_bound(fr)`. But if that is `'static` only
+    /// because there were several incomparable candidates, asking our
+    /// creator for `'static` would be too strong; the requirement is
+    /// handed on for each candidate instead, which together still
+    /// imply it for `fr`.
+    fn non_local_upper_bounds(&self, fr: RegionVid) -> Vec<RegionVid> {
+        let fr_plus = self.universal_regions.non_local_upper_bound(fr);
+        if fr_plus == self.universal_regions.fr_static {
+            let candidates = self.universal_regions.non_local_upper_bounds(fr);
+            if candidates.len() > 1 {
+                return candidates;
+            }
+        }
+        vec![fr_plus]
+    }
+
+    /// Test if `test` is true when applied to `lower_bound` at
+    /// the end of inference.
+    fn eval_region_test(&self, lower_bound: RegionVid, test: &RegionTest) -> bool {
+        debug!("eval_region_test(lower_bound={:?}, test={:?})", lower_bound, test);
+
+        match test {
+            RegionTest::IsOutlivedByAllRegionsIn(regions) => regions
+                .iter()
+                .all(|&r| self.eval_outlives(r, lower_bound)),
+
+            RegionTest::IsOutlivedByAnyRegionIn(regions) => regions
+                .iter()
+                .any(|&r| self.eval_outlives(r, lower_bound)),
+
+            RegionTest::Any(tests) => tests
+                .iter()
+                .any(|test| self.eval_region_test(lower_bound, test)),
+
+            RegionTest::All(tests) => tests
+                .iter()
+                .all(|test| self.eval_region_test(lower_bound, test)),
+        }
+    }
+
+    /// Evaluate whether `sup_region: sub_region` holds once inference
+    /// is done. Every point of `sub_region` must be in `sup_region`,
+    /// and every universal region in `sub_region` must either be in
+    /// `sup_region` too or be known to be outlived by it.
+    fn eval_outlives(&self, sup_region: RegionVid, sub_region: RegionVid) -> bool {
+        let inferred_values = self.inferred_values.as_ref().unwrap();
+        let relations = self.universal_regions.relations();
+
+        debug!(
+            "eval_outlives({:?}={:?}, {:?}={:?})",
+            sup_region,
+            inferred_values.region_value_str(sup_region),
+            sub_region,
+            inferred_values.region_value_str(sub_region),
+        );
+
+        inferred_values
+            .elements_contained_in(sub_region)
+            .all(|element| match element {
+                RegionElement::Location(point) => inferred_values.contains(sup_region, point),
+                RegionElement::UniversalRegion(fr) => {
+                    inferred_values.contains(sup_region, fr) || relations.outlives(sup_region, fr)
+                }
+            })
+    }
+
+    /// Reports that the type in `type_test` was required to outlive
+    /// a region that none of its known bounds covers.
+    fn report_type_test_error(&self, infcx: &InferCtxt<'_, '_, 'tcx>, type_test: &TypeTest<'tcx>) {
+        let inferred_values = self.inferred_values.as_ref().unwrap();
+        let mut err = infcx.tcx.sess.struct_span_err(
+            type_test.span,
+            &format!("the type `{}` may not live long enough", type_test.generic_kind),
+        );
+
+        let mut described = FxHashSet();
+        for element in inferred_values.elements_contained_in(type_test.lower_bound) {
+            if let RegionElement::UniversalRegion(fr) = element {
+                let description = self.describe_region(fr);
+                if described.insert(description.clone()) {
+                    err.note(&format!(
+                        "the type `{}` must outlive {}",
+                        type_test.generic_kind, description,
+                    ));
+                }
+            }
+        }
+
+        if let Some(closure_blame_span) = type_test.closure_blame_span {
+            err.span_label(
+                closure_blame_span,
+                "requirement occurs because of this borrow",
+            );
+        }
+
+        err.emit();
+    }
+
+    /// Returns each universal region `X` in the final value of the
+    /// universal region `fr` such that `fr: X` is neither declared
+    /// nor implied -- a missing bound. (Universal regions start out
+    /// containing every point in the CFG, so a borrow that lives too
+    /// long never shows up here; the borrow checker reports it.)
+    fn offending_universal_regions(&self, fr: RegionVid) -> Vec<RegionVid> {
+        let inferred_values = self.inferred_values.as_ref().unwrap();
+        let relations = self.universal_regions.relations();
+        inferred_values
+            .universal_regions_outlived_by(fr)
+            .filter(|&shorter_fr| !relations.outlives(fr, shorter_fr))
+            .collect()
+    }
+
     fn report_error(
         &self,
         infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir: &Mir<'tcx>,
+        mir_def_id: DefId,
         fr: RegionVid,
         outlived_fr: RegionVid,
-        blame_span: Span,
+        blame_constraint: &Constraint,
     ) {
         // Obviously uncool error reporting.
 
-        let fr_string = match self.definitions[fr].external_name {
-            Some(r) => format!("free region `{}`", r),
-            None => format!("free region `{:?}`", fr),
+        let fr_string = self.describe_region(fr);
+        let outlived_fr_string = self.describe_region(outlived_fr);
+
+        // Under `-Z nll-relaxed-unsafe`, errors that are entirely due
+        // to code within `unsafe` blocks are only linted against.
+        let tcx = infcx.tcx;
+        let within_unsafe = tcx.sess.opts.debugging_opts.nll_relaxed_unsafe && {
+            let unsafe_blocks = UnsafeBlockSpans::new(tcx, mir_def_id);
+            self.is_error_within_unsafe(&unsafe_blocks, fr, outlived_fr)
+        };
+        let escape_bound = if within_unsafe {
+            None
+        } else {
+            self.closure_escape_bound(infcx, mir_def_id, fr, outlived_fr)
+        };
+
+        // The code generated for `?` all has the span of the whole
+        // `expr?`; if the conversion of its error is to blame, point
+        // at the `?` itself.
+        let conversion_span = self.try_conversion_span(infcx, mir, blame_constraint);
+        let span = conversion_span.unwrap_or(blame_constraint.span);
+
+        let mut err = if within_unsafe {
+            let mir_node_id = tcx.hir.as_local_node_id(mir_def_id).unwrap();
+            tcx.struct_span_lint_node(
+                UNSAFE_REGION_ERRORS,
+                mir_node_id,
+                span,
+                &format!("{} does not outlive {}", fr_string, outlived_fr_string),
+            )
+        } else if escape_bound.is_some() {
+            struct_span_err!(
+                tcx.sess,
+                span,
+                E0692,
+                "borrowed data escapes the closure"
+            )
+        } else if blame_constraint.closure_blame_span.is_some() {
+            struct_span_err!(
+                tcx.sess,
+                span,
+                E0690,
+                "{} does not outlive {}",
+                fr_string,
+                outlived_fr_string
+            )
+        } else {
+            struct_span_err!(
+                tcx.sess,
+                span,
+                E0689,
+                "{} does not outlive {}",
+                fr_string,
+                outlived_fr_string
+            )
+        };
+
+        match escape_bound {
+            Some(EscapeBound::Static) => {
+                err.note(
+                    "so that the closure can be later used, the borrowed value \
+                     must be valid for the lifetime `'static`",
+                );
+                // A `move` closure owns its environment already, whether
+                // it takes it by reference or, being `FnOnce`, by value.
+                if !is_move_closure(infcx, mir_def_id) {
+                    err.help(
+                        "if the closure borrows from its environment, consider making it \
+                         a `move` closure so that it owns the data instead",
+                    );
+                }
+            }
+            Some(EscapeBound::Named(region)) => {
+                err.note(&format!(
+                    "so that the closure can be later used, the borrowed value \
+                     must be valid for the lifetime `{}` as defined on the enclosing function",
+                    region
+                ));
+            }
+            None => {}
+        }
+
+        if let Some(closure_blame_span) = blame_constraint.closure_blame_span {
+            err.span_label(
+                closure_blame_span,
+                "requirement occurs because of this borrow",
+            );
+        }
+
+        // Point at where the data of the shorter region comes from.
+        // If it is the lifetime of a reference parameter, pointing at
+        // that reference also tells the user which lifetime we mean.
+        if !self.label_anonymous_region(infcx, fr, &mut err) {
+            self.label_argument_mentioning(infcx, mir_def_id, fr, &mut err);
+        }
+
+        // Point at where the longer region is required, if that is
+        // only the return type. A `'static` that the user did not
+        // write may come from the default bound of a trait object.
+        self.label_anonymous_region(infcx, outlived_fr, &mut err);
+        let labeled_object_default = self.label_object_lifetime_default(
+            infcx,
+            mir,
+            mir_def_id,
+            fr,
+            outlived_fr,
+            blame_constraint,
+            &mut err,
+        );
+        if !labeled_object_default {
+            self.label_return_type_mentioning(infcx, mir_def_id, outlived_fr, &mut err);
+        }
+
+        self.note_impl_and_method_regions(infcx, mir_def_id, fr, outlived_fr, &mut err);
+
+        if self.dumps_enabled && infcx.tcx.sess.opts.debugging_opts.nll_dump_cause {
+            self.explain_universal_region(mir, fr, &mut err);
+            self.explain_universal_region(mir, outlived_fr, &mut err);
+
+            let common_lower_bounds: Vec<_> = self.universal_regions
+                .universal_regions_outlived_by_both(fr, outlived_fr)
+                .filter(|&r| r != fr && r != outlived_fr)
+                .map(|r| format!("`{}`", self.region_name(r)))
+                .collect();
+            if !common_lower_bounds.is_empty() {
+                err.note(&format!(
+                    "`{}` and `{}` are both known to outlive {}",
+                    self.region_name(fr),
+                    self.region_name(outlived_fr),
+                    common_lower_bounds.join(", ")
+                ));
+            }
+        }
+
+        match self.assignment_blame(infcx, mir, blame_constraint) {
+            Some(AssignmentBlame::Value { reference, reference_region }) => {
+                err.span_label(
+                    blame_constraint.span,
+                    format!(
+                        "the value assigned to `*{}` must outlive `{}` \
+                         because `{}` is a `&{} mut` reference",
+                        reference,
+                        self.region_name(outlived_fr),
+                        reference,
+                        self.region_name(reference_region)
+                    ),
+                );
+            }
+            Some(AssignmentBlame::Reference { reference }) => {
+                err.span_label(
+                    blame_constraint.span,
+                    format!(
+                        "`{}` does not live long enough to perform this assignment",
+                        reference
+                    ),
+                );
+            }
+            None => {}
+        }
+
+        if let Some(conversion_span) = conversion_span {
+            err.span_label(
+                conversion_span,
+                format!(
+                    "the error conversion performed by `?` requires that `{}` must outlive `{}`",
+                    self.region_name(fr),
+                    self.region_name(outlived_fr)
+                ),
+            );
+        }
+
+        // Bounding the trait object is a better fix than requiring
+        // `fr: 'static`.
+        if !labeled_object_default {
+            self.suggest_outlives_bound(infcx, mir_def_id, fr, outlived_fr, &mut err);
+        }
+
+        if within_unsafe {
+            err.emit();
+        } else {
+            error_buffer::emit_region_error(tcx.sess, err);
+        }
+    }
+
+    /// If `fr` is local to the closure `mir_def_id` but has to outlive
+    /// `outlived_fr`, which is not, then data borrowed within the
+    /// closure body escapes it. In that case, returns the smallest
+    /// region of the closure's creator that `fr` has to outlive as a
+    /// result, provided it has a name that the user will recognize.
+    fn closure_escape_bound(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir_def_id: DefId,
+        fr: RegionVid,
+        outlived_fr: RegionVid,
+    ) -> Option<EscapeBound<'tcx>> {
+        if !infcx.tcx.is_closure(mir_def_id)
+            || !self.universal_regions.is_local_free_region(fr)
+            || self.universal_regions.is_local_free_region(outlived_fr)
+        {
+            return None;
+        }
+
+        let upper_bound = self.universal_regions.non_local_upper_bound(outlived_fr);
+        debug!("closure_escape_bound: upper_bound={:?}", upper_bound);
+        if upper_bound == self.universal_regions.fr_static {
+            return Some(EscapeBound::Static);
+        }
+
+        // Map the bound back to the region of the creator that it
+        // stands for in the closure type.
+        let origin = self.universal_regions.external_region_origin(upper_bound)?;
+        match *origin {
+            ty::ReEarlyBound(_) => Some(EscapeBound::Named(origin)),
+            ty::ReFree(ref free_region) if free_region.bound_region.is_named() => {
+                Some(EscapeBound::Named(origin))
+            }
+            _ => None,
+        }
+    }
+
+    /// If the universal region `fr` is anonymous and is the lifetime
+    /// of a reference in the type of a parameter, labels that
+    /// reference with the name we use for `fr` (see
+    /// `UniversalRegions::name_region`). Returns true if it did.
+    fn label_anonymous_region(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        fr: RegionVid,
+        err: &mut DiagnosticBuilder<'_>,
+    ) -> bool {
+        let region_name = self.universal_regions.name_region(infcx.tcx, fr);
+        match region_name.highlight {
+            Some((span, ref description)) => {
+                err.span_label(span, format!("`{}` is {}", region_name.name, description));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// If the universal region `fr` appears in the type of one of the
+    /// parameters of the function `mir_def_id`, labels the pattern of
+    /// the first such parameter. Closures are left alone, as
+    /// `describe_region` already names their arguments.
+    fn label_argument_mentioning(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir_def_id: DefId,
+        fr: RegionVid,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        let tcx = infcx.tcx;
+        if tcx.is_closure(mir_def_id) || fr == self.universal_regions.fr_static {
+            return;
+        }
+
+        // The parameter may mention a region that is equal to `fr`
+        // (and hence shares its name) rather than `fr` itself.
+        let argument_index = self.universal_regions
+            .universal_regions()
+            .filter(|&r| self.universal_regions.equal(r, fr))
+            .filter_map(|r| self.universal_regions.arguments_containing(r).first().cloned())
+            .min();
+        let argument_index = match argument_index {
+            Some(argument_index) => argument_index,
+            None => return,
+        };
+        let mir_node_id = match tcx.hir.as_local_node_id(mir_def_id) {
+            Some(mir_node_id) => mir_node_id,
+            None => return,
+        };
+        let body = tcx.hir.body(tcx.hir.body_owned_by(mir_node_id));
+        let pat = match body.arguments.get(argument_index) {
+            Some(argument) => &argument.pat,
+            None => return,
+        };
+
+        let fr_name = self.region_name(fr);
+        match pat.node {
+            hir::PatKind::Binding(_, _, ref name, None) => {
+                err.span_label(
+                    pat.span,
+                    format!(
+                        "lifetime `{}` appears in the type of parameter `{}`",
+                        fr_name,
+                        name.node
+                    ),
+                );
+            }
+            _ => {
+                err.span_label(
+                    pat.span,
+                    format!("lifetime `{}` appears in the type of this parameter", fr_name),
+                );
+            }
+        }
+    }
+
+    /// Within a method, a lifetime of the impl (or trait) and one of
+    /// the method itself look alike in the signature. If one of `fr`
+    /// and `outlived_fr` is of each kind, says which is which.
+    fn note_impl_and_method_regions(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir_def_id: DefId,
+        fr: RegionVid,
+        outlived_fr: RegionVid,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        let (parent_region, method_region) = match (
+            self.universal_regions.name_origin(fr),
+            self.universal_regions.name_origin(outlived_fr),
+        ) {
+            (Some(NameOrigin::Parent), Some(NameOrigin::Item)) => (fr, outlived_fr),
+            (Some(NameOrigin::Item), Some(NameOrigin::Parent)) => (outlived_fr, fr),
+            _ => return,
+        };
+
+        let tcx = infcx.tcx;
+        let method_def_id = tcx.closure_base_def_id(mir_def_id);
+        let parent = if tcx.impl_of_method(method_def_id).is_some() {
+            "impl"
+        } else {
+            "trait"
         };
+        err.note(&format!(
+            "`{}` is a lifetime parameter of the {}, while `{}` is one of the method",
+            self.region_name(parent_region),
+            parent,
+            self.region_name(method_region)
+        ));
+    }
+
+    /// If the universal region `fr` appears in the return type of
+    /// `mir_def_id` but in none of its parameters, labels the return
+    /// type. For a closure whose return type is elided, the body,
+    /// whose type the return type is inferred from, is labeled
+    /// instead.
+    fn label_return_type_mentioning(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir_def_id: DefId,
+        fr: RegionVid,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        let tcx = infcx.tcx;
+
+        // As above, the return type may mention a region that is
+        // equal to `fr` rather than `fr` itself. The first input of a
+        // closure is its environment, whose type mentions all the
+        // regions of the closure signature.
+        let first_argument = if tcx.is_closure(mir_def_id) { 1 } else { 0 };
+        let mut appears_in_output = false;
+        for r in self.universal_regions.universal_regions() {
+            if !self.universal_regions.equal(r, fr) {
+                continue;
+            }
+            let in_argument = self.universal_regions
+                .arguments_containing(r)
+                .iter()
+                .any(|&idx| idx >= first_argument);
+            if in_argument {
+                return;
+            }
+            appears_in_output |= self.universal_regions.appears_in_output(r);
+        }
+        if !appears_in_output {
+            return;
+        }
 
-        let outlived_fr_string = match self.definitions[outlived_fr].external_name {
-            Some(r) => format!("free region `{}`", r),
-            None => format!("free region `{:?}`", outlived_fr),
+        let mir_node_id = match tcx.hir.as_local_node_id(mir_def_id) {
+            Some(mir_node_id) => mir_node_id,
+            None => return,
+        };
+        let span = match tcx.hir.get(mir_node_id) {
+            hir_map::NodeExpr(&hir::Expr {
+                node: hir::ExprClosure(_, ref decl, body_id, _, _),
+                ..
+            }) => match decl.output {
+                hir::Return(ref ty) => ty.span,
+                hir::DefaultReturn(_) => tcx.hir.body(body_id).value.span,
+            },
+            hir_map::NodeItem(&hir::Item { node: hir::ItemFn(ref decl, ..), .. }) => {
+                match decl.output {
+                    hir::Return(ref ty) => ty.span,
+                    hir::DefaultReturn(_) => return,
+                }
+            }
+            hir_map::NodeTraitItem(&hir::TraitItem {
+                node: hir::TraitItemKind::Method(ref sig, _),
+                ..
+            }) |
+            hir_map::NodeImplItem(&hir::ImplItem {
+                node: hir::ImplItemKind::Method(ref sig, _),
+                ..
+            }) => match sig.decl.output {
+                hir::Return(ref ty) => ty.span,
+                hir::DefaultReturn(_) => return,
+            },
+            _ => return,
         };
 
-        infcx.tcx.sess.span_err(
-            blame_span,
-            &format!("{} does not outlive {}", fr_string, outlived_fr_string,),
+        err.span_label(
+            span,
+            format!(
+                "the returned value must outlive `{}` because of this return type",
+                self.creator_region_name(fr)
+            ),
         );
     }
 
+    /// Returns the name of `r` as the closure's creator knows it: for
+    /// an external region, the name of the creator's region it stands
+    /// for, if that one is named. Otherwise, the same as `region_name`.
+    fn creator_region_name(&self, r: RegionVid) -> String {
+        let r = self.universal_regions.representative(r);
+        if self.definitions[r].external_name.is_none() {
+            if let Some(origin) = self.universal_regions.external_region_origin(r) {
+                match *origin {
+                    ty::ReEarlyBound(_) | ty::ReStatic => return format!("{}", origin),
+                    ty::ReFree(ref free_region) if free_region.bound_region.is_named() => {
+                        return format!("{}", origin)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.region_name(r)
+    }
+
+    /// Returns the name of `r` for use in error messages: the name that
+    /// `UniversalRegions` gives it if it is a late-bound region of the
+    /// signature (e.g. `'a`, or `'1` for an anonymous one), the name
+    /// of the region (or of the bound region that a placeholder
+    /// stands for) if it has one, and its debug representation
+    /// (e.g. `'_#3r`) otherwise. Universal regions known to be equal
+    /// share the name of their representative.
+    fn region_name(&self, r: RegionVid) -> String {
+        let r = self.universal_regions.representative(r);
+        if let Some(name) = self.universal_regions.late_bound_region_name(r) {
+            return name;
+        }
+        if let Some(ty::BrNamed(_, name)) = self.universal_regions.placeholder_bound_region(r) {
+            return format!("{}", name);
+        }
+        match self.definitions[r].external_name {
+            Some(name) => format!("{}", name),
+            None => format!("{:?}", r),
+        }
+    }
+
+    /// Describes the universal region `r` for the headline of a
+    /// region error. The external regions of a closure have no name
+    /// of their own, so for those we use the name of the creator's
+    /// region they stand for, or, if that one is anonymous, point at
+    /// the closure argument whose type mentions the region. The
+    /// placeholders are described as higher-ranked regions.
+    fn describe_region(&self, r: RegionVid) -> String {
+        let r = self.universal_regions.representative(r);
+        if let Some(br) = self.universal_regions.placeholder_bound_region(r) {
+            return match br {
+                ty::BrNamed(_, name) => format!("higher-ranked region `{}`", name),
+                _ => String::from("an anonymous higher-ranked region"),
+            };
+        }
+        if self.definitions[r].external_name.is_none() {
+            if let Some(origin) = self.universal_regions.external_region_origin(r) {
+                let is_named = match *origin {
+                    ty::ReEarlyBound(_) | ty::ReStatic => true,
+                    ty::ReFree(ref free_region) => free_region.bound_region.is_named(),
+                    _ => false,
+                };
+                if is_named {
+                    return format!("free region `{}`", origin);
+                }
+
+                // The first input of a closure is its environment.
+                let argument_index = (1..self.universal_regions.input_tys.len())
+                    .find(|&idx| self.universal_regions.regions_in_argument(idx).contains(&r));
+                if let Some(argument_index) = argument_index {
+                    return format!(
+                        "the lifetime appearing in the type of the closure's argument {}",
+                        argument_index
+                    );
+                }
+            }
+        }
+
+        format!("free region `{}`", self.region_name(r))
+    }
+
+    /// If `constraint` arose from an assignment through a reference,
+    /// like `*x = val`, figures out whether the regions of the
+    /// assigned value or the region of the reference `x` itself
+    /// participate in it, so that the error can say which of the two
+    /// is to blame. Returns `None` if the constraint did not come from
+    /// such an assignment (or if `x` has no user-visible name, or is
+    /// not an argument whose region we can name).
+    ///
+    /// The constraint always reads `'value: 'place`, where `'value`
+    /// comes from the type of `val` and `'place` from the type of
+    /// `*x`. The reference is only to blame if `'place` is its own
+    /// region, as in `x: &'b mut &'b u32`; the value may well have the
+    /// same region as the reference without that being the problem.
+    fn assignment_blame(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir: &Mir<'tcx>,
+        constraint: &Constraint,
+    ) -> Option<AssignmentBlame> {
+        let tcx = infcx.tcx;
+        let Location { block, statement_index } = constraint.point;
+        let statement = mir[block].statements.get(statement_index)?;
+        let (place, rvalue) = match statement.kind {
+            StatementKind::Assign(ref place, ref rvalue) => (place, rvalue),
+            _ => return None,
+        };
+
+        // We only care about writes through a reference stored in a
+        // named local, i.e. `*x = val`.
+        let local = match *place {
+            Place::Projection(ref proj) => match (&proj.base, &proj.elem) {
+                (&Place::Local(local), &ProjectionElem::Deref) => local,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let reference = mir.local_decls[local].name?;
+        let reference_region = match mir.local_decls[local].ty.sty {
+            ty::TyRef(region, _) => region.to_region_vid(),
+            _ => return None,
+        };
+        if !self.universal_regions.is_universal_region(reference_region) {
+            return None;
+        }
+
+        // If the value has to outlive the region of the reference
+        // itself, it is the reference that does not live long
+        // enough...
+        if constraint.sub == reference_region {
+            return Some(AssignmentBlame::Reference { reference });
+        }
+
+        // ...otherwise, if the value being assigned contains the
+        // region that has to outlive something, blame the value.
+        let rvalue_ty = rvalue.ty(mir, tcx);
+        let mut value_contains_sup = false;
+        tcx.for_each_free_region(&rvalue_ty, |r| {
+            if let ty::ReVar(vid) = *r {
+                value_contains_sup |= vid == constraint.sup;
+            }
+        });
+        if value_contains_sup {
+            return Some(AssignmentBlame::Value { reference, reference_region });
+        }
+
+        None
+    }
+
+    /// If `constraint` arose from the call to `From::from` by which an
+    /// `expr?` converts the error it returns early, returns the span
+    /// of the `?`.
+    fn try_conversion_span(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir: &Mir<'tcx>,
+        constraint: &Constraint,
+    ) -> Option<Span> {
+        let Location { block, statement_index } = constraint.point;
+        if statement_index != mir[block].statements.len() {
+            return None;
+        }
+        match try_desugar_call(infcx.tcx, mir, block) {
+            Some((TryDesugarCall::Conversion, try_expr_span)) => {
+                Some(question_mark_span(try_expr_span))
+            }
+            _ => None,
+        }
+    }
+
     /// Propagate the region constraints: this will grow the values
     /// for each region variable until all the constraints are
     /// satisfied. Note that some values may grow **too** large to be
@@ -506,24 +1568,45 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         changed
     }
 
-    /// Tries to finds a good span to blame for the fact that `fr1`
-    /// contains `fr2`.
-    fn blame_span(&self, fr1: RegionVid, fr2: RegionVid) -> Span {
+    /// Tries to finds a good constraint to blame for the fact that
+    /// `fr1` contains `fr2`.
+    fn blame_constraint(&self, fr1: RegionVid, fr2: RegionVid) -> &Constraint {
         // Find everything that influenced final value of `fr`.
         let influenced_fr1 = self.dependencies(fr1);
 
+        // If the relationship was imposed on us by a closure we
+        // created, prefer to blame that: we can then point into the
+        // closure body at the statement that required it. A closure
+        // constraint `'X: 'Y` is relevant if `'X` influenced `fr1`
+        // and `fr2` influenced `'Y`.
+        for constraint in &self.constraints {
+            if constraint.closure_blame_span.is_some() && influenced_fr1[constraint.sup] {
+                if self.dependencies(constraint.sub)[fr2] {
+                    return constraint;
+                }
+            }
+        }
+
         // Try to find some outlives constraint `'X: fr2` where `'X`
-        // influenced `fr1`. Blame that.
+        // influenced `fr1`. Blame the one whose `'X` is the fewest
+        // constraints away from `fr1`: that is the constraint that
+        // forced `fr2` into `fr1`, whereas the others may only be
+        // reachable through it (think of `*x = z; *x = y;`, where only
+        // the second assignment needs `y` to outlive the referent).
         //
-        // NB, this is a pretty bad choice most of the time. In
+        // NB, this is still a pretty bad choice most of the time. In
         // particular, the connection between `'X` and `fr1` may not
         // be obvious to the user -- not to mention the naive notion
         // of dependencies, which doesn't account for the locations of
         // contraints at all. But it will do for now.
-        for constraint in &self.constraints {
-            if constraint.sub == fr2 && influenced_fr1[constraint.sup] {
-                return constraint.span;
-            }
+        let distances = self.distances_from(fr1);
+        let closest = self.constraints
+            .iter()
+            .filter(|constraint| constraint.sub == fr2)
+            .filter_map(|constraint| distances[constraint.sup].map(|d| (d, constraint)))
+            .min_by_key(|&(d, _)| d);
+        if let Some((_, constraint)) = closest {
+            return constraint;
         }
 
         bug!(
@@ -533,6 +1616,31 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         );
     }
 
+    /// For each region `'b`, the length of the shortest chain of
+    /// constraints `r0: 'x1`, `'x1: 'x2`, ..., `'xn: 'b` that makes
+    /// `r0` depend on `'b`, if there is one.
+    fn distances_from(&self, r0: RegionVid) -> IndexVec<RegionVid, Option<usize>> {
+        let mut distances = IndexVec::from_elem(None, &self.definitions);
+        distances[r0] = Some(0);
+
+        let mut distance = 0;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for constraint in &self.constraints {
+                if distances[constraint.sup] == Some(distance)
+                    && distances[constraint.sub].is_none()
+                {
+                    distances[constraint.sub] = Some(distance + 1);
+                    changed = true;
+                }
+            }
+            distance += 1;
+        }
+
+        distances
+    }
+
     /// Finds all regions whose values `'a` may depend on in some way.
     /// Basically if there exists a constraint `'a: 'b @ P`, then `'b`
     /// and `dependencies('b)` will be in the final set.
@@ -585,17 +1693,55 @@ impl fmt::Debug for Constraint {
     }
 }
 
-pub trait ClosureRegionRequirementsExt {
+/// Which side of an assignment `*x = val` is to blame for a failed
+/// constraint; see `RegionInferenceContext::assignment_blame`.
+enum AssignmentBlame {
+    /// The value `val` contains a region that does not live long enough.
+    Value {
+        reference: ast::Name,
+        reference_region: RegionVid,
+    },
+
+    /// The region of the reference `x` itself does not live long enough.
+    Reference { reference: ast::Name },
+}
+
+/// True if `mir_def_id` is a closure that captures by value.
+fn is_move_closure(infcx: &InferCtxt<'_, '_, '_>, mir_def_id: DefId) -> bool {
+    let tcx = infcx.tcx;
+    let mir_node_id = match tcx.hir.as_local_node_id(mir_def_id) {
+        Some(mir_node_id) => mir_node_id,
+        None => return false,
+    };
+    match tcx.hir.get(mir_node_id) {
+        hir_map::NodeExpr(&hir::Expr {
+            node: hir::ExprClosure(hir::CaptureByValue, ..),
+            ..
+        }) => true,
+        _ => false,
+    }
+}
+
+/// The region of its creator that borrowed data escaping a closure
+/// body has to outlive; see `RegionInferenceContext::closure_escape_bound`.
+enum EscapeBound<'tcx> {
+    Static,
+
+    /// A lifetime parameter of the enclosing function.
+    Named(ty::Region<'tcx>),
+}
+
+pub trait ClosureRegionRequirementsExt<'gcx> {
     fn apply_requirements<'tcx>(
         &self,
-        infcx: &InferCtxt<'_, '_, 'tcx>,
+        infcx: &InferCtxt<'_, 'gcx, 'tcx>,
+        body_id: ast::NodeId,
         location: Location,
-        closure_def_id: DefId,
-        closure_substs: ty::ClosureSubsts<'tcx>,
+        closure_ty: Ty<'tcx>,
     );
 }
 
-impl ClosureRegionRequirementsExt for ClosureRegionRequirements {
+impl<'gcx> ClosureRegionRequirementsExt<'gcx> for ClosureRegionRequirements<'gcx> {
     /// Given an instance T of the closure type, this method
     /// instantiates the "extra" requirements that we computed for the
     /// closure into the inference context. This has the effect of
@@ -608,46 +1754,127 @@ impl ClosureRegionRequirementsExt for ClosureRegionRequirements {
     /// a vector. Then we can just index into that vector to extract
     /// out the corresponding region from T and apply the
     /// requirements.
+    ///
+    /// Requirements on types are registered as region obligations
+    /// of `body_id`, after substituting the closure substs of T for
+    /// the type parameters of the closure.
     fn apply_requirements<'tcx>(
         &self,
-        infcx: &InferCtxt<'_, '_, 'tcx>,
+        infcx: &InferCtxt<'_, 'gcx, 'tcx>,
+        body_id: ast::NodeId,
         location: Location,
-        closure_def_id: DefId,
-        closure_substs: ty::ClosureSubsts<'tcx>,
+        closure_ty: Ty<'tcx>,
     ) {
         let tcx = infcx.tcx;
 
         debug!(
-            "apply_requirements(location={:?}, closure_def_id={:?}, closure_substs={:?})",
+            "apply_requirements(location={:?}, closure_ty={:?})",
             location,
-            closure_def_id,
-            closure_substs
+            closure_ty
         );
 
-        // Get Tu.
-        let user_closure_ty = tcx.mk_closure(closure_def_id, closure_substs);
-        debug!("apply_requirements: user_closure_ty={:?}", user_closure_ty);
+        let (closure_def_id, closure_substs) = match closure_ty.sty {
+            ty::TyClosure(def_id, substs) | ty::TyGenerator(def_id, substs, _) => {
+                (def_id, substs.substs)
+            }
+            _ => bug!("apply_requirements: {:?} is not a closure or generator", closure_ty),
+        };
+
+        // The requirements may have been loaded from the incremental
+        // cache; make sure they name only regions that we can find on
+        // the closure type before we index with them.
+        if let Err(err) = self.validate() {
+            tcx.sess
+                .struct_span_fatal(
+                    tcx.def_span(closure_def_id),
+                    &format!(
+                        "the region requirements of this closure, from crate `{}`, \
+                         are incompatible with this compiler",
+                        tcx.crate_name(closure_def_id.krate)
+                    ),
+                )
+                .note(&format!(
+                    "requirement {} refers to region {:?}, but the closure has only \
+                     {} external regions",
+                    err.index,
+                    err.region,
+                    self.num_external_vids
+                ))
+                .note("the data it was loaded from may be corrupted or written by \
+                       a different version of the compiler")
+                .emit();
+            tcx.sess.abort_if_errors();
+            return;
+        }
 
-        // Extract the values of the free regions in `user_closure_ty`
+        // Extract the values of the free regions in `closure_ty`
         // into a vector.  These are the regions that we will be
         // relating to one another.
         let closure_mapping =
-            UniversalRegions::closure_mapping(infcx, user_closure_ty, self.num_external_vids);
+            UniversalRegions::closure_mapping(infcx, closure_ty, self.num_external_vids);
+        let closure_mapping = match closure_mapping {
+            Ok(closure_mapping) => closure_mapping,
+            Err(err) => {
+                // We cannot tell which regions the requirements refer
+                // to, so conservatively skip propagating them. This
+                // should only happen if some earlier error left the
+                // closure type in a strange state.
+                delay_nll_bug(
+                    tcx.sess,
+                    "closure requirements",
+                    tcx.def_span(closure_def_id),
+                    &format!(
+                        "closure type has {} free regions, but its region \
+                         requirements expect {}",
+                        err.found_num_vars,
+                        err.expected_num_vars
+                    ),
+                );
+                return;
+            }
+        };
         debug!("apply_requirements: closure_mapping={:?}", closure_mapping);
 
         // Create the predicates.
         for outlives_requirement in &self.outlives_requirements {
-            let region = closure_mapping[outlives_requirement.free_region];
             let outlived_region = closure_mapping[outlives_requirement.outlived_free_region];
-            debug!(
-                "apply_requirements: region={:?} outlived_region={:?} outlives_requirements={:?}",
-                region,
-                outlived_region,
-                outlives_requirement
-            );
-            // FIXME, this origin is not entirely suitable.
-            let origin = SubregionOrigin::CallRcvr(outlives_requirement.blame_span);
-            infcx.sub_regions(origin, outlived_region, region);
+            match outlives_requirement.subject {
+                ClosureOutlivesSubject::Region(region) => {
+                    let region = closure_mapping[region];
+                    debug!(
+                        "apply_requirements: region={:?} outlived_region={:?} \
+                         outlives_requirement={}",
+                        region,
+                        outlived_region,
+                        outlives_requirement
+                    );
+                    let origin =
+                        SubregionOrigin::ClosureRequirement(outlives_requirement.blame_span);
+                    infcx.sub_regions(origin, outlived_region, region);
+                }
+
+                ClosureOutlivesSubject::Ty(ty) => {
+                    let ty = ty.subst(tcx, closure_substs);
+                    debug!(
+                        "apply_requirements: ty={:?} outlived_region={:?} outlives_requirement={}",
+                        ty,
+                        outlived_region,
+                        outlives_requirement
+                    );
+                    infcx.register_region_obligation(
+                        body_id,
+                        RegionObligation {
+                            sub_region: outlived_region,
+                            sup_type: ty,
+                            cause: ObligationCause::new(
+                                outlives_requirement.blame_span,
+                                body_id,
+                                traits::ClosureRequirement,
+                            ),
+                        },
+                    );
+                }
+            }
         }
     }
 }
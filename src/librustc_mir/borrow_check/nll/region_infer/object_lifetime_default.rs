@@ -0,0 +1,376 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Outside of function bodies, a trait object type like `Box<dyn
+//! Trait>` whose lifetime bound is not written defaults to `dyn Trait
+//! + 'static`. A region error saying that some region "does not
+//! outlive `'static`" is very confusing when the user never wrote
+//! `'static`, so this module finds the trait object type responsible
+//! for the requirement and points at it.
+
+use rustc::hir::{self, intravisit};
+use rustc::hir::intravisit::{NestedVisitorMap, Visitor};
+use rustc::hir::def_id::DefId;
+use rustc::hir::map as hir_map;
+use rustc::infer::InferCtxt;
+use rustc::middle::resolve_lifetime as rl;
+use rustc::mir::{BasicBlock, Local, Location, Mir, Statement, Terminator};
+use rustc::mir::visit::{PlaceContext, Visitor as MirVisitor};
+use rustc::ty::{self, RegionVid, TyCtxt};
+use rustc::util::nodemap::FxHashSet;
+use rustc_data_structures::indexed_vec::Idx;
+use rustc_errors::DiagnosticBuilder;
+use syntax::ast;
+use syntax_pos::Span;
+use transform::type_check::DeclaredType;
+
+use super::{Constraint, RegionInferenceContext};
+use super::super::universal_regions::{RegionBoundPairOrigin, UniversalRegions};
+
+impl<'tcx> RegionInferenceContext<'tcx> {
+    /// If `fr` has to outlive `'static` because the struct field or
+    /// return type that `blame_constraint` stores a value into, or
+    /// the argument that it relates to `'static`, has a trait object
+    /// type whose `'static` bound was left implicit, notes that bound at
+    /// the trait object type as written and, for the signature,
+    /// suggests spelling out a shorter bound. Returns true if it did
+    /// so.
+    pub(super) fn label_object_lifetime_default(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir: &Mir<'tcx>,
+        mir_def_id: DefId,
+        fr: RegionVid,
+        outlived_fr: RegionVid,
+        blame_constraint: &Constraint,
+        err: &mut DiagnosticBuilder<'_>,
+    ) -> bool {
+        let tcx = infcx.tcx;
+        let outlived_fr = self.universal_regions.representative(outlived_fr);
+        if outlived_fr != self.universal_regions.fr_static {
+            return false;
+        }
+
+        // The constraint we blame relates a value to the type of a
+        // struct field or to the return type, as type-check noted.
+        let (object, position) = match blame_constraint.declared_type {
+            Some(DeclaredType::Field(field_did)) => {
+                let object = tcx.hir.as_local_node_id(field_did).and_then(|field_node_id| {
+                    match tcx.hir.get(field_node_id) {
+                        hir_map::NodeField(field) => find_default_static_object(tcx, &field.ty),
+                        _ => None,
+                    }
+                });
+                (object, ObjectPosition::Field)
+            }
+            Some(DeclaredType::Return) => {
+                let object = tcx.hir.as_local_node_id(mir_def_id)
+                    .and_then(|node_id| fn_output_ty(tcx, node_id))
+                    .and_then(|output_ty| find_default_static_object(tcx, output_ty));
+                (object, ObjectPosition::ReturnType)
+            }
+            None => (None, ObjectPosition::Argument),
+        };
+
+        // Otherwise, the trait object may be in the type of an
+        // argument that the blamed constraint relates to `'static`.
+        let object = match (object, position) {
+            (None, ObjectPosition::Argument) if !tcx.is_closure(mir_def_id) => {
+                argument_default_static_object(
+                    tcx,
+                    mir,
+                    mir_def_id,
+                    &self.universal_regions,
+                    blame_constraint,
+                )
+            }
+            (object, _) => object,
+        };
+        let object = match object {
+            Some(object) => object,
+            None => return false,
+        };
+        let snippet = match tcx.sess.codemap().span_to_snippet(object.span) {
+            Ok(snippet) => snippet,
+            Err(_) => return false,
+        };
+
+        // A shorter bound can only be written in the signature, as a
+        // struct field cannot refer to the lifetimes of a function. A
+        // named region can be used as is. In the return type, `'_`
+        // stands for the lifetime of the arguments if there is only
+        // one (otherwise it is an elision error), but in an argument
+        // it would be a new lifetime of its own.
+        let fr = self.universal_regions.representative(fr);
+        let named = match self.definitions[fr].external_name {
+            Some(&ty::ReEarlyBound(data)) => Some(format!("{}", data.name)),
+            Some(&ty::ReFree(ty::FreeRegion {
+                bound_region: ty::BrNamed(_, name),
+                ..
+            })) => Some(format!("{}", name)),
+            _ => None,
+        };
+        let lifetime = match position {
+            ObjectPosition::Field => None,
+            ObjectPosition::Argument => named,
+            ObjectPosition::ReturnType => named.or_else(|| {
+                let elidable = tcx.hir.as_local_node_id(mir_def_id)
+                    .and_then(|node_id| fn_decl(tcx, node_id))
+                    .map_or(false, |decl| num_input_lifetimes(decl) == 1);
+                if elidable && tcx.sess.features.borrow().underscore_lifetimes {
+                    Some(String::from("'_"))
+                } else {
+                    None
+                }
+            }),
+        };
+
+        let bounded = format!("{} + {}", snippet, lifetime.as_ref().map_or("'a", |l| l));
+        err.span_note(
+            object.span,
+            &format!(
+                "the trait object `{}` has an implicit `'static` lifetime bound here; \
+                 if the data cannot live that long, write `{}`",
+                snippet,
+                bounded
+            ),
+        );
+        if lifetime.is_some() {
+            let suggestion = if object.behind_reference {
+                format!("({})", bounded)
+            } else {
+                bounded
+            };
+            err.span_suggestion_short(
+                object.span,
+                "consider adding an explicit lifetime bound",
+                suggestion,
+            );
+        }
+        true
+    }
+}
+
+/// Where the trait object type with the implicit `'static` bound was
+/// found.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum ObjectPosition {
+    /// In the type of a struct field.
+    Field,
+
+    /// In the return type of the function.
+    ReturnType,
+
+    /// In the type of an argument of the function.
+    Argument,
+}
+
+/// The first trait object type with an implicit `'static` bound in
+/// the type of an argument of `mir_def_id` that `constraint` relates
+/// to `'static`. Such an argument type implies `'static: 'x` for some
+/// `'x` (see `UniversalRegions::static_bound_origins`), and, as the
+/// `'static` of a constraint comes from the type of a place, the
+/// argument is used at the point of `constraint`, like the `slot` of
+/// `fn store(x: &u32, slot: &mut Box<dyn Debug>) { *slot = Box::new(x) }`.
+fn argument_default_static_object<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+    mir_def_id: DefId,
+    universal_regions: &UniversalRegions<'tcx>,
+    constraint: &Constraint,
+) -> Option<DefaultStaticObject> {
+    let decl = tcx.hir.as_local_node_id(mir_def_id).and_then(|node_id| fn_decl(tcx, node_id))?;
+
+    let Location { block, statement_index } = constraint.point;
+    let data = &mir[block];
+    let used = match data.statements.get(statement_index) {
+        Some(statement) => LocalCollector::statement_locals(block, statement, constraint.point),
+        None => LocalCollector::terminator_locals(block, data.terminator(), constraint.point),
+    };
+
+    universal_regions
+        .static_bound_origins()
+        .filter_map(|origin| match origin {
+            RegionBoundPairOrigin::ImpliedByInput(index) => Some(index),
+            _ => None,
+        })
+        .filter(|&index| used.contains(&Local::new(index + 1)))
+        .filter_map(|index| decl.inputs.get(index))
+        .filter_map(|input| find_default_static_object(tcx, input))
+        .next()
+}
+
+/// Collects the locals that a statement or terminator uses.
+struct LocalCollector {
+    locals: FxHashSet<Local>,
+}
+
+impl LocalCollector {
+    fn statement_locals(
+        block: BasicBlock,
+        statement: &Statement<'_>,
+        location: Location,
+    ) -> FxHashSet<Local> {
+        let mut collector = LocalCollector { locals: FxHashSet() };
+        collector.visit_statement(block, statement, location);
+        collector.locals
+    }
+
+    fn terminator_locals(
+        block: BasicBlock,
+        terminator: &Terminator<'_>,
+        location: Location,
+    ) -> FxHashSet<Local> {
+        let mut collector = LocalCollector { locals: FxHashSet() };
+        collector.visit_terminator(block, terminator, location);
+        collector.locals
+    }
+}
+
+impl<'tcx> MirVisitor<'tcx> for LocalCollector {
+    fn visit_local(&mut self, local: &Local, _: PlaceContext<'tcx>, _: Location) {
+        self.locals.insert(*local);
+    }
+}
+
+/// The declaration of the function `node_id`.
+fn fn_decl<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    node_id: ast::NodeId,
+) -> Option<&'gcx hir::FnDecl> {
+    match tcx.hir.get(node_id) {
+        hir_map::NodeItem(&hir::Item { node: hir::ItemFn(ref decl, ..), .. }) => Some(decl),
+        hir_map::NodeTraitItem(&hir::TraitItem {
+            node: hir::TraitItemKind::Method(ref sig, _),
+            ..
+        }) |
+        hir_map::NodeImplItem(&hir::ImplItem {
+            node: hir::ImplItemKind::Method(ref sig, _),
+            ..
+        }) => Some(&sig.decl),
+        _ => None,
+    }
+}
+
+/// The number of lifetimes in the argument types of `decl`, written
+/// or elided, which the elided lifetimes of the return type may stand
+/// for. The lifetimes bound by a fn pointer type and the defaulted
+/// bounds of trait objects do not count.
+fn num_input_lifetimes(decl: &hir::FnDecl) -> usize {
+    struct LifetimeCounter {
+        count: usize,
+    }
+
+    impl<'v> Visitor<'v> for LifetimeCounter {
+        fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'v> {
+            NestedVisitorMap::None
+        }
+
+        fn visit_ty(&mut self, ty: &'v hir::Ty) {
+            match ty.node {
+                hir::TyBareFn(..) => {}
+                hir::TyTraitObject(ref bounds, ref lifetime) => {
+                    for bound in bounds {
+                        self.visit_poly_trait_ref(bound, hir::TraitBoundModifier::None);
+                    }
+                    if lifetime.name != hir::LifetimeName::Implicit {
+                        self.visit_lifetime(lifetime);
+                    }
+                }
+                _ => intravisit::walk_ty(self, ty),
+            }
+        }
+
+        fn visit_lifetime(&mut self, _: &'v hir::Lifetime) {
+            self.count += 1;
+        }
+    }
+
+    let mut counter = LifetimeCounter { count: 0 };
+    for input in &decl.inputs {
+        counter.visit_ty(input);
+    }
+    counter.count
+}
+
+/// The return type of the function `node_id`, if it is written.
+fn fn_output_ty<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    node_id: ast::NodeId,
+) -> Option<&'gcx hir::Ty> {
+    let decl = fn_decl(tcx, node_id)?;
+    match decl.output {
+        hir::Return(ref ty) => Some(ty),
+        hir::DefaultReturn(_) => None,
+    }
+}
+
+/// A trait object type without a written lifetime bound, whose bound
+/// defaults to `'static`.
+struct DefaultStaticObject {
+    span: Span,
+
+    /// True for `&'static Trait`, where adding a bound requires
+    /// parentheses.
+    behind_reference: bool,
+}
+
+fn find_default_static_object<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    ty: &'gcx hir::Ty,
+) -> Option<DefaultStaticObject> {
+    let mut finder = DefaultStaticObjectFinder { tcx, found: None };
+    finder.visit_ty(ty);
+    finder.found
+}
+
+struct DefaultStaticObjectFinder<'a, 'gcx: 'a + 'tcx, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    found: Option<DefaultStaticObject>,
+}
+
+impl<'a, 'gcx, 'tcx> DefaultStaticObjectFinder<'a, 'gcx, 'tcx> {
+    fn is_default_static_object(&self, ty: &hir::Ty) -> bool {
+        match ty.node {
+            hir::TyTraitObject(_, ref lifetime) => {
+                lifetime.name == hir::LifetimeName::Implicit &&
+                    self.tcx.named_region(self.tcx.hir.node_to_hir_id(lifetime.id))
+                        == Some(rl::Region::Static)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'a, 'gcx, 'tcx> Visitor<'gcx> for DefaultStaticObjectFinder<'a, 'gcx, 'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'gcx> {
+        NestedVisitorMap::None
+    }
+
+    fn visit_ty(&mut self, ty: &'gcx hir::Ty) {
+        if self.found.is_some() {
+            return;
+        }
+        if self.is_default_static_object(ty) {
+            self.found = Some(DefaultStaticObject { span: ty.span, behind_reference: false });
+            return;
+        }
+        if let hir::TyRptr(_, ref mut_ty) = ty.node {
+            if self.is_default_static_object(&mut_ty.ty) {
+                self.found = Some(DefaultStaticObject {
+                    span: mut_ty.ty.span,
+                    behind_reference: true,
+                });
+                return;
+            }
+        }
+        intravisit::walk_ty(self, ty);
+    }
+}
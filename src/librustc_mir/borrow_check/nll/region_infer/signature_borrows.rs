@@ -0,0 +1,69 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for `-Znll-signature-borrows-report`, which lists, for
+//! each fn, the borrows that are effectively tied to its signature:
+//! those whose region reaches the return points of the body. A
+//! caller has to keep such borrows alive for as long as the regions
+//! of the signature that they outlive, so they are the ones that
+//! constrain callers.
+
+use rustc::mir::{Location, Mir, TerminatorKind};
+use rustc::ty::RegionVid;
+
+use super::RegionInferenceContext;
+
+impl<'tcx> RegionInferenceContext<'tcx> {
+    /// If the final value of the borrow region `r` reaches a return
+    /// point of `mir`, or some universal region, returns the names of
+    /// the universal regions that `r` is required to outlive, in
+    /// index order (this may be none of them, e.g. if `r` only
+    /// reaches the return points because of a value dropped there).
+    /// Otherwise, the borrow ends within the body, and we return
+    /// `None`. Must be called after `solve`.
+    pub(crate) fn signature_borrow_bounds(
+        &self,
+        mir: &Mir<'tcx>,
+        r: RegionVid,
+    ) -> Option<Vec<String>> {
+        let inferred_values = self.inferred_values
+            .as_ref()
+            .expect("region values not yet inferred");
+
+        let mut names: Vec<String> = vec![];
+        for fr in self.universal_regions.universal_regions() {
+            if inferred_values.contains(r, fr) {
+                let name = self.region_name(fr);
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+
+        let reaches_return = mir.basic_blocks().iter_enumerated().any(|(block, data)| {
+            match data.terminator().kind {
+                TerminatorKind::Return => {
+                    let location = Location {
+                        block,
+                        statement_index: data.statements.len(),
+                    };
+                    inferred_values.contains(r, location)
+                }
+                _ => false,
+            }
+        });
+
+        if names.is_empty() && !reaches_return {
+            None
+        } else {
+            Some(names)
+        }
+    }
+}
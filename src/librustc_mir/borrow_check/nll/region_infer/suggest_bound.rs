@@ -0,0 +1,156 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! When a universal region `'b` has to outlive another universal
+//! region `'a`, but nothing tells us that `'b: 'a`, the fix is very
+//! often just to declare that bound. This module works out where in
+//! the source such a bound could go, so that `report_error` can
+//! suggest it.
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::hir::map as hir_map;
+use rustc::infer::InferCtxt;
+use rustc::ty::{self, RegionVid, TyCtxt};
+use rustc_errors::DiagnosticBuilder;
+use syntax::ast;
+use syntax_pos::Span;
+
+use super::RegionInferenceContext;
+use super::super::universal_regions::NameOrigin;
+
+impl<'tcx> RegionInferenceContext<'tcx> {
+    /// Suggests declaring the bound `fr: outlived_fr`. This is only
+    /// possible if both regions can be named in the source, i.e. if
+    /// `fr` is a named (early- or late-bound) region and
+    /// `outlived_fr` is either a named region or `'static`.
+    ///
+    /// The bound is appended to the where-clause of the enclosing
+    /// item if it has one, and otherwise added to the declaration of
+    /// `fr` (e.g., `<'a, 'b: 'a>`). A bound `'x: 'static` always goes
+    /// on the declaration of `'x`.
+    pub(super) fn suggest_outlives_bound(
+        &self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        mir_def_id: DefId,
+        fr: RegionVid,
+        outlived_fr: RegionVid,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        let tcx = infcx.tcx;
+        let fr = self.universal_regions.representative(fr);
+        let outlived_fr = self.universal_regions.representative(outlived_fr);
+
+        let (fr_def_id, fr_name) = match self.definitions[fr].external_name {
+            Some(&ty::ReEarlyBound(data)) => (data.def_id, data.name),
+            Some(&ty::ReFree(ty::FreeRegion { bound_region: ty::BrNamed(def_id, name), .. })) => {
+                (def_id, name)
+            }
+            _ => return,
+        };
+        let (outlived_name, outlives_static) = match self.definitions[outlived_fr].external_name {
+            Some(&ty::ReEarlyBound(data)) => (format!("{}", data.name), false),
+            Some(&ty::ReFree(ty::FreeRegion { bound_region: ty::BrNamed(_, name), .. })) => {
+                (format!("{}", name), false)
+            }
+            Some(&ty::ReStatic) => (String::from("'static"), true),
+            _ => return,
+        };
+        let bound = format!("{}: {}", fr_name, outlived_name);
+
+        // A lifetime of the impl cannot be bounded by one of the method
+        // in its declaration, where the latter is not in scope, so
+        // then only the where-clause of the method will do.
+        let fr_on_parent = self.universal_regions.name_origin(fr) == Some(NameOrigin::Parent);
+        let outlived_on_item =
+            self.universal_regions.name_origin(outlived_fr) == Some(NameOrigin::Item);
+
+        let suggestion = if outlives_static {
+            declaration_suggestion(tcx, fr_def_id, &outlived_name)
+        } else {
+            let item_def_id = tcx.closure_base_def_id(mir_def_id);
+            let where_clause = where_clause_suggestion(tcx, item_def_id, &bound);
+            if fr_on_parent && outlived_on_item {
+                where_clause
+            } else {
+                where_clause.or_else(|| declaration_suggestion(tcx, fr_def_id, &outlived_name))
+            }
+        };
+
+        if let Some((span, suggestion)) = suggestion {
+            err.span_suggestion_short(
+                span,
+                &format!("consider adding the bound `{}`", bound),
+                suggestion,
+            );
+        }
+    }
+}
+
+/// Suggests appending `bound` to the where-clause of the item
+/// `item_def_id`, if that item has a non-empty where-clause.
+fn where_clause_suggestion(
+    tcx: TyCtxt<'_, '_, '_>,
+    item_def_id: DefId,
+    bound: &str,
+) -> Option<(Span, String)> {
+    let node_id = tcx.hir.as_local_node_id(item_def_id)?;
+    let generics = item_generics(tcx, node_id)?;
+    let span = match *generics.where_clause.predicates.last()? {
+        hir::WherePredicate::BoundPredicate(ref p) => p.span,
+        hir::WherePredicate::RegionPredicate(ref p) => p.span,
+        hir::WherePredicate::EqPredicate(ref p) => p.span,
+    };
+    let snippet = tcx.sess.codemap().span_to_snippet(span).ok()?;
+    Some((span, format!("{}, {}", snippet, bound)))
+}
+
+/// Suggests adding `outlived_name` to the bounds in the declaration
+/// of the lifetime parameter `lifetime_def_id`.
+fn declaration_suggestion(
+    tcx: TyCtxt<'_, '_, '_>,
+    lifetime_def_id: DefId,
+    outlived_name: &str,
+) -> Option<(Span, String)> {
+    let node_id = tcx.hir.as_local_node_id(lifetime_def_id)?;
+    let generics = item_generics(tcx, tcx.hir.get_parent(node_id))?;
+    let lifetime_def = generics
+        .lifetimes
+        .iter()
+        .find(|l| l.lifetime.id == node_id)?;
+
+    // An in-band lifetime has no declaration to add a bound to.
+    if lifetime_def.in_band {
+        return None;
+    }
+
+    Some(match lifetime_def.bounds.last() {
+        Some(last_bound) => (
+            last_bound.span,
+            format!("{} + {}", last_bound.name.name(), outlived_name),
+        ),
+        None => (
+            lifetime_def.lifetime.span,
+            format!("{}: {}", lifetime_def.lifetime.name.name(), outlived_name),
+        ),
+    })
+}
+
+fn item_generics<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    node_id: ast::NodeId,
+) -> Option<&'gcx hir::Generics> {
+    match tcx.hir.get(node_id) {
+        hir_map::NodeItem(item) => item.node.generics(),
+        hir_map::NodeTraitItem(item) => Some(&item.generics),
+        hir_map::NodeImplItem(item) => Some(&item.generics),
+        _ => None,
+    }
+}
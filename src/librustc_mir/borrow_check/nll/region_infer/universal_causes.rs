@@ -0,0 +1,250 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The solver treats every universal region as live at every point
+//! in the CFG, which is all it needs. When explaining an error,
+//! though, "it is universal" is not a very satisfying answer to the
+//! question of why a region is live. This module records the more
+//! specific facts that make each universal region observable in the
+//! body -- arguments whose types mention it, the return type, and
+//! constants that are used -- so that `-Z nll-dump-cause` can report
+//! them, both for errors between universal regions and for borrows
+//! that have to outlive the body because they flow into one.
+
+use rustc::mir::{Constant, Local, Location, Mir, TerminatorKind, RETURN_PLACE, START_BLOCK};
+use rustc::mir::visit::Visitor;
+use rustc::ty::{self, RegionVid, Ty, TyCtxt};
+use rustc::ty::fold::{TypeFoldable, TypeVisitor};
+use rustc::util::nodemap::FxHashMap;
+use rustc_errors::DiagnosticBuilder;
+use syntax_pos::Span;
+
+use super::RegionInferenceContext;
+use super::values::RegionElement;
+use super::super::universal_regions::UniversalRegions;
+
+/// A fact that makes a universal region live at some point in the
+/// body.
+#[derive(Clone, Debug)]
+pub(super) enum UniversalCause<'tcx> {
+    /// The region appears in the type of the argument `local`, which
+    /// is in scope from the start of the body.
+    Argument { local: Local, location: Location },
+
+    /// The region appears in the return type, which is observed by
+    /// the caller at this return point.
+    ReturnType { location: Location },
+
+    /// The region appears in the type `ty` of a constant used at
+    /// `location`.
+    Constant {
+        location: Location,
+        span: Span,
+        ty: Ty<'tcx>,
+    },
+}
+
+/// Computes the causes for each universal region in `mir`.
+pub(super) fn compute<'tcx>(
+    mir: &Mir<'tcx>,
+    universal_regions: &UniversalRegions<'tcx>,
+) -> FxHashMap<RegionVid, Vec<UniversalCause<'tcx>>> {
+    let mut causes = FxHashMap();
+
+    // Error recovery may leave us with a body without any blocks; its
+    // arguments and return type are of no interest then.
+    if mir.basic_blocks().is_empty() {
+        return causes;
+    }
+
+    let start = Location {
+        block: START_BLOCK,
+        statement_index: 0,
+    };
+    for local in mir.args_iter() {
+        for_each_universal_region(universal_regions, mir.local_decls[local].ty, |fr| {
+            add_cause(&mut causes, fr, UniversalCause::Argument { local, location: start });
+        });
+    }
+
+    for (block, data) in mir.basic_blocks().iter_enumerated() {
+        if let TerminatorKind::Return = data.terminator().kind {
+            let location = Location {
+                block,
+                statement_index: data.statements.len(),
+            };
+            for_each_universal_region(universal_regions, mir.local_decls[RETURN_PLACE].ty, |fr| {
+                add_cause(&mut causes, fr, UniversalCause::ReturnType { location });
+            });
+        }
+    }
+
+    ConstantVisitor {
+        universal_regions,
+        causes: &mut causes,
+    }.visit_mir(mir);
+
+    causes
+}
+
+fn add_cause<'tcx>(
+    causes: &mut FxHashMap<RegionVid, Vec<UniversalCause<'tcx>>>,
+    fr: RegionVid,
+    cause: UniversalCause<'tcx>,
+) {
+    causes.entry(fr).or_insert(vec![]).push(cause);
+}
+
+/// Invokes `callback` with each universal region that appears in
+/// `ty`. After renumbering, all free regions in the MIR are region
+/// variables, so there is no need to track binders here.
+fn for_each_universal_region<'tcx, F>(
+    universal_regions: &UniversalRegions<'tcx>,
+    ty: Ty<'tcx>,
+    callback: F,
+) where
+    F: FnMut(RegionVid),
+{
+    ty.visit_with(&mut UniversalRegionVisitor {
+        universal_regions,
+        callback,
+    });
+
+    struct UniversalRegionVisitor<'a, 'tcx: 'a, F> {
+        universal_regions: &'a UniversalRegions<'tcx>,
+        callback: F,
+    }
+
+    impl<'a, 'tcx, F> TypeVisitor<'tcx> for UniversalRegionVisitor<'a, 'tcx, F>
+    where
+        F: FnMut(RegionVid),
+    {
+        fn visit_region(&mut self, r: ty::Region<'tcx>) -> bool {
+            if let ty::ReVar(vid) = *r {
+                if self.universal_regions.is_universal_region(vid) {
+                    (self.callback)(vid);
+                }
+            }
+
+            false // keep visiting
+        }
+    }
+}
+
+struct ConstantVisitor<'a, 'tcx: 'a> {
+    universal_regions: &'a UniversalRegions<'tcx>,
+    causes: &'a mut FxHashMap<RegionVid, Vec<UniversalCause<'tcx>>>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for ConstantVisitor<'a, 'tcx> {
+    fn visit_constant(&mut self, constant: &Constant<'tcx>, location: Location) {
+        {
+            let causes = &mut *self.causes;
+            for_each_universal_region(self.universal_regions, constant.ty, |fr| {
+                add_cause(
+                    causes,
+                    fr,
+                    UniversalCause::Constant {
+                        location,
+                        span: constant.span,
+                        ty: constant.ty,
+                    },
+                );
+            });
+        }
+
+        self.super_constant(constant, location);
+    }
+}
+
+impl<'tcx> RegionInferenceContext<'tcx> {
+    /// Under `-Z nll-dump-cause`, adds notes to `err` explaining why
+    /// each universal region that ended up in the value of `r` is
+    /// live in the body. This is how the borrow checker explains why
+    /// a borrow flowing into, say, the return value must outlive the
+    /// body.
+    pub(crate) fn explain_universal_regions_in(
+        &self,
+        tcx: TyCtxt<'_, '_, 'tcx>,
+        mir: &Mir<'tcx>,
+        r: RegionVid,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        if !self.dumps_enabled || !tcx.sess.opts.debugging_opts.nll_dump_cause {
+            return;
+        }
+
+        let inferred_values = self.inferred_values.as_ref().unwrap();
+        for element in inferred_values.elements_contained_in(r) {
+            if let RegionElement::UniversalRegion(fr) = element {
+                self.explain_universal_region(mir, fr, err);
+            }
+        }
+    }
+
+    /// Adds a note to `err` for each recorded reason why the
+    /// universal region `fr` is live in the body.
+    pub(super) fn explain_universal_region(
+        &self,
+        mir: &Mir<'tcx>,
+        fr: RegionVid,
+        err: &mut DiagnosticBuilder<'_>,
+    ) {
+        let causes = match self.universal_causes.get(&fr) {
+            Some(causes) => causes,
+            None => return,
+        };
+
+        let fr_name = self.region_name(fr);
+        for cause in causes {
+            match *cause {
+                UniversalCause::Argument { local, .. } => {
+                    let decl = &mir.local_decls[local];
+                    let arg_name = match decl.name {
+                        Some(name) => format!("{}", name),
+                        None => format!("{:?}", local),
+                    };
+                    err.span_note(
+                        decl.source_info.span,
+                        &format!(
+                            "`{}` is live here because argument `{}: {}` is in scope",
+                            fr_name,
+                            arg_name,
+                            decl.ty
+                        ),
+                    );
+                }
+
+                UniversalCause::ReturnType { location } => {
+                    err.span_note(
+                        mir.source_info(location).span,
+                        &format!(
+                            "`{}` is live here because it appears in the return type `{}`",
+                            fr_name,
+                            mir.return_ty()
+                        ),
+                    );
+                }
+
+                UniversalCause::Constant { span, ty, .. } => {
+                    err.span_note(
+                        span,
+                        &format!(
+                            "`{}` is live here because it appears in the type `{}` \
+                             of this constant",
+                            fr_name,
+                            ty
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
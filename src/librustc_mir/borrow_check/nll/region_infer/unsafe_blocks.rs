@@ -0,0 +1,97 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Under `-Z nll-relaxed-unsafe`, region errors that are entirely due
+//! to constraints arising within `unsafe` blocks are reported through
+//! a (deny-by-default) lint rather than as hard errors. This module
+//! finds out which constraints those are.
+
+use rustc::hir::{self, intravisit};
+use rustc::hir::intravisit::{NestedVisitorMap, Visitor};
+use rustc::hir::def_id::DefId;
+use rustc::ty::{RegionVid, TyCtxt};
+use syntax_pos::Span;
+
+use super::{Constraint, RegionInferenceContext};
+
+/// The spans of the user-written `unsafe` blocks in a body, including
+/// those in the bodies of the closures within it.
+pub(super) struct UnsafeBlockSpans {
+    spans: Vec<Span>,
+}
+
+impl UnsafeBlockSpans {
+    /// Collects the `unsafe` blocks of the body that `mir_def_id`
+    /// belongs to. For a closure, we look at the enclosing item, so
+    /// that an `unsafe` block around the closure counts as well.
+    pub(super) fn new(tcx: TyCtxt<'_, '_, '_>, mir_def_id: DefId) -> Self {
+        let base_def_id = tcx.closure_base_def_id(mir_def_id);
+        let node_id = tcx.hir.as_local_node_id(base_def_id).unwrap();
+        let body = tcx.hir.body(tcx.hir.body_owned_by(node_id));
+
+        let mut collector = UnsafeBlockCollector { tcx, spans: vec![] };
+        collector.visit_body(body);
+        UnsafeBlockSpans { spans: collector.spans }
+    }
+
+    /// True if `span` lies within one of the `unsafe` blocks.
+    pub(super) fn contain(&self, span: Span) -> bool {
+        self.spans.iter().any(|unsafe_span| unsafe_span.contains(span))
+    }
+}
+
+struct UnsafeBlockCollector<'a, 'gcx: 'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    spans: Vec<Span>,
+}
+
+impl<'a, 'gcx, 'tcx> Visitor<'gcx> for UnsafeBlockCollector<'a, 'gcx, 'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'gcx> {
+        NestedVisitorMap::All(&self.tcx.hir)
+    }
+
+    fn visit_block(&mut self, block: &'gcx hir::Block) {
+        if let hir::UnsafeBlock(hir::UserProvided) = block.rules {
+            self.spans.push(block.span);
+        }
+        intravisit::walk_block(self, block);
+    }
+}
+
+impl<'tcx> RegionInferenceContext<'tcx> {
+    /// True if every constraint that contributes to `fr1` containing
+    /// `fr2` (see `blamed_constraints`) arose within an `unsafe`
+    /// block.
+    pub(super) fn is_error_within_unsafe(
+        &self,
+        unsafe_blocks: &UnsafeBlockSpans,
+        fr1: RegionVid,
+        fr2: RegionVid,
+    ) -> bool {
+        let mut blamed = self.blamed_constraints(fr1, fr2).peekable();
+        blamed.peek().is_some() && blamed.all(|constraint| {
+            unsafe_blocks.contain(constraint.closure_blame_span.unwrap_or(constraint.span))
+        })
+    }
+
+    /// The constraints `'X: 'Y` on some path from `fr1` to `fr2`,
+    /// i.e., such that `fr1` depends on `'X` and `'Y` depends on
+    /// `fr2`. Together, these are what made `fr1` contain `fr2`.
+    fn blamed_constraints<'a>(
+        &'a self,
+        fr1: RegionVid,
+        fr2: RegionVid,
+    ) -> impl Iterator<Item = &'a Constraint> + 'a {
+        let influenced_fr1 = self.dependencies(fr1);
+        self.constraints.iter().filter(move |constraint| {
+            influenced_fr1[constraint.sup] && self.dependencies(constraint.sub)[fr2]
+        })
+    }
+}
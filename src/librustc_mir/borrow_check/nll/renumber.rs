@@ -10,13 +10,13 @@
 
 use rustc_data_structures::indexed_vec::Idx;
 use rustc::ty::subst::Substs;
-use rustc::ty::{self, ClosureSubsts, Ty, TypeFoldable};
+use rustc::ty::{self, ClosureSubsts, GeneratorInterior, Ty, TypeFoldable};
 use rustc::mir::{BasicBlock, Local, Location, Mir, Statement, StatementKind};
 use rustc::mir::RETURN_PLACE;
 use rustc::mir::visit::{MutVisitor, TyContext};
 use rustc::infer::{InferCtxt, NLLRegionVariableOrigin};
 
-use super::ToRegionVid;
+use super::{fold_regions_unless_region_free, ToRegionVid};
 use super::universal_regions::UniversalRegions;
 
 /// Replaces all free regions appearing in the MIR with fresh
@@ -25,26 +25,48 @@ pub fn renumber_mir<'a, 'gcx, 'tcx>(
     infcx: &InferCtxt<'a, 'gcx, 'tcx>,
     universal_regions: &UniversalRegions<'tcx>,
     mir: &mut Mir<'tcx>,
+    tainted_by_errors: bool,
 ) {
-    debug!("renumber_mir()");
+    debug!("renumber_mir(tainted_by_errors={:?})", tainted_by_errors);
     debug!("renumber_mir: mir.arg_count={:?}", mir.arg_count);
 
     // Update the return type and types of the arguments based on the
-    // `universal_regions` computation.
-    debug!("renumber_mir: output_ty={:?}", universal_regions.output_ty);
-    mir.local_decls[RETURN_PLACE].ty = universal_regions.output_ty;
-    for (&input_ty, local) in universal_regions
-        .input_tys
-        .iter()
-        .zip((1..).map(Local::new))
-    {
-        debug!("renumber_mir: input_ty={:?} local={:?}", input_ty, local);
-        mir.local_decls[local].ty = input_ty;
+    // `universal_regions` computation. If the body failed to
+    // type-check, the MIR is just a stand-in (see `construct_error`)
+    // without any arguments, so there is nothing to update. A return
+    // type with `impl Trait` in it keeps the hidden types it was built
+    // with; they get fresh variables like any other local.
+    //
+    // The inputs only fail to line up with the arguments of the MIR
+    // after an error (see `compute_inputs_and_output`); the arguments
+    // left without an input are then renumbered like any other local.
+    let anon_output = !tainted_by_errors && universal_regions.anon_output_ty().is_some();
+    let mut arg_count = 0;
+    if !tainted_by_errors {
+        debug!("renumber_mir: output_ty={:?}", universal_regions.output_ty);
+        if !anon_output {
+            mir.local_decls[RETURN_PLACE].ty = universal_regions.output_ty;
+        }
+        for (&input_ty, local) in universal_regions
+            .input_tys
+            .iter()
+            .zip((1..mir.arg_count + 1).map(Local::new))
+        {
+            arg_count += 1;
+            debug!("renumber_mir: input_ty={:?} local={:?}", input_ty, local);
+            mir.local_decls[local].ty = input_ty;
+        }
+    }
+
+    if let Some(yield_ty) = universal_regions.yield_ty {
+        debug!("renumber_mir: yield_ty={:?}", yield_ty);
+        mir.yield_ty = Some(yield_ty);
     }
 
     let mut visitor = NLLVisitor {
         infcx,
-        arg_count: mir.arg_count,
+        arg_count,
+        anon_output,
     };
     visitor.visit_mir(mir);
 }
@@ -52,6 +74,10 @@ pub fn renumber_mir<'a, 'gcx, 'tcx>(
 struct NLLVisitor<'a, 'gcx: 'a + 'tcx, 'tcx: 'a> {
     infcx: &'a InferCtxt<'a, 'gcx, 'tcx>,
     arg_count: usize,
+
+    /// True if the return place holds the hidden types of `impl
+    /// Trait`s, and hence is to be renumbered.
+    anon_output: bool,
 }
 
 impl<'a, 'gcx, 'tcx> NLLVisitor<'a, 'gcx, 'tcx> {
@@ -64,12 +90,11 @@ impl<'a, 'gcx, 'tcx> NLLVisitor<'a, 'gcx, 'tcx> {
     {
         debug!("renumber_regions(value={:?})", value);
 
-        self.infcx
-            .tcx
-            .fold_regions(value, &mut false, |_region, _depth| {
-                let origin = NLLRegionVariableOrigin::Inferred(ty_context);
-                self.infcx.next_nll_region_var(origin)
-            })
+        let infcx = self.infcx;
+        fold_regions_unless_region_free(infcx.tcx, value, |_region, _depth| {
+            let origin = NLLRegionVariableOrigin::Inferred(ty_context);
+            infcx.next_nll_region_var(origin)
+        })
     }
 
     /// Checks that all the regions appearing in `value` have already
@@ -87,6 +112,9 @@ impl<'a, 'gcx, 'tcx> NLLVisitor<'a, 'gcx, 'tcx> {
 
     fn is_argument_or_return_slot(&self, local: Local) -> bool {
         // The first argument is return slot, next N are arguments.
+        if local == RETURN_PLACE {
+            return !self.anon_output;
+        }
         local.index() <= self.arg_count
     }
 }
@@ -95,6 +123,9 @@ impl<'a, 'gcx, 'tcx> MutVisitor<'tcx> for NLLVisitor<'a, 'gcx, 'tcx> {
     fn visit_ty(&mut self, ty: &mut Ty<'tcx>, ty_context: TyContext) {
         let is_arg = match ty_context {
             TyContext::LocalDecl { local, .. } => self.is_argument_or_return_slot(local),
+            // This is only a copy of the type of the return place,
+            // whose hidden types are renumbered with the local decls.
+            TyContext::ReturnTy(..) if self.anon_output => return,
             TyContext::ReturnTy(..) => true,
             TyContext::Location(..) => false,
         };
@@ -151,6 +182,23 @@ impl<'a, 'gcx, 'tcx> MutVisitor<'tcx> for NLLVisitor<'a, 'gcx, 'tcx> {
         debug!("visit_closure_substs: substs={:?}", substs);
     }
 
+    fn visit_generator_interior(
+        &mut self,
+        interior: &mut GeneratorInterior<'tcx>,
+        location: Location,
+    ) {
+        debug!(
+            "visit_generator_interior(interior={:?}, location={:?})",
+            interior,
+            location
+        );
+
+        let ty_context = TyContext::Location(location);
+        *interior = self.renumber_regions(ty_context, interior);
+
+        debug!("visit_generator_interior: interior={:?}", interior);
+    }
+
     fn visit_statement(
         &mut self,
         block: BasicBlock,
@@ -9,13 +9,21 @@
 // except according to those terms.
 
 use rustc::mir::Mir;
+use rustc::infer::{InferCtxt, NLLRegionVariableOrigin, SubregionOrigin};
 use rustc::infer::region_constraints::Constraint;
 use rustc::infer::region_constraints::RegionConstraintData;
+use rustc::infer::region_constraints::{Verify, VerifyBound};
 use rustc::ty;
+use std::collections::BTreeMap;
+use std::mem;
+use syntax_pos::Span;
+use transform::type_check::AnonTypeDefinition;
+use transform::type_check::Locations;
 use transform::type_check::MirTypeckRegionConstraints;
 use transform::type_check::OutlivesSet;
 
-use super::region_infer::RegionInferenceContext;
+use super::region_infer::{AnonTypeCapture, RegionInferenceContext, RegionTest, TypeTest};
+use super::universal_regions::UniversalRegions;
 
 /// When the MIR type-checker executes, it validates all the types in
 /// the MIR, and in the process generates a set of constraints that
@@ -33,6 +41,113 @@ pub(super) fn generate<'tcx>(
     }.generate(constraints);
 }
 
+/// Replaces each occurrence of `ReEmpty` in `constraints` with a
+/// fresh region variable. `ReEmpty` is outlived by every region, so a
+/// variable that starts out empty is a faithful stand-in wherever it
+/// is the smaller region of a constraint. Giving each occurrence a
+/// variable of its own keeps unrelated constraints on the empty
+/// region from being merged through a shared variable. This has to
+/// happen before the `RegionInferenceContext` is created, as it
+/// takes the region variables out of `infcx`.
+pub(super) fn replace_empty_regions<'tcx>(
+    infcx: &InferCtxt<'_, '_, 'tcx>,
+    constraints: &mut MirTypeckRegionConstraints<'tcx>,
+) {
+    let replace = |r: ty::Region<'tcx>| match *r {
+        ty::ReEmpty => infcx.next_nll_region_var(NLLRegionVariableOrigin::Empty),
+        _ => r,
+    };
+
+    fn replace_in_verify_bound<'tcx>(
+        verify_bound: &mut VerifyBound<'tcx>,
+        replace: &Fn(ty::Region<'tcx>) -> ty::Region<'tcx>,
+    ) {
+        match verify_bound {
+            VerifyBound::AnyRegion(rs) | VerifyBound::AllRegions(rs) => {
+                for r in rs {
+                    *r = replace(*r);
+                }
+            }
+            VerifyBound::AnyBound(bounds) | VerifyBound::AllBounds(bounds) => {
+                for bound in bounds {
+                    replace_in_verify_bound(bound, replace);
+                }
+            }
+        }
+    }
+
+    for OutlivesSet { data, .. } in &mut constraints.outlives_sets {
+        let old_constraints = mem::replace(&mut data.constraints, BTreeMap::new());
+        for (constraint, origin) in old_constraints {
+            let constraint = match constraint {
+                Constraint::VarSubVar(..) => constraint,
+                Constraint::RegSubVar(a, b) => Constraint::RegSubVar(replace(a), b),
+                Constraint::VarSubReg(a, b) => Constraint::VarSubReg(a, replace(b)),
+                Constraint::RegSubReg(a, b) => Constraint::RegSubReg(replace(a), replace(b)),
+            };
+            data.constraints.insert(constraint, origin);
+        }
+
+        for verify in &mut data.verifys {
+            verify.region = replace(verify.region);
+            replace_in_verify_bound(&mut verify.bound, &replace);
+        }
+    }
+
+    for anon_type in &mut constraints.anon_types {
+        for r in &mut anon_type.bound_regions {
+            *r = replace(*r);
+        }
+    }
+}
+
+/// Creates the placeholders for the skolemized regions that
+/// `constraints` mention (see
+/// `UniversalRegions::create_placeholder_region`), so that `generate`
+/// can map them to region vids. This has to happen before the
+/// `RegionInferenceContext` is created, as it takes the region
+/// variables out of `infcx`.
+pub(super) fn create_placeholder_regions<'tcx>(
+    infcx: &InferCtxt<'_, '_, 'tcx>,
+    universal_regions: &mut UniversalRegions<'tcx>,
+    constraints: &MirTypeckRegionConstraints<'tcx>,
+) {
+    fn verify_bound_regions<'tcx>(
+        verify_bound: &VerifyBound<'tcx>,
+        regions: &mut Vec<ty::Region<'tcx>>,
+    ) {
+        match verify_bound {
+            VerifyBound::AnyRegion(rs) | VerifyBound::AllRegions(rs) => {
+                regions.extend(rs.iter().cloned())
+            }
+            VerifyBound::AnyBound(bounds) | VerifyBound::AllBounds(bounds) => {
+                for bound in bounds {
+                    verify_bound_regions(bound, regions);
+                }
+            }
+        }
+    }
+
+    let mut regions = vec![];
+    for OutlivesSet { data, .. } in &constraints.outlives_sets {
+        for constraint in data.constraints.keys() {
+            match *constraint {
+                Constraint::VarSubVar(..) => {}
+                Constraint::RegSubVar(r, _) | Constraint::VarSubReg(_, r) => regions.push(r),
+                Constraint::RegSubReg(a, b) => regions.extend(&[a, b]),
+            }
+        }
+        for verify in &data.verifys {
+            regions.push(verify.region);
+            verify_bound_regions(&verify.bound, &mut regions);
+        }
+    }
+
+    for r in regions {
+        universal_regions.create_placeholder_region(infcx, r);
+    }
+}
+
 struct SubtypeConstraintGenerator<'cx, 'tcx: 'cx> {
     regioncx: &'cx mut RegionInferenceContext<'tcx>,
     mir: &'cx Mir<'tcx>,
@@ -43,6 +158,7 @@ impl<'cx, 'tcx> SubtypeConstraintGenerator<'cx, 'tcx> {
         let MirTypeckRegionConstraints {
             liveness_set,
             outlives_sets,
+            anon_types,
         } = constraints;
 
         debug!(
@@ -57,7 +173,7 @@ impl<'cx, 'tcx> SubtypeConstraintGenerator<'cx, 'tcx> {
             self.regioncx.add_live_point(region_vid, *location);
         }
 
-        for OutlivesSet { locations, data } in outlives_sets {
+        for OutlivesSet { locations, data, declared_type } in outlives_sets {
             debug!("generate: constraints at: {:#?}", locations);
             let RegionConstraintData {
                 constraints,
@@ -65,7 +181,9 @@ impl<'cx, 'tcx> SubtypeConstraintGenerator<'cx, 'tcx> {
                 givens,
             } = data;
 
-            for constraint in constraints.keys() {
+            let span = self.mir.source_info(locations.from_location).span;
+
+            for (constraint, origin) in constraints {
                 debug!("generate: constraint: {:?}", constraint);
                 let (a_vid, b_vid) = match constraint {
                     Constraint::VarSubVar(a_vid, b_vid) => (*a_vid, *b_vid),
@@ -76,22 +194,103 @@ impl<'cx, 'tcx> SubtypeConstraintGenerator<'cx, 'tcx> {
                     }
                 };
 
+                // If this constraint was imposed by the requirements
+                // of a closure, remember where in the closure body it
+                // came from, so that errors can point there.
+                let closure_blame_span = match *origin {
+                    SubregionOrigin::ClosureRequirement(blame_span) => Some(blame_span),
+                    _ => None,
+                };
+
                 // We have the constraint that `a_vid <= b_vid`. Add
                 // `b_vid: a_vid` to our region checker. Note that we
                 // reverse direction, because `regioncx` talks about
                 // "outlives" (`>=`) whereas the region constraints
                 // talk about `<=`.
-                let span = self.mir.source_info(locations.from_location).span;
-                self.regioncx
-                    .add_outlives(span, b_vid, a_vid, locations.at_location);
+                self.regioncx.add_outlives(
+                    span,
+                    b_vid,
+                    a_vid,
+                    locations.at_location,
+                    closure_blame_span,
+                    *declared_type,
+                );
+            }
+
+            for verify in verifys {
+                let type_test = self.verify_to_type_test(verify, span, locations);
+                self.regioncx.add_type_test(type_test);
             }
 
-            assert!(verifys.is_empty(), "verifys not yet implemented");
             assert!(
                 givens.is_empty(),
                 "MIR type-checker does not use givens (thank goodness)"
             );
         }
+
+        for AnonTypeDefinition { def_id, hidden_ty, bound_regions } in anon_types {
+            debug!("generate: hidden type {:?} of {:?}", hidden_ty, def_id);
+            self.regioncx.add_anon_type_capture(AnonTypeCapture {
+                def_id: *def_id,
+                hidden_ty: *hidden_ty,
+                bound_regions: bound_regions.iter().map(|r| self.to_region_vid(r)).collect(),
+            });
+        }
+    }
+
+    fn verify_to_type_test(
+        &self,
+        verify: &Verify<'tcx>,
+        span: Span,
+        locations: &Locations,
+    ) -> TypeTest<'tcx> {
+        let generic_kind = verify.kind;
+
+        let lower_bound = self.to_region_vid(verify.region);
+
+        let point = locations.at_location;
+
+        let test = self.verify_bound_to_region_test(&verify.bound);
+
+        let closure_blame_span = match verify.origin {
+            SubregionOrigin::ClosureRequirement(blame_span) => Some(blame_span),
+            _ => None,
+        };
+
+        TypeTest {
+            generic_kind,
+            lower_bound,
+            point,
+            span,
+            closure_blame_span,
+            test,
+        }
+    }
+
+    fn verify_bound_to_region_test(&self, verify_bound: &VerifyBound<'tcx>) -> RegionTest {
+        match verify_bound {
+            VerifyBound::AnyRegion(regions) => RegionTest::IsOutlivedByAnyRegionIn(
+                regions.iter().map(|r| self.to_region_vid(r)).collect(),
+            ),
+
+            VerifyBound::AllRegions(regions) => RegionTest::IsOutlivedByAllRegionsIn(
+                regions.iter().map(|r| self.to_region_vid(r)).collect(),
+            ),
+
+            VerifyBound::AnyBound(bounds) => RegionTest::Any(
+                bounds
+                    .iter()
+                    .map(|b| self.verify_bound_to_region_test(b))
+                    .collect(),
+            ),
+
+            VerifyBound::AllBounds(bounds) => RegionTest::All(
+                bounds
+                    .iter()
+                    .map(|b| self.verify_bound_to_region_test(b))
+                    .collect(),
+            ),
+        }
     }
 
     fn to_region_vid(&self, r: ty::Region<'tcx>) -> ty::RegionVid {
@@ -22,26 +22,52 @@
 //! The code in this file doesn't *do anything* with those results; it
 //! just returns them for other code to use.
 
-use rustc::hir::HirId;
+use rustc::hir::{self, BodyOwnerKind, HirId};
 use rustc::hir::def_id::DefId;
+use rustc::hir::map as hir_map;
+use rustc::hir::map::definitions::DefPathData;
 use rustc::infer::{InferCtxt, NLLRegionVariableOrigin};
 use rustc::infer::region_constraints::GenericKind;
 use rustc::infer::outlives::bounds::{self, OutlivesBound};
+use rustc::infer::resolve::OpportunisticTypeAndRegionResolver;
+use rustc::mir::{Mir, UniversalRegionsSummary};
+use rustc::session::Session;
+use rustc::traits::{self, FulfillmentContext, Normalized, ObligationCause, SelectionContext};
 use rustc::ty::{self, RegionVid, Ty, TyCtxt};
 use rustc::ty::fold::TypeFoldable;
 use rustc::ty::subst::Substs;
-use rustc::util::nodemap::FxHashMap;
+use rustc::util::nodemap::{FxHashMap, FxHashSet};
 use rustc_data_structures::indexed_vec::{Idx, IndexVec};
 use rustc_data_structures::transitive_relation::TransitiveRelation;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
 use std::iter;
+use std::ops::Range;
+use syntax::abi::Abi;
 use syntax::ast;
+use syntax::symbol::keywords;
+use syntax_pos::Span;
 
-use super::ToRegionVid;
+use super::{fold_regions_unless_region_free, ToRegionVid};
+use super::delayed_bugs::delay_nll_bug;
+use super::free_region_relations::UniversalRegionRelations;
+use super::redundant_bounds::item_generics;
 
 #[derive(Debug)]
 pub struct UniversalRegions<'tcx> {
+    /// The MIR body that these are the universal regions of.
+    mir_def_id: DefId,
+
     indices: UniversalRegionIndices<'tcx>,
 
+    /// The names of the late-bound regions liberated in `build`,
+    /// recovered from the map that `replace_late_bound_regions`
+    /// returns. As those regions are not in `indices`, they are named
+    /// separately; see `name_region` and `named_universal_regions`.
+    late_bound_names: FxHashMap<RegionVid, LateBoundName<'tcx>>,
+
     /// The vid assigned to `'static`
     pub fr_static: RegionVid,
 
@@ -72,6 +98,10 @@ pub struct UniversalRegions<'tcx> {
     /// regions replaced by their universal `RegionVid` equivalents.
     pub input_tys: &'tcx [Ty<'tcx>],
 
+    /// For a generator, the type of the values it yields, with all
+    /// regions replaced by their universal `RegionVid` equivalents.
+    pub yield_ty: Option<Ty<'tcx>>,
+
     /// Each RBP `('a, GK)` indicates that `GK: 'a` can be assumed to
     /// be true. These encode relationships like `T: 'a` that are
     /// added via implicit bounds.
@@ -84,10 +114,173 @@ pub struct UniversalRegions<'tcx> {
     /// our special inference variable there, we would mess that up.
     pub region_bound_pairs: Vec<(ty::Region<'tcx>, GenericKind<'tcx>)>,
 
+    /// Where each entry of `region_bound_pairs` came from, at the
+    /// same index; see `region_bound_pair_origin`.
+    region_bound_pair_origins: Vec<RegionBoundPairOrigin>,
+
+    /// Each outlives relation `'a: 'b` between universal regions that
+    /// was added from a where-clause or an implied bound, in the
+    /// order they were added. The reflexive relations and those of
+    /// `'static`, which hold anyway, are only listed if one of these
+    /// gave them (see `static_bound_origins`).
+    pub outlives_facts: Vec<OutlivesFact>,
+
+    /// Maps each free region of `defining_ty` to the region it
+    /// replaced in the defining type as recorded by typeck. For a
+    /// closure, the external regions thus map to the regions of the
+    /// closure's creator (the same ones that `closure_mapping` finds
+    /// in the creator), which often have names.
+    external_origins: FxHashMap<RegionVid, ty::Region<'tcx>>,
+
+    /// For each index into `input_tys`, the universal regions that
+    /// appear in the type of that input, in order of appearance.
+    argument_regions: Vec<Vec<RegionVid>>,
+
+    /// The inverse of `argument_regions`: for each universal region,
+    /// the indices into `input_tys` of the inputs whose types mention
+    /// it, in increasing order.
+    argument_positions: FxHashMap<RegionVid, Vec<usize>>,
+
+    /// The universal regions that appear in `output_ty`.
+    output_regions: FxHashSet<RegionVid>,
+
     relations: UniversalRegionRelations,
+
+    /// Caches the answers of `non_local_upper_bound` and
+    /// `non_local_lower_bound`, which closure constraint propagation
+    /// asks repeatedly for the same regions. The relations never
+    /// change once built, so the answers never go stale.
+    non_local_bounds: NonLocalBoundCache,
+}
+
+/// How to refer to a late-bound region of the signature.
+#[derive(Copy, Clone, Debug)]
+enum LateBoundName<'tcx> {
+    /// A region declared with a name, like the `'a` of
+    /// `fn foo<'a>(x: &'a u32)`. We keep the free region that stands
+    /// for it elsewhere in the compiler (e.g., `ReFree(foo, 'a)`),
+    /// together with its name.
+    Named(ty::Region<'tcx>, ast::Name),
+
+    /// An anonymous region, referred to as `'1`, `'2`, etc. The
+    /// numbers are assigned in the order in which the parameters,
+    /// and then the return type, first mention the regions, so that
+    /// they do not depend on how the region vids happen to be
+    /// numbered.
+    Anonymous(usize),
+}
+
+/// Where a named universal region was declared; see
+/// `UniversalRegions::named_universal_regions` and
+/// `UniversalRegions::static_region`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NameOrigin {
+    /// `'static`.
+    Static,
+
+    /// A lifetime parameter of the impl or trait that the fn being
+    /// checked belongs to, like the `'a` of `impl<'a> Foo<'a> { fn
+    /// m(&self) { .. } }`. These come first in the identity substs
+    /// of the fn.
+    Parent,
+
+    /// A lifetime parameter of the fn itself, be it early- or
+    /// late-bound.
+    Item,
+}
+
+/// The name of a universal region for use in diagnostics; see
+/// `UniversalRegions::name_region`.
+#[derive(Clone, Debug)]
+pub struct RegionName {
+    /// How to refer to the region, e.g. `'a`, or `'1` for an
+    /// anonymous region.
+    pub name: String,
+
+    /// For an anonymous region that is the lifetime of a reference in
+    /// the type of a parameter (like the `&u32` of `x: &u32`), the
+    /// span of that reference type, together with a description of
+    /// the region.
+    pub highlight: Option<(Span, String)>,
+}
+
+impl fmt::Display for RegionName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Where a region-bound pair `GK: 'a` came from, so that errors can
+/// explain why the bound is (or is not) known to hold.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RegionBoundPairOrigin {
+    /// A where-clause (or other predicate) of the item. The span is
+    /// that of the bound as written, or of the item if the bound is
+    /// not written on it.
+    FromWhereClause(Span),
+
+    /// Implied by the type of the input with the given index (for a
+    /// closure or generator, input 0 is the closure or generator
+    /// itself).
+    ImpliedByInput(usize),
+
+    /// Implied by the return type.
+    ImpliedByOutput,
+
+    /// Implied by the type of the upvar with the given index,
+    /// captured by a closure or generator.
+    ImpliedByUpvar(usize),
+}
+
+/// A relation `sup: sub` between two universal regions, and where it
+/// came from.
+#[derive(Copy, Clone, Debug)]
+pub struct OutlivesFact {
+    pub sup: RegionVid,
+    pub sub: RegionVid,
+    pub origin: RegionBoundPairOrigin,
+}
+
+/// Which of `non_local_upper_bound` and `non_local_lower_bound` an
+/// entry in the `non_local_bounds` cache is for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum BoundDirection {
+    Upper,
+    Lower,
+}
+
+/// The answers of `non_local_upper_bound` and `non_local_lower_bound`
+/// computed so far. With debug assertions, a hit is checked against a
+/// fresh computation.
+#[derive(Default)]
+struct NonLocalBoundCache {
+    map: RefCell<FxHashMap<(BoundDirection, RegionVid), Option<RegionVid>>>,
+}
+
+impl NonLocalBoundCache {
+    fn get_or_compute<F>(
+        &self,
+        direction: BoundDirection,
+        fr: RegionVid,
+        compute: F,
+    ) -> Option<RegionVid> where
+        F: Fn() -> Option<RegionVid>,
+    {
+        let cached = self.map.borrow().get(&(direction, fr)).cloned();
+        match cached {
+            Some(bound) => {
+                debug_assert_eq!(bound, compute(), "stale non-local bound for {:?}", fr);
+                bound
+            }
+            None => {
+                let bound = compute();
+                self.map.borrow_mut().insert((direction, fr), bound);
+                bound
+            }
+        }
+    }
 }
 
-#[derive(Debug)]
 struct UniversalRegionIndices<'tcx> {
     /// For those regions that may appear in the parameter environment
     /// ('static and early-bound regions), we maintain a map from the
@@ -99,20 +292,54 @@ struct UniversalRegionIndices<'tcx> {
     /// contains an entry for `ReStatic` -- it might be nice to just
     /// use a substs, and then handle `ReStatic` another way.
     indices: FxHashMap<ty::Region<'tcx>, RegionVid>,
+
+    /// Those early-bound regions in `indices` that are declared on
+    /// the impl or trait that the fn belongs to, rather than on the
+    /// fn itself.
+    parent_regions: FxHashSet<ty::Region<'tcx>>,
+
+    /// The vid that `ReErased` is mapped to; see `to_region_vid`.
+    fr_static: RegionVid,
+
+    /// The vids that skolemized regions are mapped to, as they are
+    /// created (see `UniversalRegions::create_placeholder_region`).
+    placeholders: FxHashMap<ty::Region<'tcx>, RegionVid>,
+
+    /// The placeholders are created back to back, so their vids are
+    /// the range that starts at `first_placeholder_index`; this is
+    /// the bound region that each one stands for, in vid order.
+    first_placeholder_index: usize,
+    placeholder_bound_regions: Vec<ty::BoundRegion>,
+
+    /// The span of the body, for reporting unexpected regions.
+    span: Span,
+
+    /// The session, for delaying a bug on unexpected regions.
+    sess: &'tcx Session,
 }
 
-#[derive(Debug)]
-struct UniversalRegionRelations {
-    /// Stores the outlives relations that are known to hold from the
-    /// implied bounds, in-scope where clauses, and that sort of
-    /// thing.
-    outlives: TransitiveRelation<RegionVid>,
-
-    /// This is the `<=` relation; that is, if `a: b`, then `b <= a`,
-    /// and we store that here. This is useful when figuring out how
-    /// to express some local region in terms of external regions our
-    /// caller will understand.
-    inverse_outlives: TransitiveRelation<RegionVid>,
+// `Session` is not `Debug`, so the session is left out.
+impl<'tcx> fmt::Debug for UniversalRegionIndices<'tcx> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("UniversalRegionIndices")
+            .field("indices", &self.indices)
+            .field("parent_regions", &self.parent_regions)
+            .field("fr_static", &self.fr_static)
+            .field("placeholders", &self.placeholders)
+            .field("first_placeholder_index", &self.first_placeholder_index)
+            .field("placeholder_bound_regions", &self.placeholder_bound_regions)
+            .field("span", &self.span)
+            .finish()
+    }
+}
+
+/// Error returned by `UniversalRegions::closure_mapping` when the
+/// free regions of a closure type do not line up with the external
+/// vids recorded in its `ClosureRegionRequirements`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ClosureMappingError {
+    pub expected_num_vars: usize,
+    pub found_num_vars: usize,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -155,6 +382,16 @@ pub enum RegionClassification {
     /// `foo`, then `'a` and `'b` are local (and `'x` is not in
     /// scope).
     Local,
+
+    /// A **placeholder** region stands for the bound region of a
+    /// higher-ranked obligation, like the `'x` in `for<'x> &'x T:
+    /// Trait`, which shows up skolemized in the constraints of the
+    /// body. It must be satisfied for any region at all, so we know
+    /// nothing it outlives. Placeholders are not in the index range
+    /// of the other universal regions, as they are only created once
+    /// the body has been type-checked (see
+    /// `UniversalRegions::create_placeholder_region`).
+    Placeholder,
 }
 
 const FIRST_GLOBAL_INDEX: usize = 0;
@@ -165,26 +402,38 @@ impl<'tcx> UniversalRegions<'tcx> {
     /// MIR -- that is, all the regions that appear in the function's
     /// signature. This will also compute the relationships that are
     /// known between those regions.
-    pub fn new(
-        infcx: &InferCtxt<'_, '_, 'tcx>,
+    ///
+    /// The type of a closure or generator comes from the typeck
+    /// tables of its enclosing item; a caller that already has those
+    /// (`tables`) can pass them in to spare us the lookup.
+    pub fn new<'gcx>(
+        infcx: &InferCtxt<'_, 'gcx, 'tcx>,
         mir_def_id: DefId,
         param_env: ty::ParamEnv<'tcx>,
+        tables: Option<&'gcx ty::TypeckTables<'gcx>>,
+    ) -> Self {
+        UniversalRegionsBuilder::new(infcx, mir_def_id, param_env, tables, None).build()
+    }
+
+    /// Creates the `UniversalRegions` of the MIR of a compiler-generated
+    /// shim (drop glue, a `Clone` shim, a fn-pointer shim, ...) for the
+    /// shimmed item `instance`. A shim has neither HIR nor typeck
+    /// tables, so its defining type is the fn pointer type of its
+    /// signature, as read off the arguments and return place of
+    /// `mir`. No caller can name the regions of a shim, so they are
+    /// all local.
+    pub fn new_for_shim(
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        instance: ty::InstanceDef<'tcx>,
+        param_env: ty::ParamEnv<'tcx>,
+        mir: &Mir<'tcx>,
     ) -> Self {
         let tcx = infcx.tcx;
-        let mir_node_id = tcx.hir.as_local_node_id(mir_def_id).unwrap();
-        let mir_hir_id = tcx.hir.node_to_hir_id(mir_node_id);
-        UniversalRegionsBuilder {
-            infcx,
-            mir_def_id,
-            mir_node_id,
-            mir_hir_id,
-            param_env,
-            region_bound_pairs: vec![],
-            relations: UniversalRegionRelations {
-                outlives: TransitiveRelation::new(),
-                inverse_outlives: TransitiveRelation::new(),
-            },
-        }.build()
+        let inputs = mir.args_iter().map(|local| mir.local_decls[local].ty);
+        let output = mir.return_ty();
+        let sig = tcx.mk_fn_sig(inputs, output, false, hir::Unsafety::Normal, Abi::Rust);
+        let mir_def_id = instance.def_id();
+        UniversalRegionsBuilder::new(infcx, mir_def_id, param_env, None, Some(sig)).build()
     }
 
     /// Given a reference to a closure type, extracts all the values
@@ -196,29 +445,28 @@ impl<'tcx> UniversalRegions<'tcx> {
     /// if the `ClosureRegionRequirements` contains something like
     /// `'1: '2`, then the caller would impose the constraint that
     /// `V[1]: V[2]`.
+    ///
+    /// Returns an error if the number of free regions found in the
+    /// closure type does not match `expected_num_vars` (the number of
+    /// external vids that the closure's own borrowck reported).
     pub fn closure_mapping(
         infcx: &InferCtxt<'_, '_, 'tcx>,
         closure_ty: Ty<'tcx>,
         expected_num_vars: usize,
-    ) -> IndexVec<RegionVid, ty::Region<'tcx>> {
-        let mut region_mapping = IndexVec::with_capacity(expected_num_vars);
-        region_mapping.push(infcx.tcx.types.re_static);
+    ) -> Result<IndexVec<RegionVid, ty::Region<'tcx>>, ClosureMappingError> {
+        let mut free_regions = vec![];
         infcx.tcx.for_each_free_region(&closure_ty, |fr| {
-            region_mapping.push(fr);
+            free_regions.push(fr);
         });
 
-        assert_eq!(
-            region_mapping.len(),
-            expected_num_vars,
-            "index vec had unexpected number of variables"
-        );
-
-        region_mapping
+        map_closure_regions(infcx.tcx.types.re_static, free_regions, expected_num_vars)
     }
 
-    /// True if `r` is a member of this set of universal regions.
+    /// True if `r` is a member of this set of universal regions,
+    /// including the placeholders.
     pub fn is_universal_region(&self, r: RegionVid) -> bool {
         (FIRST_GLOBAL_INDEX..self.num_universals).contains(r.index())
+            || self.is_placeholder_region(r)
     }
 
     /// Classifies `r` as a universal region, returning `None` if this
@@ -231,6 +479,8 @@ impl<'tcx> UniversalRegions<'tcx> {
             Some(RegionClassification::External)
         } else if (self.first_local_index..self.num_universals).contains(index) {
             Some(RegionClassification::Local)
+        } else if self.is_placeholder_region(r) {
+            Some(RegionClassification::Placeholder)
         } else {
             None
         }
@@ -257,6 +507,47 @@ impl<'tcx> UniversalRegions<'tcx> {
         self.region_classification(r) == Some(RegionClassification::Local)
     }
 
+    /// True if the creator of a closure can name `r`, that is, if `r`
+    /// is global or external. Only such regions may appear in the
+    /// `ClosureRegionRequirements` that we propagate to the creator;
+    /// local regions, placeholders and existential regions may not.
+    pub fn is_nameable_by_caller(&self, r: RegionVid) -> bool {
+        r.index() < self.first_local_index
+    }
+
+    /// Returns the regions for which `is_nameable_by_caller` holds, in
+    /// index order.
+    pub fn nameable_regions(&self) -> impl Iterator<Item = RegionVid> {
+        (FIRST_GLOBAL_INDEX..self.first_local_index).map(RegionVid::new)
+    }
+
+    /// True if `r` is the placeholder for a skolemized region.
+    pub fn is_placeholder_region(&self, r: RegionVid) -> bool {
+        self.placeholder_range().contains(r.index())
+    }
+
+    /// Returns the placeholders created so far (see
+    /// `create_placeholder_region`), in index order.
+    pub fn placeholder_regions(&self) -> impl Iterator<Item = RegionVid> {
+        self.placeholder_range().map(RegionVid::new)
+    }
+
+    /// If `r` is a placeholder, returns the bound region of the
+    /// higher-ranked obligation that it stands for.
+    pub fn placeholder_bound_region(&self, r: RegionVid) -> Option<ty::BoundRegion> {
+        if self.is_placeholder_region(r) {
+            let index = r.index() - self.indices.first_placeholder_index;
+            Some(self.indices.placeholder_bound_regions[index])
+        } else {
+            None
+        }
+    }
+
+    fn placeholder_range(&self) -> Range<usize> {
+        let first = self.indices.first_placeholder_index;
+        first..first + self.indices.placeholder_bound_regions.len()
+    }
+
     /// Returns the number of universal regions created in any category.
     pub fn len(&self) -> usize {
         self.num_universals
@@ -265,16 +556,46 @@ impl<'tcx> UniversalRegions<'tcx> {
     /// Finds an "upper bound" for `fr` that is not local. In other
     /// words, returns the smallest (*) known region `fr1` that (a)
     /// outlives `fr` and (b) is not local. This cannot fail, because
-    /// we will always find `'static` at worst.
+    /// we will always find `'static` at worst. (The relations do not
+    /// store that `'static` outlives `fr`, so that is the fallback
+    /// when no other candidate turns up.)
     ///
     /// (*) If there are multiple competing choices, we pick the "postdominating"
-    /// one. See `TransitiveRelation::postdom_upper_bound` for details.
+    /// one. See `TransitiveRelation::postdom_upper_bound` for details, and
+    /// `non_local_upper_bounds` for the competing choices themselves.
     pub fn non_local_upper_bound(&self, fr: RegionVid) -> RegionVid {
         debug!("non_local_upper_bound(fr={:?})", fr);
-        self.non_local_bound(&self.relations.inverse_outlives, fr)
+        self.cached_non_local_bound(BoundDirection::Upper, fr)
             .unwrap_or(self.fr_static)
     }
 
+    /// Returns all the smallest known non-local regions that outlive
+    /// `fr`; no region in the result outlives another. When there is
+    /// more than one, they are incomparable candidates for
+    /// `non_local_upper_bound`, which then has to settle for a common
+    /// upper bound of all of them -- often `'static`.
+    pub fn non_local_upper_bounds(&self, fr: RegionVid) -> Vec<RegionVid> {
+        debug!("non_local_upper_bounds(fr={:?})", fr);
+        let relation = self.relations.inverse_outlives_relation();
+        let mut candidates = self.external_parents(relation, fr);
+        candidates.sort();
+        candidates.dedup();
+
+        // Drop any candidate that outlives another one: the smaller
+        // one is the tighter bound.
+        let minimal: Vec<RegionVid> = candidates
+            .iter()
+            .cloned()
+            .filter(|&c| {
+                !candidates
+                    .iter()
+                    .any(|&d| d != c && self.outlives(c, d) && !self.outlives(d, c))
+            })
+            .collect();
+        debug!("non_local_upper_bounds: minimal={:?}", minimal);
+        minimal
+    }
+
     /// Finds a "lower bound" for `fr` that is not local. In other
     /// words, returns the largest (*) known region `fr1` that (a) is
     /// outlived by `fr` and (b) is not local. This cannot fail,
@@ -284,7 +605,15 @@ impl<'tcx> UniversalRegions<'tcx> {
     /// one. See `TransitiveRelation::postdom_upper_bound` for details.
     pub fn non_local_lower_bound(&self, fr: RegionVid) -> Option<RegionVid> {
         debug!("non_local_lower_bound(fr={:?})", fr);
-        self.non_local_bound(&self.relations.outlives, fr)
+
+        // A region known to outlive `'static` outlives every region,
+        // through the edges `'static: x` that the relations leave
+        // implicit, so `'static` is the largest region it outlives.
+        if self.outlives(fr, self.fr_static) {
+            return Some(self.fr_static);
+        }
+
+        self.cached_non_local_bound(BoundDirection::Lower, fr)
     }
 
     /// Returns the number of global plus external universal regions.
@@ -296,6 +625,21 @@ impl<'tcx> UniversalRegions<'tcx> {
         self.first_local_index
     }
 
+    /// Looks up the answer for `fr` in the `non_local_bounds` cache,
+    /// computing it with `non_local_bound` on a miss.
+    fn cached_non_local_bound(
+        &self,
+        direction: BoundDirection,
+        fr: RegionVid,
+    ) -> Option<RegionVid> {
+        let relation = match direction {
+            BoundDirection::Upper => self.relations.inverse_outlives_relation(),
+            BoundDirection::Lower => self.relations.outlives_relation(),
+        };
+
+        self.non_local_bounds.get_or_compute(direction, fr, || self.non_local_bound(relation, fr))
+    }
+
     /// Helper for `non_local_upper_bound` and
     /// `non_local_lower_bound`.  Repeatedly invokes `postdom_parent`
     /// until we find something that is not local. Returns None if we
@@ -305,35 +649,22 @@ impl<'tcx> UniversalRegions<'tcx> {
         relation: &TransitiveRelation<RegionVid>,
         fr0: RegionVid,
     ) -> Option<RegionVid> {
-        let mut external_parents = vec![];
-        let mut queue = vec![&fr0];
-
-        // Keep expanding `fr` into its parents until we reach
-        // non-local regions.
-        while let Some(fr) = queue.pop() {
-            if !self.is_local_free_region(*fr) {
-                external_parents.push(fr);
-                continue;
-            }
-
-            queue.extend(relation.parents(fr));
-        }
-
+        let external_parents = self.external_parents(relation, fr0);
         debug!("non_local_bound: external_parents={:?}", external_parents);
 
         // In case we find more than one, reduce to one for
         // convenience.  This is to prevent us from generating more
         // complex constraints, but it will cause spurious errors.
         let post_dom = relation
-            .mutual_immediate_postdominator(external_parents)
+            .mutual_immediate_postdominator(external_parents.iter().collect())
             .cloned();
 
         debug!("non_local_bound: post_dom={:?}", post_dom);
 
         post_dom.and_then(|post_dom| {
-            // If the mutual immediate postdom is not local, then
-            // there is no non-local result we can return.
-            if !self.is_local_free_region(post_dom) {
+            // If the mutual immediate postdom is local, then there is
+            // no non-local result we can return.
+            if self.is_nameable_by_caller(post_dom) {
                 Some(post_dom)
             } else {
                 None
@@ -341,45 +672,632 @@ impl<'tcx> UniversalRegions<'tcx> {
         })
     }
 
-    /// True if fr1 is known to outlive fr2.
-    ///
-    /// This will only ever be true for universally quantified regions.
+    /// Expands `fr0` into its parents in `relation`, and those into
+    /// theirs, until reaching regions that our caller can name;
+    /// returns the regions so reached. Regions that are neither local
+    /// nor nameable (placeholders) have no parents, and contribute
+    /// nothing.
+    fn external_parents(
+        &self,
+        relation: &TransitiveRelation<RegionVid>,
+        fr0: RegionVid,
+    ) -> Vec<RegionVid> {
+        let mut external_parents = vec![];
+        let mut queue = vec![fr0];
+
+        while let Some(fr) = queue.pop() {
+            if self.is_nameable_by_caller(fr) {
+                external_parents.push(fr);
+            } else if self.is_local_free_region(fr) {
+                queue.extend(relation.parents(&fr).into_iter().cloned());
+            }
+        }
+
+        external_parents
+    }
+
+    /// The outlives relations known to hold among the universal
+    /// regions.
+    pub fn relations(&self) -> &UniversalRegionRelations {
+        &self.relations
+    }
+
+    /// True if fr1 is known to outlive fr2. See
+    /// `UniversalRegionRelations::outlives`.
     pub fn outlives(&self, fr1: RegionVid, fr2: RegionVid) -> bool {
-        self.relations.outlives.contains(&fr1, &fr2)
+        self.relations.outlives(fr1, fr2)
+    }
+
+    /// True if `fr1` and `fr2` are known to outlive each other, e.g.
+    /// because of where-clauses `'a: 'b, 'b: 'a`, and hence are
+    /// semantically the same region.
+    pub fn equal(&self, fr1: RegionVid, fr2: RegionVid) -> bool {
+        self.outlives(fr1, fr2) && self.outlives(fr2, fr1)
+    }
+
+    /// Picks a canonical member of the set of universal regions that
+    /// are `equal` to `fr`: `'static` if it is in the set, and
+    /// otherwise its lowest-indexed region with a name (or its
+    /// lowest-indexed region, if none has a name). Non-universal
+    /// regions and placeholders are their own representative.
+    pub fn representative(&self, fr: RegionVid) -> RegionVid {
+        if !self.is_universal_region(fr) || self.is_placeholder_region(fr) {
+            return fr;
+        }
+
+        if self.equal(fr, self.fr_static) {
+            return self.fr_static;
+        }
+
+        // `universal_regions()` yields the regions in index order, and
+        // `fr` itself is always in its class.
+        let named: FxHashSet<RegionVid> = self.indices.indices.values().cloned().collect();
+        let class: Vec<RegionVid> = self.universal_regions()
+            .filter(|&r| r == fr || self.equal(fr, r))
+            .collect();
+        class.iter().cloned().find(|r| named.contains(r)).unwrap_or(class[0])
+    }
+
+    /// Returns where the region-bound pair `kind: r` came from, or
+    /// `None` if it is not in `region_bound_pairs`. A pair is only
+    /// listed once, with the first origin it was added with.
+    pub fn region_bound_pair_origin(
+        &self,
+        r: ty::Region<'tcx>,
+        kind: &GenericKind<'tcx>,
+    ) -> Option<RegionBoundPairOrigin> {
+        self.region_bound_pairs
+            .iter()
+            .position(|&(pair_r, ref pair_kind)| pair_r == r && pair_kind == kind)
+            .map(|index| self.region_bound_pair_origins[index])
+    }
+
+    /// Returns where each implied or declared relation `'static: 'a`
+    /// came from. For an argument of type `&'a Box<dyn Trait>`, say,
+    /// the implicit `'static` bound of the trait object gives one.
+    pub fn static_bound_origins<'a>(&'a self) -> impl Iterator<Item = RegionBoundPairOrigin> + 'a {
+        let fr_static = self.fr_static;
+        self.outlives_facts
+            .iter()
+            .filter(move |fact| fact.sup == fr_static)
+            .map(|fact| fact.origin)
+    }
+
+    /// If `fr` is an external region, returns the region that typeck
+    /// recorded in its place in the closure type; this is a region
+    /// of the closure's creator.
+    pub fn external_region_origin(&self, fr: RegionVid) -> Option<ty::Region<'tcx>> {
+        if !self.is_extern_free_region(fr) {
+            return None;
+        }
+
+        self.external_origins.get(&fr).cloned()
+    }
+
+    /// Returns the universal regions appearing in the type of the
+    /// input `idx`, an index into `input_tys` (for a closure or
+    /// generator, input 0 is the closure or generator itself).
+    pub fn regions_in_argument(&self, idx: usize) -> &[RegionVid] {
+        &self.argument_regions[idx]
+    }
+
+    /// Returns the indices into `input_tys` of the inputs whose types
+    /// mention the universal region `r`, in increasing order.
+    pub fn arguments_containing(&self, r: RegionVid) -> &[usize] {
+        self.argument_positions.get(&r).map_or(&[], |positions| &positions[..])
+    }
+
+    /// True if the universal region `r` appears in `output_ty`.
+    pub fn appears_in_output(&self, r: RegionVid) -> bool {
+        self.output_regions.contains(&r)
+    }
+
+    /// The declared return type, if it mentions `impl Trait` types.
+    /// The MIR of such a fn computes a value of the hidden types
+    /// instead, so the return place keeps the type it was built with,
+    /// which the MIR type check then relates to this one (see
+    /// `renumber_mir` and `type_check`).
+    pub fn anon_output_ty(&self) -> Option<Ty<'tcx>> {
+        let has_anon_types = self.output_ty.walk().any(|ty| match ty.sty {
+            ty::TyAnon(..) => true,
+            _ => false,
+        });
+        if has_anon_types {
+            Some(self.output_ty)
+        } else {
+            None
+        }
     }
 
-    /// Returns a vector of free regions `x` such that `fr1: x` is
-    /// known to hold.
-    pub fn regions_outlived_by(&self, fr1: RegionVid) -> Vec<&RegionVid> {
-        self.relations.outlives.reachable_from(&fr1)
+    /// Returns the free regions `x` such that `fr1: x` is known to
+    /// hold.
+    pub fn regions_outlived_by<'a>(
+        &'a self,
+        fr1: RegionVid,
+    ) -> impl Iterator<Item = RegionVid> + 'a {
+        self.relations.regions_outlived_by(fr1)
     }
 
-    /// Get an iterator over all the early-bound regions that have names.
+    /// Returns the free regions `x` such that both `fr1: x` and
+    /// `fr2: x` are known to hold.
+    pub fn universal_regions_outlived_by_both<'a>(
+        &'a self,
+        fr1: RegionVid,
+        fr2: RegionVid,
+    ) -> impl Iterator<Item = RegionVid> + 'a {
+        self.regions_outlived_by(fr1).filter(move |&r| self.outlives(fr2, r))
+    }
+
+    /// Summarizes these universal regions in a form that does not
+    /// depend on the inference context; see the
+    /// `universal_regions_summary` query.
+    pub fn summary<'gcx>(&self, tcx: TyCtxt<'_, 'gcx, 'tcx>) -> UniversalRegionsSummary<'gcx> {
+        let mut outlives = vec![];
+        for fr in self.universal_regions() {
+            outlives.extend(
+                self.regions_outlived_by(fr)
+                    .filter(|&r| r != fr)
+                    .map(|r| (fr, r)),
+            );
+        }
+        outlives.sort();
+
+        let named_regions = self.named_regions_with_static()
+            .filter_map(|(r, vid, _)| match *r {
+                ty::ReEarlyBound(data) => Some((vid, data.name)),
+                ty::ReFree(ty::FreeRegion { bound_region: ty::BrNamed(_, name), .. }) => {
+                    Some((vid, name))
+                }
+                ty::ReStatic => Some((vid, keywords::StaticLifetime.name())),
+                _ => None,
+            })
+            .collect();
+
+        // Map each universal region back to the region it was created
+        // from (for an external region, the region of the creator it
+        // stands for), so that the summary no longer mentions any
+        // region variables and can be lifted into the global arena.
+        let mut indices: Vec<(RegionVid, ty::Region<'tcx>)> = self.named_regions_with_static()
+            .map(|(r, vid, _)| (vid, r))
+            .collect();
+        indices.sort_by_key(|&(vid, _)| vid);
+        let origins: FxHashMap<RegionVid, ty::Region<'tcx>> = indices.iter().cloned().collect();
+        let original_region = |r: ty::Region<'tcx>| match *r {
+            ty::ReVar(vid) => origins
+                .get(&vid)
+                .cloned()
+                .or_else(|| self.external_origins.get(&vid).cloned())
+                .unwrap_or(tcx.types.re_erased),
+            _ => r,
+        };
+        let globalize = |ty: Ty<'tcx>| {
+            lift_to_global(tcx, &tcx.fold_regions(&ty, &mut false, |r, _| original_region(r)))
+        };
+
+        UniversalRegionsSummary {
+            num_universals: self.num_universals,
+            first_extern_index: self.first_extern_index,
+            first_local_index: self.first_local_index,
+            outlives,
+            named_regions,
+            indices: indices.iter().map(|&(vid, r)| (vid, lift_to_global(tcx, &r))).collect(),
+            defining_ty: globalize(self.defining_ty),
+            input_tys: self.input_tys.iter().map(|&ty| globalize(ty)).collect(),
+            output_ty: globalize(self.output_ty),
+            region_bound_pairs: self.region_bound_pairs
+                .iter()
+                .map(|&(r, ref kind)| {
+                    (lift_to_global(tcx, &original_region(r)), globalize(kind.to_ty(tcx)))
+                })
+                .collect(),
+        }
+    }
+
+    /// Writes a human-readable table of the universal regions into
+    /// `out`, for use in MIR dumps (see `-Z nll-dump-mir-regions`).
+    /// There is one line per region, giving its classification and
+    /// the `ty::Region` it was created from (if known), followed by
+    /// the index ranges of each classification, the known outlives
+    /// relations and the region-bound pairs.
+    pub fn annotate(&self, out: &mut Write) -> io::Result<()> {
+        let origins: FxHashMap<RegionVid, ty::Region<'tcx>> = self.named_regions_with_static()
+            .map(|(r, vid, _)| (vid, r))
+            .collect();
+
+        writeln!(out, "| Universal Regions")?;
+        for fr in self.universal_regions() {
+            let origin = match origins.get(&fr) {
+                Some(r) => format!("{:?}", r),
+                None => String::from("-"),
+            };
+            writeln!(
+                out,
+                "| {r:8} | {c:8} | {o}",
+                r = format!("{:?}", fr),
+                c = format!("{:?}", self.region_classification(fr).unwrap()),
+                o = origin
+            )?;
+        }
+        writeln!(
+            out,
+            "| first_extern_index={} first_local_index={} num_universals={}",
+            self.first_extern_index,
+            self.first_local_index,
+            self.num_universals
+        )?;
+
+        writeln!(out, "|")?;
+        writeln!(out, "| Known Outlives Relations")?;
+        for fr in self.universal_regions() {
+            let mut outlived_by_fr: Vec<RegionVid> = self.regions_outlived_by(fr)
+                .filter(|&r| r != fr)
+                .collect();
+            outlived_by_fr.sort();
+            for r in outlived_by_fr {
+                writeln!(out, "| {:?}: {:?}", fr, r)?;
+            }
+        }
+
+        writeln!(out, "|")?;
+        writeln!(out, "| Region Bound Pairs")?;
+        let mut region_bound_pairs: Vec<String> = self.region_bound_pairs
+            .iter()
+            .map(|&(r, ref generic_kind)| {
+                let origin = self.region_bound_pair_origin(r, generic_kind).unwrap();
+                format!("{:?}: {:?} ({:?})", generic_kind, r, origin)
+            })
+            .collect();
+        region_bound_pairs.sort();
+        for region_bound_pair in region_bound_pairs {
+            writeln!(out, "| {}", region_bound_pair)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the outlives relation among the universal regions as a
+    /// graphviz graph (see `-Z nll-dump-region-graph`). There is one
+    /// node per universal region, colored by its classification, and
+    /// an edge `'a -> 'b` for each `'a: 'b` that is not implied
+    /// transitively by the other edges.
+    pub fn dump_graphviz(&self, w: &mut Write) -> io::Result<()> {
+        let names: FxHashMap<RegionVid, ty::Region<'tcx>> = self.named_regions_with_static()
+            .map(|(r, vid, _)| (vid, r))
+            .collect();
+
+        writeln!(w, "digraph UniversalRegions {{")?;
+
+        for fr in self.universal_regions() {
+            let label = match names.get(&fr) {
+                Some(r) => format!("{}", r),
+                None => format!("{:?}", fr),
+            };
+            let color = match self.region_classification(fr).unwrap() {
+                RegionClassification::Global => "gray",
+                RegionClassification::External => "blue",
+                RegionClassification::Local => "green",
+                RegionClassification::Placeholder => "red",
+            };
+            writeln!(
+                w,
+                "    r{} [label=\"{}\", color=\"{}\"];",
+                fr.index(),
+                label,
+                color
+            )?;
+        }
+
+        for fr in self.universal_regions() {
+            let mut outlived_by_fr: Vec<RegionVid> = self.regions_outlived_by(fr)
+                .filter(|&r| r != fr)
+                .collect();
+            outlived_by_fr.sort();
+
+            // Skip `fr: r` if there is some `fr: mid` with `mid: r`.
+            for &r in &outlived_by_fr {
+                let implied = outlived_by_fr
+                    .iter()
+                    .any(|&mid| mid != r && self.outlives(mid, r));
+                if !implied {
+                    writeln!(w, "    r{} -> r{};", fr.index(), r.index())?;
+                }
+            }
+        }
+
+        writeln!(w, "}}")
+    }
+
+    /// If `r` is one of the late-bound regions of the signature,
+    /// returns how to refer to it: its declared name (e.g. `'a`), or
+    /// for an anonymous region, a number (e.g. `'1`).
+    pub fn late_bound_region_name(&self, r: RegionVid) -> Option<String> {
+        self.late_bound_names.get(&r).map(|name| match *name {
+            LateBoundName::Named(_, name) => format!("{}", name),
+            LateBoundName::Anonymous(number) => format!("'{}", number),
+        })
+    }
+
+    /// Returns how to refer to the universal region `r` in
+    /// diagnostics. Besides the name, this finds, for an anonymous
+    /// region that is the lifetime of a reference in the type of a
+    /// parameter, the span of that reference type, so that the
+    /// diagnostic can point out which lifetime `'1` (say) is.
+    pub fn name_region(&self, tcx: TyCtxt<'_, '_, 'tcx>, r: RegionVid) -> RegionName {
+        let r = self.representative(r);
+        match self.late_bound_names.get(&r) {
+            Some(&LateBoundName::Named(_, name)) => RegionName {
+                name: format!("{}", name),
+                highlight: None,
+            },
+            Some(&LateBoundName::Anonymous(number)) => RegionName {
+                name: format!("'{}", number),
+                highlight: self.anonymous_reference_in_argument(tcx, r),
+            },
+            None => {
+                let name = match self.named_regions_with_static().find(|&(_, v, _)| v == r) {
+                    Some((region, _, _)) => format!("{}", region),
+                    None => format!("{:?}", r),
+                };
+                RegionName {
+                    name,
+                    highlight: None,
+                }
+            }
+        }
+    }
+
+    /// If the anonymous region `r` is the lifetime of a reference
+    /// that is the type of one of the parameters, with the lifetime
+    /// elided (e.g., `x: &u32`), returns the span of that reference
+    /// type, and a description of `r`.
+    fn anonymous_reference_in_argument(
+        &self,
+        tcx: TyCtxt<'_, '_, 'tcx>,
+        r: RegionVid,
+    ) -> Option<(Span, String)> {
+        let first_argument = if tcx.is_closure(self.mir_def_id) { 1 } else { 0 };
+        let argument_index = *self.arguments_containing(r)
+            .iter()
+            .find(|&&idx| idx >= first_argument)?;
+        match self.input_tys[argument_index].sty {
+            ty::TyRef(region, _) if region.to_region_vid() == r => {}
+            _ => return None,
+        }
+
+        let node_id = tcx.hir.as_local_node_id(self.mir_def_id)?;
+        let (decl, body_id) = match tcx.hir.get(node_id) {
+            hir_map::NodeItem(&hir::Item { node: hir::ItemFn(ref decl, .., body_id), .. }) => {
+                (decl, body_id)
+            }
+            hir_map::NodeTraitItem(&hir::TraitItem {
+                node: hir::TraitItemKind::Method(ref sig, hir::TraitMethod::Provided(body_id)),
+                ..
+            }) |
+            hir_map::NodeImplItem(&hir::ImplItem {
+                node: hir::ImplItemKind::Method(ref sig, body_id),
+                ..
+            }) => (&sig.decl, body_id),
+            hir_map::NodeExpr(&hir::Expr {
+                node: hir::ExprClosure(_, ref decl, body_id, _, _),
+                ..
+            }) => (decl, body_id),
+            _ => return None,
+        };
+
+        let hir_index = argument_index - first_argument;
+        let hir_ty = decl.inputs.get(hir_index)?;
+        match hir_ty.node {
+            hir::TyRptr(ref lifetime, _) if lifetime.is_elided() => {}
+            _ => return None,
+        }
+
+        let body = tcx.hir.body(body_id);
+        let description = match body.arguments.get(hir_index)?.pat.node {
+            hir::PatKind::Binding(_, _, ref name, None) => format!(
+                "the anonymous lifetime of the reference in parameter `{}`",
+                name.node
+            ),
+            _ => format!(
+                "the anonymous lifetime of the reference in parameter {}",
+                hir_index + 1
+            ),
+        };
+        Some((hir_ty.span, description))
+    }
+
+    /// Get an iterator over the regions that the user declared with a
+    /// name: the early-bound regions, and the late-bound regions
+    /// declared with a name, which are given as the free regions that
+    /// stand for them (e.g., `ReFree(foo, 'a)`), together with where
+    /// each was declared. They come in the order of their
+    /// `RegionVid`s (rather than the hash order of the maps), so that
+    /// dumps and diagnostics built from it are deterministic.
+    ///
+    /// `'static` is not among them; see `static_region`.
     pub fn named_universal_regions<'s>(
         &'s self,
-    ) -> impl Iterator<Item = (ty::Region<'tcx>, ty::RegionVid)> + 's {
-        self.indices.indices.iter().map(|(&r, &v)| (r, v))
+    ) -> impl Iterator<Item = (ty::Region<'tcx>, ty::RegionVid, NameOrigin)> + 's {
+        let late_bound = self.late_bound_names.iter().filter_map(|(&v, name)| match *name {
+            LateBoundName::Named(r, _) => Some((r, v, NameOrigin::Item)),
+            LateBoundName::Anonymous(_) => None,
+        });
+        let mut named: Vec<_> = self.indices
+            .indices
+            .iter()
+            .filter(|&(_, &v)| v != self.fr_static)
+            .map(|(&r, &v)| {
+                let origin = if self.indices.parent_regions.contains(&r) {
+                    NameOrigin::Parent
+                } else {
+                    NameOrigin::Item
+                };
+                (r, v, origin)
+            })
+            .chain(late_bound)
+            .collect();
+        named.sort_by_key(|&(_, v, _)| v);
+        named.into_iter()
+    }
+
+    /// The `'static` region, together with the universal region that
+    /// stands for it (that is, `fr_static`), in the same form as the
+    /// items of `named_universal_regions`.
+    pub fn static_region(&self) -> (ty::Region<'tcx>, ty::RegionVid, NameOrigin) {
+        let r = self.indices
+            .indices
+            .iter()
+            .find(|&(_, &v)| v == self.fr_static)
+            .map(|(&r, _)| r)
+            .unwrap_or_else(|| bug!("no region maps to fr_static {:?}", self.fr_static));
+        (r, self.fr_static, NameOrigin::Static)
+    }
+
+    /// `static_region` followed by `named_universal_regions`, for the
+    /// dumps and names that cover every region with a name. As
+    /// `fr_static` is the first universal region, this keeps them
+    /// in the order of their `RegionVid`s.
+    fn named_regions_with_static<'s>(
+        &'s self,
+    ) -> impl Iterator<Item = (ty::Region<'tcx>, ty::RegionVid, NameOrigin)> + 's {
+        iter::once(self.static_region()).chain(self.named_universal_regions())
+    }
+
+    /// Where the named universal region `r` was declared, or `None`
+    /// if it has no name. See `named_universal_regions`.
+    pub fn name_origin(&self, r: RegionVid) -> Option<NameOrigin> {
+        let r = self.representative(r);
+        self.named_regions_with_static()
+            .find(|&(_, v, _)| v == r)
+            .map(|(_, _, origin)| origin)
     }
 
     /// See `UniversalRegionIndices::to_region_vid`.
     pub fn to_region_vid(&self, r: ty::Region<'tcx>) -> RegionVid {
         self.indices.to_region_vid(r)
     }
+
+    /// If `r` is a skolemized region, creates the placeholder that
+    /// stands in for it in the constraints of this body, unless that
+    /// was done already. Skolemized regions are only found in the
+    /// constraints that type-checking the body produces for
+    /// higher-ranked obligations, so this is called on demand once the
+    /// MIR has been type-checked (and before the region inference
+    /// context takes over the region variables of `infcx`).
+    pub fn create_placeholder_region(
+        &mut self,
+        infcx: &InferCtxt<'_, '_, 'tcx>,
+        r: ty::Region<'tcx>,
+    ) {
+        if let ty::ReSkolemized(_, br) = *r {
+            let indices = &mut self.indices;
+            if indices.placeholders.contains_key(&r) {
+                return;
+            }
+
+            let origin = NLLRegionVariableOrigin::Placeholder(br);
+            let placeholder = infcx.next_nll_region_var(origin).to_region_vid();
+            if indices.placeholder_bound_regions.is_empty() {
+                indices.first_placeholder_index = placeholder.index();
+            }
+            debug_assert_eq!(
+                placeholder.index(),
+                indices.first_placeholder_index + indices.placeholder_bound_regions.len(),
+                "create_placeholder_region: placeholders must be created back to back"
+            );
+            indices.placeholder_bound_regions.push(br);
+            indices.placeholders.insert(r, placeholder);
+        }
+    }
+}
+
+/// Lifts a part of the universal regions summary into the global
+/// arena; it must not mention any region or type variables.
+fn lift_to_global<'gcx, 'tcx, T>(tcx: TyCtxt<'_, 'gcx, 'tcx>, value: &T) -> T::Lifted
+where
+    T: ty::Lift<'gcx> + fmt::Debug,
+{
+    tcx.lift_to_global(value)
+        .unwrap_or_else(|| bug!("universal regions summary: cannot lift `{:?}`", value))
 }
 
 struct UniversalRegionsBuilder<'cx, 'gcx: 'tcx, 'tcx: 'cx> {
     infcx: &'cx InferCtxt<'cx, 'gcx, 'tcx>,
     mir_def_id: DefId,
+
+    /// The HIR of `mir_def_id`, or dummy ids for a shim, which has
+    /// none (see `shim_sig`).
     mir_hir_id: HirId,
     mir_node_id: ast::NodeId,
     param_env: ty::ParamEnv<'tcx>,
+
+    /// For the MIR of a shim, its signature; see `new_for_shim`.
+    shim_sig: Option<ty::FnSig<'tcx>>,
+
+    /// The typeck tables of `mir_def_id`, if the caller had them at
+    /// hand; see `unrenumbered_defining_ty`.
+    tables: Option<&'gcx ty::TypeckTables<'gcx>>,
+
     region_bound_pairs: Vec<(ty::Region<'tcx>, GenericKind<'tcx>)>,
+    region_bound_pair_origins: Vec<RegionBoundPairOrigin>,
+    outlives_facts: Vec<OutlivesFact>,
     relations: UniversalRegionRelations,
+
+    /// The relations and region-bound pairs added so far. A bound is
+    /// often both declared and implied (`'b: 'a` for an argument of
+    /// type `&'a &'b u32` with `'b: 'a` in the where-clauses), and we
+    /// only want to record it once.
+    related_regions: FxHashSet<(RegionVid, RegionVid)>,
+    known_region_bound_pairs: FxHashSet<(ty::Region<'tcx>, GenericKind<'tcx>)>,
+
+    /// The implied bounds computed so far, by input/output type;
+    /// bodies often have many arguments of the same type.
+    implied_bounds_cache: FxHashMap<Ty<'tcx>, Vec<OutlivesBound<'tcx>>>,
+
+    /// The number of region variables that computing the implied
+    /// bounds created along the way; see `compute_implied_bounds`.
+    num_stray_region_vars: usize,
 }
 
 const FR: NLLRegionVariableOrigin = NLLRegionVariableOrigin::FreeRegion;
 
 impl<'cx, 'gcx, 'tcx> UniversalRegionsBuilder<'cx, 'gcx, 'tcx> {
+    fn new(
+        infcx: &'cx InferCtxt<'cx, 'gcx, 'tcx>,
+        mir_def_id: DefId,
+        param_env: ty::ParamEnv<'tcx>,
+        tables: Option<&'gcx ty::TypeckTables<'gcx>>,
+        shim_sig: Option<ty::FnSig<'tcx>>,
+    ) -> Self {
+        let tcx = infcx.tcx;
+        // A shim has no HIR of its own, and its `mir_def_id` is the
+        // shimmed item, which may not be local either. The ids are
+        // only needed for the implied bounds, which are happy with a
+        // synthetic node.
+        let (mir_node_id, mir_hir_id) = if shim_sig.is_some() {
+            (ast::DUMMY_NODE_ID, hir::DUMMY_HIR_ID)
+        } else {
+            let mir_node_id = tcx.hir.as_local_node_id(mir_def_id).unwrap();
+            (mir_node_id, tcx.hir.node_to_hir_id(mir_node_id))
+        };
+        UniversalRegionsBuilder {
+            infcx,
+            mir_def_id,
+            mir_node_id,
+            mir_hir_id,
+            param_env,
+            shim_sig,
+            tables,
+            region_bound_pairs: vec![],
+            region_bound_pair_origins: vec![],
+            outlives_facts: vec![],
+            relations: UniversalRegionRelations::new(),
+            related_regions: FxHashSet(),
+            known_region_bound_pairs: FxHashSet(),
+            implied_bounds_cache: FxHashMap(),
+            num_stray_region_vars: 0,
+        }
+    }
+
     fn build(mut self) -> UniversalRegions<'tcx> {
         debug!("build(mir_def_id={:?})", self.mir_def_id);
 
@@ -395,42 +1313,131 @@ impl<'cx, 'gcx, 'tcx> UniversalRegionsBuilder<'cx, 'gcx, 'tcx> {
         // add will be external.
         let first_extern_index = self.infcx.num_region_vars();
 
-        let defining_ty = self.defining_ty();
+        let unrenumbered_defining_ty = self.unrenumbered_defining_ty();
+        let defining_ty = self.infcx
+            .replace_free_regions_with_nll_infer_vars(FR, &unrenumbered_defining_ty);
         debug!("build: defining_ty={:?}", defining_ty);
+        if let Some(num_free_regions) = self.num_free_regions_in_substs(unrenumbered_defining_ty) {
+            debug_assert_eq!(self.infcx.num_region_vars() - first_extern_index, num_free_regions,
+                             "defining type {:?} has free regions outside of its substs",
+                             unrenumbered_defining_ty);
+        }
+
+        // The free regions of the signature of a shim are not bound
+        // in any enclosing item, so they are local rather than external.
+        let external_origins = if self.shim_sig.is_some() {
+            FxHashMap()
+        } else {
+            self.compute_external_origins(unrenumbered_defining_ty, defining_ty)
+        };
 
-        let indices = self.compute_indices(fr_static, defining_ty);
+        let mut indices = self.compute_indices(fr_static, defining_ty);
         debug!("build: indices={:?}", indices);
 
         let bound_inputs_and_output = self.compute_inputs_and_output(&indices, defining_ty);
 
         // "Liberate" the late-bound regions. These correspond to
         // "local" free regions.
-        let first_local_index = self.infcx.num_region_vars();
-        let inputs_and_output = self.infcx
+        let first_local_index = if self.shim_sig.is_some() {
+            first_extern_index
+        } else {
+            self.infcx.num_region_vars()
+        };
+        let num_early_bound = self.infcx.num_region_vars();
+        let (inputs_and_output, late_bound_regions) = self.infcx
             .replace_bound_regions_with_nll_infer_vars(FR, &bound_inputs_and_output);
         let num_universals = self.infcx.num_region_vars();
+        debug_assert_eq!(num_universals - num_early_bound, late_bound_regions.len());
+
+        // Now that every region of the signature has a variable, map
+        // the regions of any `impl Trait` in the return type to them.
+        self.add_anon_type_regions(&mut indices, inputs_and_output);
+
+        // Most bodies never mention a region, not even `'static`, and
+        // then there are no relations or bound pairs to compute.
+        let region_free = self.is_region_free(defining_ty, &bound_inputs_and_output);
+        let verify_fast_path =
+            self.infcx.tcx.sess.opts.debugging_opts.nll_verify_universal_regions_fast_path;
+        if region_free && !verify_fast_path {
+            debug!("build: region-free, only relating 'static to itself");
+            self.relate_universal_regions(fr_static, fr_static);
+        } else {
+            // Insert the facts we know from the predicates. Why? Why not.
+            // (For a closure, `param_env` already holds the predicates
+            // of the enclosing item.)
+            for bound in bounds::explicit_outlives_bounds(param_env) {
+                let span = match bound {
+                    OutlivesBound::RegionSubRegion(r_b, r_a) => self.where_clause_span(r_a, r_b),
+                    OutlivesBound::RegionSubParam(..) |
+                    OutlivesBound::RegionSubProjection(..) => {
+                        self.infcx.tcx.def_span(self.mir_def_id)
+                    }
+                };
+                let origin = RegionBoundPairOrigin::FromWhereClause(span);
+                self.add_outlives_bounds(&indices, origin, Some(bound));
+            }
 
-        // Insert the facts we know from the predicates. Why? Why not.
-        self.add_outlives_bounds(&indices, bounds::explicit_outlives_bounds(param_env));
-
-        // Add the implied bounds from inputs and outputs.
-        for ty in inputs_and_output {
-            debug!("build: input_or_output={:?}", ty);
-            self.add_implied_bounds(&indices, ty);
-        }
+            // Add the implied bounds from inputs and outputs, and
+            // from the types of any captured upvars. Any region
+            // variables created from here on are stray ones of
+            // `compute_implied_bounds`.
+            debug_assert_eq!(self.infcx.num_region_vars(), num_universals);
+            self.add_implied_bounds(&indices, inputs_and_output);
+            self.add_upvar_implied_bounds(&indices, defining_ty);
+
+            // Finally, outlives is reflexive. (That static outlives
+            // every other free region goes without saying; see
+            // `UniversalRegionRelations`.)
+            for fr in (FIRST_GLOBAL_INDEX..num_universals).map(RegionVid::new) {
+                debug!("build: relating free region {:?} to itself", fr);
+                self.relate_universal_regions(fr, fr);
+            }
 
-        // Finally, outlives is reflexive, and static outlives every
-        // other free region.
-        for fr in (FIRST_GLOBAL_INDEX..num_universals).map(RegionVid::new) {
-            debug!("build: relating free region {:?} to itself and to 'static", fr);
-            self.relations.relate_universal_regions(fr, fr);
-            self.relations.relate_universal_regions(fr_static, fr);
+            if region_free {
+                // The fast path would have produced just `'static`
+                // and no bound pairs; check that this agrees.
+                assert_eq!(num_universals, first_extern_index);
+                assert!(self.region_bound_pairs.is_empty(),
+                        "region-free body has region-bound pairs: {:?}",
+                        self.region_bound_pairs);
+            }
         }
 
         let (output_ty, input_tys) = inputs_and_output.split_last().unwrap();
+        let (yield_ty, input_tys) = match defining_ty.sty {
+            ty::TyGenerator(..) => {
+                let (yield_ty, input_tys) = input_tys.split_last().unwrap();
+                (Some(*yield_ty), input_tys)
+            }
+            _ => (None, input_tys),
+        };
 
-        // we should not have created any more variables
-        assert_eq!(self.infcx.num_region_vars(), num_universals);
+        // we should not have created any more variables, except for
+        // the stray ones of the implied bounds, which come after all
+        // the universal regions
+        assert_eq!(self.infcx.num_region_vars(), num_universals + self.num_stray_region_vars);
+
+        let argument_regions: Vec<_> = input_tys
+            .iter()
+            .map(|&input_ty| self.regions_in_ty(input_ty))
+            .collect();
+        let mut argument_positions = FxHashMap();
+        for (idx, regions) in argument_regions.iter().enumerate() {
+            for &r in regions {
+                argument_positions.entry(r).or_insert(vec![]).push(idx);
+            }
+        }
+        let output_regions_in_order = self.regions_in_ty(output_ty);
+        let late_bound_names = self.name_late_bound_regions(
+            &late_bound_regions,
+            &argument_regions,
+            &output_regions_in_order,
+        );
+        let output_regions = output_regions_in_order.into_iter().collect();
+
+        // The relations are complete, so we can now answer `outlives`
+        // queries from a precomputed table.
+        self.relations.freeze(fr_static, num_universals);
 
         debug!("build: global regions = {}..{}",
                FIRST_GLOBAL_INDEX,
@@ -443,7 +1450,9 @@ impl<'cx, 'gcx, 'tcx> UniversalRegionsBuilder<'cx, 'gcx, 'tcx> {
                num_universals);
 
         UniversalRegions {
+            mir_def_id: self.mir_def_id,
             indices,
+            late_bound_names,
             fr_static,
             first_extern_index,
             first_local_index,
@@ -451,19 +1460,174 @@ impl<'cx, 'gcx, 'tcx> UniversalRegionsBuilder<'cx, 'gcx, 'tcx> {
             defining_ty,
             output_ty,
             input_tys,
+            yield_ty,
             region_bound_pairs: self.region_bound_pairs,
+            region_bound_pair_origins: self.region_bound_pair_origins,
+            outlives_facts: self.outlives_facts,
+            external_origins,
+            argument_regions,
+            argument_positions,
+            output_regions,
             relations: self.relations,
+            non_local_bounds: NonLocalBoundCache::default(),
         }
     }
 
-    /// Returns the "defining type" of the current MIR:
+    /// Names the late-bound regions liberated from the signature (see
+    /// `LateBoundName`). `late_bound_regions` maps each bound region
+    /// to the variable that replaced it; `argument_regions` and
+    /// `output_regions` are the regions of the inputs and of the
+    /// output, in order of appearance.
+    fn name_late_bound_regions(
+        &self,
+        late_bound_regions: &BTreeMap<ty::BoundRegion, ty::Region<'tcx>>,
+        argument_regions: &[Vec<RegionVid>],
+        output_regions: &[RegionVid],
+    ) -> FxHashMap<RegionVid, LateBoundName<'tcx>> {
+        // A closure cannot declare lifetimes; the names in its
+        // signature, if any, come from whatever bound the signature
+        // was deduced from (e.g. `F: for<'x> Fn(&'x u32)`), which
+        // would only confuse, so all of its regions are anonymous.
+        let tcx = self.infcx.tcx;
+        let is_closure = tcx.is_closure(self.mir_def_id);
+        let mut names = FxHashMap();
+        let mut anonymous = FxHashSet();
+        for (&br, &region) in late_bound_regions {
+            let vid = region.to_region_vid();
+            match br {
+                ty::BrNamed(_, name) if !is_closure => {
+                    let free_region = tcx.mk_region(ty::ReFree(ty::FreeRegion {
+                        scope: self.mir_def_id,
+                        bound_region: br,
+                    }));
+                    names.insert(vid, LateBoundName::Named(free_region, name));
+                }
+                _ => {
+                    anonymous.insert(vid);
+                }
+            }
+        }
+
+        // The first input of a closure or generator is the closure
+        // or generator itself, which the user did not write, so its
+        // regions only come after all the others.
+        let first_argument = if is_closure { 1 } else { 0 };
+        let mut remaining: Vec<RegionVid> = anonymous.iter().cloned().collect();
+        remaining.sort();
+        let in_order = argument_regions
+            .iter()
+            .skip(first_argument)
+            .flat_map(|regions| regions.iter())
+            .chain(output_regions)
+            .cloned()
+            .chain(remaining);
+        let mut next = 1;
+        for vid in in_order {
+            if anonymous.remove(&vid) {
+                names.insert(vid, LateBoundName::Anonymous(next));
+                next += 1;
+            }
+        }
+
+        names
+    }
+
+    /// Returns the distinct free regions of `ty`, in order of
+    /// appearance. Called once all of them have been replaced by
+    /// universal region variables.
+    fn regions_in_ty(&self, ty: Ty<'tcx>) -> Vec<RegionVid> {
+        let mut regions = vec![];
+        self.infcx.tcx.for_each_free_region(&ty, |r| {
+            let vid = r.to_region_vid();
+            if !regions.contains(&vid) {
+                regions.push(vid);
+            }
+        });
+        regions
+    }
+
+    /// True if neither the defining type nor the signature mention
+    /// any region, free or late-bound, and there are no explicit
+    /// outlives bounds in scope. In that case `'static` is the only
+    /// universal region and computing the implied bounds is wasted
+    /// work.
+    fn is_region_free(
+        &self,
+        defining_ty: Ty<'tcx>,
+        bound_inputs_and_output: &ty::Binder<&'tcx ty::Slice<Ty<'tcx>>>,
+    ) -> bool {
+        let tcx = self.infcx.tcx;
+
+        let mut mentions_regions = false;
+        tcx.for_each_free_region(&defining_ty, |_| mentions_regions = true);
+        tcx.for_each_free_region(bound_inputs_and_output, |_| mentions_regions = true);
+        if mentions_regions || bound_inputs_and_output.skip_binder().has_escaping_regions() {
+            return false;
+        }
+
+        bounds::explicit_outlives_bounds(self.param_env).next().is_none()
+    }
+
+    /// Returns the span of the bound `'longer: 'shorter` as written
+    /// on the item that owns this MIR or on one of its parents (like
+    /// the enclosing impl). Bounds that are not written anywhere,
+    /// like those elaborated from a supertrait, get the span of the
+    /// MIR's item instead.
+    fn where_clause_span(&self, longer: ty::Region<'tcx>, shorter: ty::Region<'tcx>) -> Span {
+        let tcx = self.infcx.tcx;
+        let names = |lifetime: &hir::Lifetime, r: ty::Region<'tcx>| match (lifetime.name, r) {
+            (hir::LifetimeName::Static, &ty::ReStatic) => true,
+            (hir::LifetimeName::Name(name), &ty::ReEarlyBound(data)) => data.name == name,
+            _ => false,
+        };
+
+        let mut item_def_id = Some(tcx.closure_base_def_id(self.mir_def_id));
+        while let Some(def_id) = item_def_id {
+            let generics = tcx.hir
+                .as_local_node_id(def_id)
+                .and_then(|node_id| item_generics(tcx, node_id));
+            if let Some(generics) = generics {
+                let inline_bounds = generics
+                    .lifetimes
+                    .iter()
+                    .map(|def| (&def.lifetime, &def.bounds));
+                let where_bounds = generics
+                    .where_clause
+                    .predicates
+                    .iter()
+                    .filter_map(|predicate| match *predicate {
+                        hir::WherePredicate::RegionPredicate(ref predicate) => {
+                            Some((&predicate.lifetime, &predicate.bounds))
+                        }
+                        _ => None,
+                    });
+                for (lifetime, bounds) in inline_bounds.chain(where_bounds) {
+                    if !names(lifetime, longer) {
+                        continue;
+                    }
+                    if let Some(bound) = bounds.iter().find(|bound| names(bound, shorter)) {
+                        return lifetime.span.to(bound.span);
+                    }
+                }
+            }
+            item_def_id = tcx.generics_of(def_id).parent;
+        }
+
+        tcx.def_span(self.mir_def_id)
+    }
+
+    /// Returns the "defining type" of the current MIR, before its
+    /// regions are replaced with universal region variables:
     ///
     /// - for functions, this is the `TyFnDef`;
     /// - for closures, this is the `TyClosure`;
     /// - for generators, this is the `TyGenerator`;
-    /// - for constants, this is the type of value that gets produced.
-    ///   - FIXME. Constants are handled somewhat inelegantly; this gets
-    ///     patched in a later PR that has already landed on nll-master.
+    /// - for constants and statics, this is the type of value that
+    ///   gets produced. Unlike the other cases, this does not
+    ///   determine the universal regions in scope, so
+    ///   `compute_indices` and `compute_inputs_and_output` look at
+    ///   the item itself instead (see `is_const_or_static`);
+    /// - for shims, this is the `TyFnPtr` of the shim's signature.
     ///
     /// The key feature of the "defining type" is that it contains the
     /// information needed to derive all the universal regions that
@@ -471,19 +1635,101 @@ impl<'cx, 'gcx, 'tcx> UniversalRegionsBuilder<'cx, 'gcx, 'tcx> {
     /// the MIR. In general, early-bound universal regions appear free
     /// in the defining type and late-bound regions appear bound in
     /// the signature.
-    fn defining_ty(&self) -> ty::Ty<'tcx> {
+    fn unrenumbered_defining_ty(&self) -> ty::Ty<'tcx> {
         let tcx = self.infcx.tcx;
-        let closure_base_def_id = tcx.closure_base_def_id(self.mir_def_id);
+        if let Some(sig) = self.shim_sig {
+            return tcx.mk_fn_ptr(ty::Binder(sig));
+        }
 
-        let defining_ty = if self.mir_def_id == closure_base_def_id {
+        let closure_base_def_id = tcx.closure_base_def_id(self.mir_def_id);
+        if self.mir_def_id == closure_base_def_id {
             tcx.type_of(closure_base_def_id)
         } else {
-            let tables = tcx.typeck_tables_of(self.mir_def_id);
+            let tables = self.tables.unwrap_or_else(|| tcx.typeck_tables_of(self.mir_def_id));
             tables.node_id_to_type(self.mir_hir_id)
-        };
+        }
+    }
+
+    /// The number of free regions that the substs of `defining_ty`
+    /// (and, for a generator, its interior) contain, or `None` for a
+    /// defining type that is not a fn, closure or generator.
+    ///
+    /// Those are the only places where such a defining type carries
+    /// free regions: `replace_free_regions_with_nll_infer_vars` folds
+    /// every region that is free anywhere in the type, but the type is
+    /// nothing more than a def-id and these substs. Nor is there any
+    /// region to skip inside them: type aliases are already expanded,
+    /// and an extern type, like `TyForeign`, is just a def-id.
+    fn num_free_regions_in_substs(&self, defining_ty: Ty<'tcx>) -> Option<usize> {
+        let tcx = self.infcx.tcx;
+        let mut num_free_regions = 0;
+        match defining_ty.sty {
+            ty::TyFnDef(_, substs) | ty::TyClosure(_, ty::ClosureSubsts { substs }) => {
+                tcx.for_each_free_region(&substs, |_| num_free_regions += 1);
+            }
+            ty::TyGenerator(_, ty::ClosureSubsts { substs }, interior) => {
+                tcx.for_each_free_region(&substs, |_| num_free_regions += 1);
+                tcx.for_each_free_region(&interior, |_| num_free_regions += 1);
+            }
+            _ => return None,
+        }
+        Some(num_free_regions)
+    }
+
+    /// True if the MIR being checked is the synthesized body of the
+    /// constructor of a tuple struct or tuple variant.
+    fn is_ctor(&self) -> bool {
+        if self.shim_sig.is_some() {
+            return false;
+        }
+
+        match self.infcx.tcx.def_key(self.mir_def_id).disambiguated_data.data {
+            DefPathData::StructCtor | DefPathData::EnumVariant(_) => true,
+            _ => false,
+        }
+    }
+
+    /// True if the MIR being checked is the initializer of a constant
+    /// or static (rather than a fn or closure body).
+    fn is_const_or_static(&self) -> bool {
+        let tcx = self.infcx.tcx;
+        if self.shim_sig.is_some() || tcx.closure_base_def_id(self.mir_def_id) != self.mir_def_id {
+            return false;
+        }
 
-        self.infcx
-            .replace_free_regions_with_nll_infer_vars(FR, &defining_ty)
+        match tcx.hir.body_owner_kind(self.mir_node_id) {
+            BodyOwnerKind::Fn => false,
+            BodyOwnerKind::Const | BodyOwnerKind::Static(_) => true,
+        }
+    }
+
+    /// Relates the free regions of `defining_ty` to the regions they
+    /// replaced in `unrenumbered_defining_ty`. Renumbering creates
+    /// one variable per free region, in the order in which
+    /// `for_each_free_region` visits them, so we can simply zip the
+    /// two walks together.
+    fn compute_external_origins(
+        &self,
+        unrenumbered_defining_ty: Ty<'tcx>,
+        defining_ty: Ty<'tcx>,
+    ) -> FxHashMap<RegionVid, ty::Region<'tcx>> {
+        let tcx = self.infcx.tcx;
+
+        let mut origins = vec![];
+        tcx.for_each_free_region(&unrenumbered_defining_ty, |r| origins.push(r));
+
+        let mut vids = vec![];
+        tcx.for_each_free_region(&defining_ty, |r| vids.push(r.to_region_vid()));
+
+        // `replace_free_regions_with_nll_infer_vars` creates exactly
+        // one variable per free region that `for_each_free_region`
+        // visits; both skip bound regions, and neither finds any
+        // region in types without substs, like extern types. So the
+        // two lists line up one-to-one.
+        assert_eq!(origins.len(), vids.len(),
+                   "free regions of {:?} do not line up with those of {:?}",
+                   unrenumbered_defining_ty, defining_ty);
+        vids.into_iter().zip(origins).collect()
     }
 
     /// Builds a hashmap that maps from the universal regions that are
@@ -498,7 +1744,21 @@ impl<'cx, 'gcx, 'tcx> UniversalRegionsBuilder<'cx, 'gcx, 'tcx> {
         let tcx = self.infcx.tcx;
         let gcx = tcx.global_tcx();
         let closure_base_def_id = tcx.closure_base_def_id(self.mir_def_id);
-        let identity_substs = Substs::identity_for_item(gcx, closure_base_def_id);
+        // A shim is not generic over any region, whatever the
+        // generics of the shimmed item: the regions of its signature
+        // are all local, and none is early-bound.
+        //
+        // The generics of a constructor are those of its ADT, which
+        // it takes as its parent; they are looked up by the
+        // constructor's own def-id all the same, as that is what its
+        // `TyFnDef` and signature are expressed in.
+        let identity_substs = if self.shim_sig.is_some() {
+            gcx.intern_substs(&[])
+        } else if self.is_ctor() {
+            Substs::identity_for_item(gcx, self.mir_def_id)
+        } else {
+            Substs::identity_for_item(gcx, closure_base_def_id)
+        };
         let fr_substs = match defining_ty.sty {
             ty::TyClosure(_, substs) | ty::TyGenerator(_, substs, ..) => {
                 // In the case of closures, we rely on the fact that
@@ -508,25 +1768,62 @@ impl<'cx, 'gcx, 'tcx> UniversalRegionsBuilder<'cx, 'gcx, 'tcx> {
                 // `identity_substs`, we will get only those regions
                 // that correspond to early-bound regions declared on
                 // the `closure_base_def_id`.
-                assert!(substs.substs.len() >= identity_substs.len());
-                assert_eq!(substs.substs.regions().count(), identity_substs.regions().count());
+                //
+                // This holds for closures inside a constant or
+                // static as well: the closure base is then the
+                // constant itself, whose generics are those of the
+                // impl or trait it belongs to (if any), and it is
+                // from those that typeck builds the closure substs.
+                assert!(
+                    substs.substs.len() >= identity_substs.len(),
+                    "substs {:?} of {:?} lack the prefix {:?} inherited from {:?}",
+                    substs.substs,
+                    self.mir_def_id,
+                    identity_substs,
+                    closure_base_def_id
+                );
+                assert_eq!(
+                    substs.substs.regions().count(),
+                    identity_substs.regions().count(),
+                    "regions of {:?} do not match those inherited from {:?}",
+                    self.mir_def_id,
+                    closure_base_def_id
+                );
                 substs.substs
             }
-            ty::TyFnDef(_, substs) => substs,
+            ty::TyFnDef(_, substs) => {
+                // For a method, these are the regions of the impl (or
+                // trait) followed by the early-bound regions of the
+                // method itself, just like in `identity_substs`.
+                assert_eq!(substs.regions().count(), identity_substs.regions().count());
+                substs
+            }
+
+            ty::TyFnPtr(_) if self.shim_sig.is_some() => identity_substs,
+
+            // For constants and statics -- including the constant
+            // expressions such as the `22` in `[foo; 22]` -- the
+            // defining type is just the type of the value, which
+            // says nothing about the generics in scope. An
+            // early-bound region of the item that appears in that
+            // type keeps the variable that `defining_ty` already
+            // gave it; only the others get a variable of their own.
+            _ if self.is_const_or_static() => {
+                let mut renumbered = FxHashMap();
+                let mut origins = vec![];
+                tcx.for_each_free_region(&self.unrenumbered_defining_ty(), |r| origins.push(r));
+                let mut vids = vec![];
+                tcx.for_each_free_region(&defining_ty, |r| vids.push(r));
+                for (origin, r) in origins.into_iter().zip(vids) {
+                    renumbered.entry(origin).or_insert(r);
+                }
 
-            // FIXME. When we encounter other sorts of constant
-            // expressions, such as the `22` in `[foo; 22]`, we can
-            // get the type `usize` here. For now, just return an
-            // empty vector of substs in this case, since there are no
-            // generics in scope in such expressions right now.
-            //
-            // Eventually I imagine we could get a wider range of
-            // types.  What is the best way to handle this? Should we
-            // be checking something other than the type of the def-id
-            // to figure out what to do (e.g. the def-key?).
-            ty::TyUint(..) => {
-                assert!(identity_substs.is_empty());
-                identity_substs
+                tcx.fold_regions(&identity_substs, &mut false, |r, _| {
+                    renumbered
+                        .get(&r)
+                        .cloned()
+                        .unwrap_or_else(|| self.infcx.next_nll_region_var(FR))
+                })
             }
 
             _ => span_bug!(
@@ -541,8 +1838,72 @@ impl<'cx, 'gcx, 'tcx> UniversalRegionsBuilder<'cx, 'gcx, 'tcx> {
             .regions()
             .zip(fr_substs.regions().map(|r| r.to_region_vid()));
 
+        // `Substs::identity_for_item` starts with the substs of the
+        // parent, so the first regions are those of the impl or trait.
+        // The parent of a constructor is its ADT, whose lifetimes are
+        // as much the constructor's own as those of a fn are.
+        let num_parent_regions = if self.is_ctor() {
+            0
+        } else {
+            tcx.generics_of(closure_base_def_id).parent_regions as usize
+        };
+        let parent_regions = identity_substs.regions().take(num_parent_regions).collect();
+
+        let mut indices = FxHashMap();
+        for (r, vid) in global_mapping.chain(subst_mapping) {
+            let previous = indices.insert(r, vid);
+            debug_assert!(
+                previous.is_none(),
+                "compute_indices: region {:?} inserted twice ({:?} and {:?})",
+                r,
+                previous,
+                vid
+            );
+        }
+
         UniversalRegionIndices {
-            indices: global_mapping.chain(subst_mapping).collect(),
+            indices,
+            parent_regions,
+            fr_static,
+            placeholders: FxHashMap(),
+            first_placeholder_index: 0,
+            placeholder_bound_regions: vec![],
+            span: tcx.def_span(self.mir_def_id),
+            sess: tcx.sess,
+        }
+    }
+
+    /// The return type of a fn may mention `impl Trait` types, each
+    /// of which carries its own early-bound regions (e.g., the copy
+    /// of `'a` in `impl Fn() + 'a`). These regions are not among the
+    /// generics of the fn itself, so `compute_indices` does not know
+    /// about them. Each of them stands for the region of the fn that
+    /// the anonymous type's substs put in its place, which already
+    /// has a variable in the (liberated) `inputs_and_output`; we map
+    /// the region to that variable.
+    fn add_anon_type_regions(
+        &self,
+        indices: &mut UniversalRegionIndices<'tcx>,
+        inputs_and_output: &'tcx ty::Slice<Ty<'tcx>>,
+    ) {
+        let gcx = self.infcx.tcx.global_tcx();
+        let output = match inputs_and_output.last() {
+            Some(&output) => output,
+            None => return,
+        };
+
+        for ty in output.walk() {
+            if let ty::TyAnon(anon_def_id, substs) = ty.sty {
+                let identity_substs = Substs::identity_for_item(gcx, anon_def_id);
+                for (r, actual) in identity_substs.regions().zip(substs.regions()) {
+                    if !indices.indices.contains_key(r) {
+                        let vid = actual.to_region_vid();
+                        debug!("add_anon_type_regions: {:?} in {:?} => {:?}",
+                               r, anon_def_id, vid);
+                        indices.indices.insert(r, vid);
+                    }
+                }
+            }
         }
     }
 
@@ -555,9 +1916,39 @@ impl<'cx, 'gcx, 'tcx> UniversalRegionsBuilder<'cx, 'gcx, 'tcx> {
         match defining_ty.sty {
             ty::TyClosure(def_id, substs) => {
                 assert_eq!(self.mir_def_id, def_id);
-                let closure_sig = substs.closure_sig_ty(def_id, tcx).fn_sig(tcx);
+
+                // `closure_env_ty` picks the type of the environment
+                // from the closure kind: the closure type itself for
+                // an `FnOnce` closure, which consumes its environment,
+                // and a reference (with a fresh bound region) to it
+                // for `FnMut` and `Fn`.
+                //
+                // After a type error, the kind or the signature may
+                // never have been inferred. The body is then tainted
+                // by errors and will not be borrow-checked, so we just
+                // need something that does not ICE: the closure type
+                // as the only input, and an error as the output.
+                let closure_sig_ty = substs.closure_sig_ty(def_id, tcx);
+                let is_sig_resolved = match closure_sig_ty.sty {
+                    ty::TyFnPtr(_) => true,
+                    _ => false,
+                };
+                let closure_ty = match tcx.closure_env_ty(def_id, substs) {
+                    Some(closure_ty) if is_sig_resolved => closure_ty,
+                    _ => {
+                        delay_nll_bug(
+                            tcx.sess,
+                            "closure signature",
+                            tcx.def_span(def_id),
+                            &format!("closure with unresolved kind or signature: {:?}", substs),
+                        );
+                        return ty::Binder::dummy(
+                            tcx.intern_type_list(&[defining_ty, tcx.types.err]),
+                        );
+                    }
+                };
+                let closure_sig = closure_sig_ty.fn_sig(tcx);
                 let inputs_and_output = closure_sig.inputs_and_output();
-                let closure_ty = tcx.closure_env_ty(def_id, substs).unwrap();
                 ty::Binder::fuse(
                     closure_ty,
                     inputs_and_output,
@@ -566,12 +1957,28 @@ impl<'cx, 'gcx, 'tcx> UniversalRegionsBuilder<'cx, 'gcx, 'tcx> {
                         // signature appear as a tuple.  The MIR side
                         // flattens this tuple.
                         let (&output, tuplized_inputs) = inputs_and_output.split_last().unwrap();
-                        assert_eq!(tuplized_inputs.len(), 1, "multiple closure inputs");
-                        let inputs = match tuplized_inputs[0].sty {
-                            ty::TyTuple(inputs, _) => inputs,
-                            _ => bug!("closure inputs not a tuple: {:?}", tuplized_inputs[0]),
+                        let inputs = match tuplized_inputs.len() {
+                            1 => match tuplized_inputs[0].sty {
+                                ty::TyTuple(inputs, _) => Some(&inputs[..]),
+                                _ => None,
+                            },
+                            _ => None,
                         };
 
+                        // Error recovery can leave behind a signature
+                        // whose inputs are not a single tuple. Take
+                        // them as they are; `renumber_mir` copes with
+                        // a count that does not match the MIR.
+                        let inputs = inputs.unwrap_or_else(|| {
+                            delay_nll_bug(
+                                tcx.sess,
+                                "closure signature",
+                                tcx.def_span(def_id),
+                                &format!("closure inputs not a tuple: {:?}", tuplized_inputs),
+                            );
+                            tuplized_inputs
+                        });
+
                         tcx.mk_type_list(
                             iter::once(closure_ty)
                                 .chain(inputs.iter().cloned())
@@ -582,24 +1989,68 @@ impl<'cx, 'gcx, 'tcx> UniversalRegionsBuilder<'cx, 'gcx, 'tcx> {
             }
 
             ty::TyGenerator(def_id, substs, ..) => {
+                // For generators, the yield type goes just before the
+                // output, so that `build` can split it off again.
                 assert_eq!(self.mir_def_id, def_id);
+                let yield_ty = substs.generator_yield_ty(def_id, tcx);
                 let output = substs.generator_return_ty(def_id, tcx);
-                let inputs_and_output = self.infcx.tcx.intern_type_list(&[defining_ty, output]);
+                let inputs_and_output =
+                    self.infcx.tcx.intern_type_list(&[defining_ty, yield_ty, output]);
                 ty::Binder::dummy(inputs_and_output)
             }
 
             ty::TyFnDef(def_id, _) => {
+                // Foreign fns have no body of their own; their
+                // signatures only show up inside other bodies, as the
+                // types of operands, and must not contribute to the
+                // universal regions of the body being checked.
+                if tcx.is_foreign_item(def_id) {
+                    span_bug!(
+                        tcx.def_span(self.mir_def_id),
+                        "foreign fn {:?} used as defining type",
+                        def_id
+                    );
+                }
+
                 let sig = tcx.fn_sig(def_id);
+
+                // Only foreign fns can be variadic, so the inputs
+                // here always match the MIR arguments one-to-one.
+                // Calls *to* variadic fns are fine: the type check
+                // matches their extra arguments against nothing.
+                if sig.variadic() {
+                    span_bug!(
+                        tcx.def_span(self.mir_def_id),
+                        "variadic fn {:?} used as defining type",
+                        def_id
+                    );
+                }
+
+                // Unlike the signature of a closure, that of an
+                // `extern "rust-call"` fn item (say, a hand-written
+                // `FnOnce::call_once`) is left as is: its body takes
+                // the tupled arguments as a single argument, as it was
+                // declared, and only the ABI spreads them out (see
+                // `Mir::spread_arg`). Flattening the tuple here would
+                // match the inputs with the wrong argument locals.
                 let sig = indices.fold_to_region_vids(tcx, &sig);
                 sig.inputs_and_output()
             }
 
-            // FIXME: as above, this happens on things like `[foo;
-            // 22]`. For now, no inputs, one output, but it seems like
-            // we need a more general way to handle this category of
-            // MIR.
-            ty::TyUint(..) => {
-                ty::Binder::dummy(tcx.mk_type_list(iter::once(defining_ty)))
+            // The signature of a shim has already had its free regions
+            // replaced along with those of the defining type.
+            ty::TyFnPtr(sig) if self.shim_sig.is_some() => sig.inputs_and_output(),
+
+            // Constants and statics have no inputs, and their output
+            // is the declared type of the item, expressed in terms of
+            // the universal regions (so `'static` really is
+            // `'static`), rather than the defining type with its
+            // regions replaced by fresh ones. The initializer must
+            // then produce a value with regions at least that long.
+            _ if self.is_const_or_static() => {
+                let item_ty = tcx.type_of(self.mir_def_id);
+                let output = indices.fold_to_region_vids(tcx, &item_ty);
+                ty::Binder::dummy(tcx.mk_type_list(iter::once(output)))
             }
 
             _ => span_bug!(
@@ -610,25 +2061,203 @@ impl<'cx, 'gcx, 'tcx> UniversalRegionsBuilder<'cx, 'gcx, 'tcx> {
         }
     }
 
-    /// Update the type of a single local, which should represent
-    /// either the return type of the MIR or one of its arguments. At
-    /// the same time, compute and add any implied bounds that come
-    /// from this local.
+    /// Computes and adds the implied bounds that come from the
+    /// inputs and output of the MIR, `inputs_and_output`.
     ///
     /// Assumes that `universal_regions` indices map is fully constructed.
-    fn add_implied_bounds(&mut self, indices: &UniversalRegionIndices<'tcx>, ty: Ty<'tcx>) {
-        debug!("add_implied_bounds(ty={:?})", ty);
-        let span = self.infcx.tcx.def_span(self.mir_def_id);
-        let bounds = self.infcx
-            .implied_outlives_bounds(self.param_env, self.mir_node_id, ty, span);
-        self.add_outlives_bounds(indices, bounds);
+    fn add_implied_bounds(
+        &mut self,
+        indices: &UniversalRegionIndices<'tcx>,
+        inputs_and_output: &[Ty<'tcx>],
+    ) {
+        debug!("add_implied_bounds(inputs_and_output={:?})", inputs_and_output);
+
+        self.compute_implied_bounds(indices, inputs_and_output);
+
+        let output_index = inputs_and_output.len() - 1;
+        for (index, ty) in inputs_and_output.iter().enumerate() {
+            debug!("add_implied_bounds: ty={:?}", ty);
+            let origin = if index == output_index {
+                RegionBoundPairOrigin::ImpliedByOutput
+            } else {
+                RegionBoundPairOrigin::ImpliedByInput(index)
+            };
+            let bounds = match self.implied_bounds_cache.get(ty) {
+                Some(bounds) => bounds.clone(),
+                None => continue,
+            };
+            self.add_outlives_bounds(indices, origin, bounds);
+        }
+    }
+
+    /// Computes and adds the implied bounds that come from the types
+    /// of the upvars captured by a closure or generator. The body may
+    /// assume them just as it would for its parameters: capturing a
+    /// `&'a Foo<T>` implies `T: 'a`. For other bodies, this is a
+    /// no-op.
+    ///
+    /// The upvar types mention only the external regions of the
+    /// closure, so this too requires the indices map to be complete.
+    fn add_upvar_implied_bounds(
+        &mut self,
+        indices: &UniversalRegionIndices<'tcx>,
+        defining_ty: Ty<'tcx>,
+    ) {
+        let upvar_tys: Vec<Ty<'tcx>> = match defining_ty.sty {
+            ty::TyClosure(def_id, substs) | ty::TyGenerator(def_id, substs, _) => {
+                substs.upvar_tys(def_id, self.infcx.tcx).collect()
+            }
+            _ => return,
+        };
+        debug!("add_upvar_implied_bounds(upvar_tys={:?})", upvar_tys);
+
+        self.compute_implied_bounds(indices, &upvar_tys);
+
+        for (index, ty) in upvar_tys.iter().enumerate() {
+            let bounds = match self.implied_bounds_cache.get(ty) {
+                Some(bounds) => bounds.clone(),
+                None => continue,
+            };
+            let origin = RegionBoundPairOrigin::ImpliedByUpvar(index);
+            self.add_outlives_bounds(indices, origin, bounds);
+        }
+    }
+
+    /// Fills in `implied_bounds_cache` for those of `tys` that can
+    /// imply any bounds at all. The bounds are those of the normalized
+    /// types (see `normalize_for_implied_bounds`), but are cached
+    /// under the types as given.
+    fn compute_implied_bounds(&mut self, indices: &UniversalRegionIndices<'tcx>, tys: &[Ty<'tcx>]) {
+        // A type with neither free regions nor type parameters cannot
+        // imply any bounds that we care about. (By now, the free
+        // regions of `ty` have been replaced by inference variables,
+        // which `has_erasable_regions` accounts for.) Of the others,
+        // compute the bounds of those we have not seen yet all at once.
+        let mut new_tys = vec![];
+        for &ty in tys {
+            if (ty.has_erasable_regions() || ty.has_param_types())
+                && !self.implied_bounds_cache.contains_key(&ty)
+                && !new_tys.contains(&ty)
+            {
+                new_tys.push(ty);
+            }
+        }
+        if !new_tys.is_empty() {
+            let span = self.infcx.tcx.def_span(self.mir_def_id);
+            let normalized_tys: Vec<Ty<'tcx>> = new_tys
+                .iter()
+                .map(|&ty| self.normalize_for_implied_bounds(indices, ty, span))
+                .collect();
+            let num_region_vars = self.infcx.num_region_vars();
+            let mut new_bounds = self.infcx.implied_outlives_bounds_for_tys(
+                self.param_env,
+                self.mir_node_id,
+                &normalized_tys,
+                span,
+            );
+
+            // Normalizing a projection in the types may have created
+            // region variables, e.g., for the substs of the impl that
+            // it was resolved with. These are not universal regions
+            // (they come after all of those), so the bounds that
+            // mention them tell us nothing we could use; drop them.
+            let num_stray_region_vars = self.infcx.num_region_vars() - num_region_vars;
+            if num_stray_region_vars > 0 {
+                debug!(
+                    "compute_implied_bounds: {} stray region variables",
+                    num_stray_region_vars
+                );
+                self.num_stray_region_vars += num_stray_region_vars;
+                let tcx = self.infcx.tcx;
+                let is_stray = |r: ty::Region<'tcx>| match *r {
+                    ty::ReVar(vid) => vid.index() >= num_region_vars,
+                    _ => false,
+                };
+                for bounds in &mut new_bounds {
+                    bounds.retain(|bound| match *bound {
+                        OutlivesBound::RegionSubRegion(r1, r2) => !is_stray(r1) && !is_stray(r2),
+                        OutlivesBound::RegionSubParam(r, _) => !is_stray(r),
+                        OutlivesBound::RegionSubProjection(r, projection) => {
+                            let mut mentions_stray = is_stray(r);
+                            tcx.for_each_free_region(&projection.substs, |r| {
+                                mentions_stray |= is_stray(r);
+                            });
+                            !mentions_stray
+                        }
+                    });
+                }
+            }
+
+            self.implied_bounds_cache.extend(new_tys.into_iter().zip(new_bounds));
+        }
+    }
+
+    /// Normalizes `ty` for the purposes of computing its implied
+    /// bounds. Those of a projection like `<T as Trait<'a>>::Assoc`
+    /// would be bounds on the projection itself, which the type tests
+    /// in the body, made against its normalized form, never find.
+    ///
+    /// Normalization may equate our universal regions with fresh
+    /// region variables, e.g. for the substs of an impl; these are
+    /// resolved back to the universal regions. If `ty` cannot be
+    /// normalized, or only in terms of regions that mean nothing
+    /// outside of the normalization, it is kept as is.
+    fn normalize_for_implied_bounds(
+        &self,
+        indices: &UniversalRegionIndices<'tcx>,
+        ty: Ty<'tcx>,
+        span: Span,
+    ) -> Ty<'tcx> {
+        if !ty.has_projections() {
+            return ty;
+        }
+
+        let infcx = self.infcx;
+        let num_region_vars = infcx.num_region_vars();
+        let normalized_ty = infcx.probe(|_| {
+            let cause = ObligationCause::misc(span, self.mir_node_id);
+            let mut selcx = SelectionContext::new(infcx);
+            let Normalized { value, obligations } =
+                traits::normalize(&mut selcx, self.param_env, cause, &ty);
+
+            let mut fulfill_cx = FulfillmentContext::new();
+            fulfill_cx.register_predicate_obligations(infcx, obligations);
+            if fulfill_cx.select_all_or_error(infcx).is_err() {
+                return None;
+            }
+
+            let value = value.fold_with(&mut OpportunisticTypeAndRegionResolver::new(infcx));
+            let mut mentions_fresh_region = false;
+            infcx.tcx.for_each_free_region(&value, |r| {
+                if let ty::ReVar(vid) = *r {
+                    mentions_fresh_region |= vid.index() >= num_region_vars;
+                }
+            });
+            if value.has_infer_types() || mentions_fresh_region {
+                None
+            } else {
+                Some(value)
+            }
+        });
+        debug!("normalize_for_implied_bounds: {:?} => {:?}", ty, normalized_ty);
+
+        match normalized_ty {
+            // Regions taken from the `param_env` are still the
+            // early-bound ones; express them as vids like the rest.
+            Some(normalized_ty) => indices.fold_to_region_vids(infcx.tcx, &normalized_ty),
+            None => ty,
+        }
     }
 
     /// Registers the `OutlivesBound` items from `outlives_bounds` in
     /// the outlives relation as well as the region-bound pairs
-    /// listing.
-    fn add_outlives_bounds<I>(&mut self, indices: &UniversalRegionIndices<'tcx>, outlives_bounds: I)
-    where
+    /// listing, recording that the latter came from `origin`.
+    fn add_outlives_bounds<I>(
+        &mut self,
+        indices: &UniversalRegionIndices<'tcx>,
+        origin: RegionBoundPairOrigin,
+        outlives_bounds: I,
+    ) where
         I: IntoIterator<Item = OutlivesBound<'tcx>>,
     {
         for outlives_bound in outlives_bounds {
@@ -639,34 +2268,45 @@ impl<'cx, 'gcx, 'tcx> UniversalRegionsBuilder<'cx, 'gcx, 'tcx> {
                     // The bound says that `r1 <= r2`; we store `r2: r1`.
                     let r1 = indices.to_region_vid(r1);
                     let r2 = indices.to_region_vid(r2);
-                    self.relations.relate_universal_regions(r2, r1);
+                    self.relate_universal_regions(r2, r1);
+                    self.outlives_facts.push(OutlivesFact {
+                        sup: r2,
+                        sub: r1,
+                        origin,
+                    });
                 }
 
                 OutlivesBound::RegionSubParam(r_a, param_b) => {
-                    self.region_bound_pairs
-                        .push((r_a, GenericKind::Param(param_b)));
+                    self.add_region_bound_pair(r_a, GenericKind::Param(param_b), origin);
                 }
 
                 OutlivesBound::RegionSubProjection(r_a, projection_b) => {
-                    self.region_bound_pairs
-                        .push((r_a, GenericKind::Projection(projection_b)));
+                    self.add_region_bound_pair(r_a, GenericKind::Projection(projection_b), origin);
                 }
             }
         }
     }
-}
 
-impl UniversalRegionRelations {
-    /// Records in the `outlives_relation` (and
-    /// `inverse_outlives_relation`) that `fr_a: fr_b`.
+    /// Records in the relations that `fr_a: fr_b`, unless that was
+    /// already done.
     fn relate_universal_regions(&mut self, fr_a: RegionVid, fr_b: RegionVid) {
-        debug!(
-            "relate_universal_regions: fr_a={:?} outlives fr_b={:?}",
-            fr_a,
-            fr_b
-        );
-        self.outlives.add(fr_a, fr_b);
-        self.inverse_outlives.add(fr_b, fr_a);
+        if self.related_regions.insert((fr_a, fr_b)) {
+            self.relations.relate_universal_regions(fr_a, fr_b);
+        }
+    }
+
+    /// Records the region-bound pair `kind: r`, unless it is already
+    /// known; in that case, the first origin is kept.
+    fn add_region_bound_pair(
+        &mut self,
+        r: ty::Region<'tcx>,
+        kind: GenericKind<'tcx>,
+        origin: RegionBoundPairOrigin,
+    ) {
+        if self.known_region_bound_pairs.insert((r, kind)) {
+            self.region_bound_pairs.push((r, kind));
+            self.region_bound_pair_origins.push(origin);
+        }
     }
 }
 
@@ -683,7 +2323,7 @@ pub(crate) trait InferCtxtExt<'tcx> {
         &self,
         origin: NLLRegionVariableOrigin,
         value: &ty::Binder<T>,
-    ) -> T
+    ) -> (T, BTreeMap<ty::BoundRegion, ty::Region<'tcx>>)
     where
         T: TypeFoldable<'tcx>;
 }
@@ -697,9 +2337,9 @@ impl<'cx, 'gcx, 'tcx> InferCtxtExt<'tcx> for InferCtxt<'cx, 'gcx, 'tcx> {
     where
         T: TypeFoldable<'tcx>,
     {
-        self.tcx.fold_regions(
+        fold_regions_unless_region_free(
+            self.tcx,
             value,
-            &mut false,
             |_region, _depth| self.next_nll_region_var(origin),
         )
     }
@@ -708,13 +2348,24 @@ impl<'cx, 'gcx, 'tcx> InferCtxtExt<'tcx> for InferCtxt<'cx, 'gcx, 'tcx> {
         &self,
         origin: NLLRegionVariableOrigin,
         value: &ty::Binder<T>,
-    ) -> T
+    ) -> (T, BTreeMap<ty::BoundRegion, ty::Region<'tcx>>)
     where
         T: TypeFoldable<'tcx>,
     {
-        let (value, _map) = self.tcx
-            .replace_late_bound_regions(value, |_br| self.next_nll_region_var(origin));
-        value
+        // Only the regions bound by `value` itself are replaced, and
+        // those are the ones that escape its contents.
+        if !value.skip_binder().has_escaping_regions() {
+            let verify = self.tcx.sess.opts.debugging_opts.nll_verify_fold_fast_path;
+            if cfg!(debug_assertions) && verify {
+                self.tcx.replace_late_bound_regions(value, |br| {
+                    bug!("fold fast path skipped bound region `{:?}` in `{:?}`", br, value)
+                });
+            }
+            return (value.skip_binder().clone(), BTreeMap::new());
+        }
+
+        self.tcx
+            .replace_late_bound_regions(value, |_br| self.next_nll_region_var(origin))
     }
 }
 
@@ -727,24 +2378,141 @@ impl<'tcx> UniversalRegionIndices<'tcx> {
     /// reference those regions from the `ParamEnv`. It is also used
     /// during initialization. Relies on the `indices` map having been
     /// fully initialized.
+    ///
+    /// Constraints coming back from trait selection may also mention
+    /// skolemized regions, which we map to their placeholders, and --
+    /// if something went through a query that erases regions --
+    /// `ReErased`. (They may mention `ReEmpty` as well, but each
+    /// occurrence of it gets its own variable before we get here; see
+    /// `subtype_constraint_generation::replace_empty_regions`.) We cannot know what an erased
+    /// region stood for, so we conservatively map it to `'static`,
+    /// but also record a delayed bug, as this should not happen.
     pub fn to_region_vid(&self, r: ty::Region<'tcx>) -> RegionVid {
         match r {
             ty::ReEarlyBound(..) | ty::ReStatic => *self.indices.get(&r).unwrap(),
             ty::ReVar(..) => r.to_region_vid(),
+            ty::ReSkolemized(..) => match self.placeholders.get(&r) {
+                Some(&placeholder) => placeholder,
+                None => span_bug!(self.span, "`{:?}` before its placeholder was created", r),
+            },
+            ty::ReErased => {
+                delay_nll_bug(
+                    self.sess,
+                    "region conversion",
+                    self.span,
+                    "erased region in NLL constraints",
+                );
+                self.fr_static
+            }
             _ => bug!("cannot convert `{:?}` to a region vid", r),
         }
     }
 
     /// Replace all free regions in `value` with region vids, as
-    /// returned by `to_region_vid`.
+    /// returned by `to_region_vid` (so `ReErased` is replaced as
+    /// well).
+    ///
+    /// Late-bound regions are left untouched: `fold_regions` already
+    /// skips those bound by a binder within `value` (e.g., the `'x`
+    /// in an argument of type `for<'x> fn(&'x u32)`), and those bound
+    /// by a binder outside of it (e.g., when folding the inputs of a
+    /// signature whose binder was skipped) are liberated separately.
     pub fn fold_to_region_vids<T>(&self, tcx: TyCtxt<'_, '_, 'tcx>, value: &T) -> T
     where
         T: TypeFoldable<'tcx>,
     {
-        tcx.fold_regions(
-            value,
-            &mut false,
-            |region, _| tcx.mk_region(ty::ReVar(self.to_region_vid(region))),
-        )
+        fold_regions_unless_region_free(tcx, value, |region, current_depth| match *region {
+            ty::ReLateBound(debruijn, _) => {
+                debug_assert!(debruijn.depth >= current_depth);
+                region
+            }
+            _ => tcx.mk_region(ty::ReVar(self.to_region_vid(region))),
+        })
+    }
+}
+
+/// Builds the vector `V` of `UniversalRegions::closure_mapping`: `'static`
+/// at index 0, followed by the free regions of the closure type, in
+/// order. Fails if that does not give exactly `expected_num_vars`
+/// entries.
+fn map_closure_regions<R>(
+    re_static: R,
+    free_regions: Vec<R>,
+    expected_num_vars: usize,
+) -> Result<IndexVec<RegionVid, R>, ClosureMappingError> {
+    let found_num_vars = free_regions.len() + 1;
+    if found_num_vars != expected_num_vars {
+        return Err(ClosureMappingError {
+            expected_num_vars,
+            found_num_vars,
+        });
+    }
+
+    let mut region_mapping = IndexVec::with_capacity(expected_num_vars);
+    region_mapping.push(re_static);
+    region_mapping.extend(free_regions);
+    Ok(region_mapping)
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc::ty::RegionVid;
+    use rustc_data_structures::indexed_vec::Idx;
+    use std::cell::Cell;
+    use super::{map_closure_regions, BoundDirection, ClosureMappingError, NonLocalBoundCache};
+
+    #[test]
+    fn closure_mapping_matching_counts() {
+        let mapping = map_closure_regions("'static", vec!["'a", "'b"], 3).unwrap();
+        assert_eq!(mapping[RegionVid::new(0)], "'static");
+        assert_eq!(mapping[RegionVid::new(1)], "'a");
+        assert_eq!(mapping[RegionVid::new(2)], "'b");
+    }
+
+    #[test]
+    fn closure_mapping_mismatched_counts() {
+        assert_eq!(
+            map_closure_regions("'static", vec!["'a"], 3),
+            Err(ClosureMappingError { expected_num_vars: 3, found_num_vars: 2 })
+        );
+        assert_eq!(
+            map_closure_regions("'static", vec!["'a", "'b", "'c"], 3),
+            Err(ClosureMappingError { expected_num_vars: 3, found_num_vars: 4 })
+        );
+    }
+
+    #[test]
+    fn non_local_bound_cache_repeated_query() {
+        let cache = NonLocalBoundCache::default();
+        let fr = RegionVid::new(3);
+        let calls = Cell::new(0);
+        let compute = || {
+            calls.set(calls.get() + 1);
+            Some(RegionVid::new(1))
+        };
+
+        assert_eq!(cache.get_or_compute(BoundDirection::Upper, fr, &compute),
+                   Some(RegionVid::new(1)));
+        assert_eq!(calls.get(), 1);
+
+        // The second query is answered from the cache; only the debug
+        // assertion recomputes it.
+        assert_eq!(cache.get_or_compute(BoundDirection::Upper, fr, &compute),
+                   Some(RegionVid::new(1)));
+        assert_eq!(calls.get(), if cfg!(debug_assertions) { 2 } else { 1 });
+
+        // The other direction is cached separately.
+        assert_eq!(cache.get_or_compute(BoundDirection::Lower, fr, || None), None);
+        assert_eq!(cache.get_or_compute(BoundDirection::Lower, fr, || None), None);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "stale non-local bound")]
+    fn non_local_bound_cache_checks_hits() {
+        let cache = NonLocalBoundCache::default();
+        let fr = RegionVid::new(3);
+        cache.get_or_compute(BoundDirection::Lower, fr, || None);
+        cache.get_or_compute(BoundDirection::Lower, fr, || Some(RegionVid::new(0)));
     }
 }
@@ -0,0 +1,74 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `-Znll-signature-borrows-report`: prints, for each fn, the borrows
+//! whose region reaches the return points of its body, together with
+//! the regions of its signature that they have to outlive. Since
+//! such borrows constrain the callers of the fn, this is meant to
+//! help find accidentally long borrows in library APIs.
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::mir::BorrowKind;
+
+use super::MirBorrowckCtxt;
+use super::nll::ToRegionVid;
+use super::nll::region_infer::RegionInferenceContext;
+
+impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
+    /// Prints the report for the body `def_id` to stdout, if it is a
+    /// fn. The lines all start with `nll-signature-borrows`, and come
+    /// in the order of the borrows in the MIR.
+    pub(super) fn report_signature_borrows(
+        &self,
+        regioncx: &RegionInferenceContext<'tcx>,
+        def_id: DefId,
+    ) {
+        let tcx = self.tcx;
+        if tcx.hir.body_owner_kind(self.node_id) != hir::BodyOwnerKind::Fn
+            || tcx.is_closure(def_id)
+        {
+            return;
+        }
+
+        let mut lines = vec![];
+        for borrow in self.borrow_set.borrows.iter() {
+            let borrow_region = borrow.region.to_region_vid();
+            let bounds = match regioncx.signature_borrow_bounds(self.mir, borrow_region) {
+                Some(bounds) => bounds,
+                None => continue,
+            };
+            let kind = match borrow.kind {
+                BorrowKind::Shared => "shared",
+                BorrowKind::Unique => "unique",
+                BorrowKind::Mut => "mutable",
+            };
+            let place = self.describe_place(&borrow.borrowed_place)
+                .unwrap_or("_".to_owned());
+            let extent = if bounds.is_empty() {
+                String::from("reaches the return points")
+            } else {
+                let bounds: Vec<String> = bounds.iter().map(|b| format!("`{}`", b)).collect();
+                format!("outlives {}", bounds.join(", "))
+            };
+            lines.push(format!("{} borrow of `{}`: {}", kind, place, extent));
+        }
+
+        println!(
+            "nll-signature-borrows fn: `{}`: {} of {} borrows",
+            tcx.item_path_str(def_id),
+            lines.len(),
+            self.borrow_set.borrows.len()
+        );
+        for line in lines {
+            println!("nll-signature-borrows     {}", line);
+        }
+    }
+}
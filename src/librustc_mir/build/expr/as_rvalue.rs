@@ -193,7 +193,25 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
             ExprKind::Closure { closure_id, substs, upvars, interior } => { // see (*) above
                 let mut operands: Vec<_> =
                     upvars.into_iter()
-                          .map(|upvar| unpack!(block = this.as_operand(block, scope, upvar)))
+                          .map(|upvar| {
+                              let upvar = this.hir.mirror(upvar);
+                              // A variable captured by unique immutable borrow
+                              // is only used through, not borrowed by, the
+                              // closure body; record that use so borrowck can
+                              // blame the capture rather than the borrow.
+                              if let ExprKind::Borrow {
+                                  borrow_kind: BorrowKind::Unique, ref arg, ..
+                              } = upvar.kind {
+                                  let place = unpack!(block = this.as_place(block, arg.clone()));
+                                  let source_info = this.source_info(upvar.span);
+                                  this.cfg.push(block, Statement {
+                                      source_info,
+                                      kind: StatementKind::FakeRead(FakeReadCause::ForCapture,
+                                                                    place),
+                                  });
+                              }
+                              unpack!(block = this.as_operand(block, scope, upvar))
+                          })
                           .collect();
                 let result = if let Some(interior) = interior {
                     // Add the state operand since it follows the upvars in the generator
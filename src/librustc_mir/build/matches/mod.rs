@@ -36,8 +36,18 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                       discriminant: ExprRef<'tcx>,
                       arms: Vec<Arm<'tcx>>)
                       -> BlockAnd<()> {
+        let discriminant = self.hir.mirror(discriminant);
+        let discriminant_span = discriminant.span;
         let discriminant_place = unpack!(block = self.as_place(block, discriminant));
 
+        // The scrutinee must remain valid while we select an arm, even
+        // if no pattern actually reads it (e.g., `match x { _ => .. }`).
+        let source_info = self.source_info(discriminant_span);
+        self.cfg.push(block, Statement {
+            source_info,
+            kind: StatementKind::FakeRead(FakeReadCause::ForMatch, discriminant_place.clone()),
+        });
+
         let mut arm_blocks = ArmBlocks {
             blocks: arms.iter()
                         .map(|_| self.cfg.start_new_block())
@@ -145,7 +155,18 @@ impl<'a, 'gcx, 'tcx> Builder<'a, 'gcx, 'tcx> {
                 block.unit()
             }
             _ => {
+                let initializer = self.hir.mirror(initializer);
+                let initializer_span = initializer.span;
                 let place = unpack!(block = self.as_place(block, initializer));
+
+                // Like the scrutinee of a `match`, the initializer is
+                // read even if the pattern binds nothing (`let _ = x;`).
+                let source_info = self.source_info(initializer_span);
+                self.cfg.push(block, Statement {
+                    source_info,
+                    kind: StatementKind::FakeRead(FakeReadCause::ForLet, place.clone()),
+                });
+
                 self.place_into_pattern(block, irrefutable_pat, &place)
             }
         }
@@ -20,52 +20,33 @@ use rustc::util::nodemap::{FxHashMap, FxHashSet};
 
 use rustc_data_structures::bitslice::{BitwiseOperator};
 use rustc_data_structures::indexed_set::{IdxSet};
-use rustc_data_structures::indexed_vec::{Idx, IndexVec};
+use rustc_data_structures::indexed_vec::Idx;
+use rustc_errors::DiagnosticBuilder;
 
 use dataflow::{BitDenotation, BlockSets, InitialFlow};
 pub use dataflow::indexes::{BorrowIndex, ReserveOrActivateIndex};
+use borrow_check::borrow_set::{BorrowData, BorrowSet, is_potential_use, is_unsafe_place};
 use borrow_check::nll::region_infer::RegionInferenceContext;
 use borrow_check::nll::ToRegionVid;
+use util::try_desugar::{question_mark_span, try_desugar_call, TryDesugarCall};
 
 use syntax_pos::Span;
 
-use std::fmt;
-use std::hash::Hash;
 use std::rc::Rc;
 
 /// `Borrows` stores the data used in the analyses that track the flow
 /// of borrows.
 ///
-/// It uniquely identifies every borrow (`Rvalue::Ref`) by a
-/// `BorrowIndex`, and maps each such index to a `BorrowData`
-/// describing the borrow. These indexes are used for representing the
-/// borrows in compact bitvectors.
+/// The borrows themselves (each `Rvalue::Ref`, identified by a
+/// `BorrowIndex`) are gathered up front into a `BorrowSet`; these
+/// indexes are used for representing the borrows in compact
+/// bitvectors.
 pub struct Borrows<'a, 'gcx: 'tcx, 'tcx: 'a> {
     tcx: TyCtxt<'a, 'gcx, 'tcx>,
     mir: &'a Mir<'tcx>,
     scope_tree: Rc<region::ScopeTree>,
     root_scope: Option<region::Scope>,
-
-    /// The fundamental map relating bitvector indexes to the borrows
-    /// in the MIR.
-    borrows: IndexVec<BorrowIndex, BorrowData<'tcx>>,
-
-    /// Each borrow is also uniquely identified in the MIR by the
-    /// `Location` of the assignment statement in which it appears on
-    /// the right hand side; we map each such location to the
-    /// corresponding `BorrowIndex`.
-    location_map: FxHashMap<Location, BorrowIndex>,
-
-    /// Every borrow in MIR is immediately stored into a place via an
-    /// assignment statement. This maps each such assigned place back
-    /// to its borrow-indexes.
-    assigned_map: FxHashMap<Place<'tcx>, FxHashSet<BorrowIndex>>,
-
-    /// Every borrow has a region; this maps each such regions back to
-    /// its borrow-indexes.
-    region_map: FxHashMap<Region<'tcx>, FxHashSet<BorrowIndex>>,
-    local_map: FxHashMap<mir::Local, FxHashSet<BorrowIndex>>,
-    region_span_map: FxHashMap<RegionKind, Span>,
+    borrow_set: &'a BorrowSet<'tcx>,
     nonlexical_regioncx: Option<RegionInferenceContext<'tcx>>,
 }
 
@@ -103,32 +84,6 @@ impl<'a, 'gcx, 'tcx> ActiveBorrows<'a, 'gcx, 'tcx> {
     }
 }
 
-// temporarily allow some dead fields: `kind` and `region` will be
-// needed by borrowck; `borrowed_place` will probably be a MovePathIndex when
-// that is extended to include borrowed data paths.
-#[allow(dead_code)]
-#[derive(Debug)]
-pub struct BorrowData<'tcx> {
-    pub(crate) location: Location,
-    pub(crate) kind: mir::BorrowKind,
-    pub(crate) region: Region<'tcx>,
-    pub(crate) borrowed_place: mir::Place<'tcx>,
-    pub(crate) assigned_place: mir::Place<'tcx>,
-}
-
-impl<'tcx> fmt::Display for BorrowData<'tcx> {
-    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
-        let kind = match self.kind {
-            mir::BorrowKind::Shared => "",
-            mir::BorrowKind::Unique => "uniq ",
-            mir::BorrowKind::Mut => "mut ",
-        };
-        let region = format!("{}", self.region);
-        let region = if region.len() > 0 { format!("{} ", region) } else { region };
-        write!(w, "&{}{}{:?}", region, kind, self.borrowed_place)
-    }
-}
-
 impl ReserveOrActivateIndex {
     fn reserved(i: BorrowIndex) -> Self { ReserveOrActivateIndex::new((i.index() * 2)) }
     fn active(i: BorrowIndex) -> Self { ReserveOrActivateIndex::new((i.index() * 2) + 1) }
@@ -149,133 +104,29 @@ impl<'a, 'gcx, 'tcx> Borrows<'a, 'gcx, 'tcx> {
                mir: &'a Mir<'tcx>,
                nonlexical_regioncx: Option<RegionInferenceContext<'tcx>>,
                def_id: DefId,
-               body_id: Option<hir::BodyId>)
+               body_id: Option<hir::BodyId>,
+               borrow_set: &'a BorrowSet<'tcx>)
                -> Self {
         let scope_tree = tcx.region_scope_tree(def_id);
         let root_scope = body_id.map(|body_id| {
             region::Scope::CallSite(tcx.hir.body(body_id).value.hir_id.local_id)
         });
-        let mut visitor = GatherBorrows {
-            tcx,
-            mir,
-            idx_vec: IndexVec::new(),
-            location_map: FxHashMap(),
-            assigned_map: FxHashMap(),
-            region_map: FxHashMap(),
-            local_map: FxHashMap(),
-            region_span_map: FxHashMap()
-        };
-        visitor.visit_mir(mir);
-        return Borrows { tcx: tcx,
-                         mir: mir,
-                         borrows: visitor.idx_vec,
-                         scope_tree,
-                         root_scope,
-                         location_map: visitor.location_map,
-                         assigned_map: visitor.assigned_map,
-                         region_map: visitor.region_map,
-                         local_map: visitor.local_map,
-                         region_span_map: visitor.region_span_map,
-                         nonlexical_regioncx };
-
-        struct GatherBorrows<'a, 'gcx: 'tcx, 'tcx: 'a> {
-            tcx: TyCtxt<'a, 'gcx, 'tcx>,
-            mir: &'a Mir<'tcx>,
-            idx_vec: IndexVec<BorrowIndex, BorrowData<'tcx>>,
-            location_map: FxHashMap<Location, BorrowIndex>,
-            assigned_map: FxHashMap<Place<'tcx>, FxHashSet<BorrowIndex>>,
-            region_map: FxHashMap<Region<'tcx>, FxHashSet<BorrowIndex>>,
-            local_map: FxHashMap<mir::Local, FxHashSet<BorrowIndex>>,
-            region_span_map: FxHashMap<RegionKind, Span>,
-        }
-
-        impl<'a, 'gcx, 'tcx> Visitor<'tcx> for GatherBorrows<'a, 'gcx, 'tcx> {
-            fn visit_assign(&mut self,
-                            block: mir::BasicBlock,
-                            assigned_place: &mir::Place<'tcx>,
-                            rvalue: &mir::Rvalue<'tcx>,
-                            location: mir::Location) {
-                fn root_local(mut p: &mir::Place<'_>) -> Option<mir::Local> {
-                    loop { match p {
-                        mir::Place::Projection(pi) => p = &pi.base,
-                        mir::Place::Static(_) => return None,
-                        mir::Place::Local(l) => return Some(*l)
-                    }}
-                }
-
-                if let mir::Rvalue::Ref(region, kind, ref borrowed_place) = *rvalue {
-                    if is_unsafe_place(self.tcx, self.mir, borrowed_place) { return; }
-
-                    let borrow = BorrowData {
-                        location, kind, region,
-                        borrowed_place: borrowed_place.clone(),
-                        assigned_place: assigned_place.clone(),
-                    };
-                    let idx = self.idx_vec.push(borrow);
-                    self.location_map.insert(location, idx);
-
-                    insert(&mut self.assigned_map, assigned_place, idx);
-                    insert(&mut self.region_map, &region, idx);
-                    if let Some(local) = root_local(borrowed_place) {
-                        insert(&mut self.local_map, &local, idx);
-                    }
-                }
-
-                return self.super_assign(block, assigned_place, rvalue, location);
-
-                fn insert<'a, K, V>(map: &'a mut FxHashMap<K, FxHashSet<V>>,
-                                    k: &K,
-                                    v: V)
-                    where K: Clone+Eq+Hash, V: Eq+Hash
-                {
-                    map.entry(k.clone())
-                        .or_insert(FxHashSet())
-                        .insert(v);
-                }
-            }
-
-            fn visit_rvalue(&mut self,
-                            rvalue: &mir::Rvalue<'tcx>,
-                            location: mir::Location) {
-                if let mir::Rvalue::Ref(region, kind, ref place) = *rvalue {
-                    // double-check that we already registered a BorrowData for this
-
-                    let mut found_it = false;
-                    for idx in &self.region_map[region] {
-                        let bd = &self.idx_vec[*idx];
-                        if bd.location == location &&
-                            bd.kind == kind &&
-                            bd.region == region &&
-                            bd.borrowed_place == *place
-                        {
-                            found_it = true;
-                            break;
-                        }
-                    }
-                    assert!(found_it, "Ref {:?} at {:?} missing BorrowData", rvalue, location);
-                }
-
-                return self.super_rvalue(rvalue, location);
-            }
-
-            fn visit_statement(&mut self,
-                               block: mir::BasicBlock,
-                               statement: &mir::Statement<'tcx>,
-                               location: Location) {
-                if let mir::StatementKind::EndRegion(region_scope) = statement.kind {
-                    self.region_span_map.insert(ReScope(region_scope), statement.source_info.span);
-                }
-                return self.super_statement(block, statement, location);
-            }
+        Borrows {
+            tcx: tcx,
+            mir: mir,
+            scope_tree,
+            root_scope,
+            borrow_set,
+            nonlexical_regioncx,
         }
     }
 
-    pub fn borrows(&self) -> &IndexVec<BorrowIndex, BorrowData<'tcx>> { &self.borrows }
+    pub fn borrow_set(&self) -> &'a BorrowSet<'tcx> { self.borrow_set }
 
     pub fn scope_tree(&self) -> &Rc<region::ScopeTree> { &self.scope_tree }
 
     pub fn location(&self, idx: BorrowIndex) -> &Location {
-        &self.borrows[idx].location
+        &self.borrow_set.borrows[idx].reserve_location
     }
 
     /// Add all borrows to the kill set, if those borrows are out of scope at `location`.
@@ -303,7 +154,7 @@ impl<'a, 'gcx, 'tcx> Borrows<'a, 'gcx, 'tcx> {
             // terminator *does* introduce a new loan of the same
             // region, then setting that gen-bit will override any
             // potential kill introduced here.
-            for (borrow_index, borrow_data) in self.borrows.iter_enumerated() {
+            for (borrow_index, borrow_data) in self.borrow_set.borrows.iter_enumerated() {
                 let borrow_region = borrow_data.region.to_region_vid();
                 if !regioncx.region_contains_point(borrow_region, location) {
                     sets.kill(&ReserveOrActivateIndex::reserved(borrow_index));
@@ -315,6 +166,122 @@ impl<'a, 'gcx, 'tcx> Borrows<'a, 'gcx, 'tcx> {
         }
     }
 
+    /// If the assignment to `lhs` at `location` completely overwrites
+    /// some local, returns that local, along with the location of the
+    /// first write of the overwrite.
+    ///
+    /// This is the case for a direct assignment `x = ...`, but also
+    /// for the last of a sequence of field-by-field assignments
+    /// `x.0 = ...; x.1 = ...;` (as produced when lowering patterns
+    /// and aggregates) once every field of the tuple or struct `x`
+    /// has been assigned within the current block. We do not look
+    /// past the start of the block, nor past a `StorageLive(x)`,
+    /// which is a conservative but cheap coverage check.
+    ///
+    /// A borrow taken in between the writes of such a sequence may
+    /// refer to a field that has already been written, and so does
+    /// not die with the old value; see `kill_borrows_on_local`.
+    fn overwritten_local(&self,
+                         lhs: &Place<'tcx>,
+                         location: Location)
+                         -> Option<(mir::Local, Location)> {
+        let proj = match *lhs {
+            Place::Local(local) => return Some((local, location)),
+            Place::Static(_) => return None,
+            Place::Projection(ref proj) => proj,
+        };
+
+        let local = match (&proj.base, &proj.elem) {
+            (&Place::Local(local), &mir::ProjectionElem::Field(..)) => local,
+            _ => return None,
+        };
+
+        let num_fields = match self.mir.local_decls[local].ty.sty {
+            ty::TyTuple(tys, _) => tys.len(),
+            ty::TyAdt(adt_def, _) if adt_def.is_struct() => adt_def.struct_variant().fields.len(),
+            _ => return None,
+        };
+
+        // Walk backwards, remembering the most recent write of each
+        // field; the overwrite begins at the earliest of those.
+        let statements = &self.mir[location.block].statements[..location.statement_index + 1];
+        let mut assigned_fields = FxHashSet();
+        let mut first_write = location;
+        for (statement_index, statement) in statements.iter().enumerate().rev() {
+            match statement.kind {
+                mir::StatementKind::Assign(Place::Projection(ref proj), _) => {
+                    if let (&Place::Local(l), &mir::ProjectionElem::Field(field, _)) =
+                        (&proj.base, &proj.elem)
+                    {
+                        if l == local && assigned_fields.insert(field) {
+                            first_write = Location { block: location.block, statement_index };
+                        }
+                    }
+                }
+                mir::StatementKind::StorageLive(l) if l == local => break,
+                _ => {}
+            }
+            if assigned_fields.len() == num_fields {
+                return Some((local, first_write));
+            }
+        }
+
+        None
+    }
+
+    /// Kills the borrows of data stored in `local`, which has just
+    /// been completely overwritten at `location` by writes starting
+    /// at `first_write`. Borrows that go through a dereference of
+    /// `local` are left alone, since the referent is not affected by
+    /// overwriting the pointer itself, and so are borrows taken in
+    /// between the writes, which may refer to the new value.
+    fn kill_borrows_on_local(&self,
+                             sets: &mut BlockSets<ReserveOrActivateIndex>,
+                             local: mir::Local,
+                             first_write: Location,
+                             location: Location,
+                             is_activations: bool) {
+        if let Some(borrow_indexes) = self.borrow_set.local_map.get(&local) {
+            let borrow_indexes = borrow_indexes.iter().filter(|&&b| {
+                let borrow = &self.borrow_set.borrows[b];
+                let reserve_location = borrow.reserve_location;
+                let taken_between_writes = reserve_location.block == location.block &&
+                    reserve_location.statement_index >= first_write.statement_index &&
+                    reserve_location.statement_index < location.statement_index;
+                !taken_between_writes && !place_contains_deref(&borrow.borrowed_place)
+            });
+            for &b in borrow_indexes {
+                sets.kill(&ReserveOrActivateIndex::reserved(b));
+                if is_activations {
+                    sets.kill(&ReserveOrActivateIndex::active(b));
+                }
+            }
+        }
+    }
+
+    /// Checks that the activations just computed by `FindPlaceUses`
+    /// at `location` agree with the precomputed `activation_map` of
+    /// the `BorrowSet`, restricted to the borrows reserved on entry.
+    ///
+    /// This is a transitional sanity check, which can go away once
+    /// the `ActiveBorrows` analysis reads its gen-bits from the
+    /// activation map directly.
+    fn check_activation_map(&self,
+                            sets: &BlockSets<ReserveOrActivateIndex>,
+                            location: Location,
+                            activated: &FxHashSet<BorrowIndex>) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        let expected: FxHashSet<BorrowIndex> = self.borrow_set
+            .activations_at_location(location)
+            .filter(|b| sets.on_entry.contains(&ReserveOrActivateIndex::reserved(*b)))
+            .collect();
+        assert_eq!(&expected, activated,
+                   "activation map disagrees with `FindPlaceUses` at {:?}", location);
+    }
+
     /// Models statement effect in Reservations and ActiveBorrows flow
     /// analyses; `is activations` tells us if we are in the latter
     /// case.
@@ -341,14 +308,20 @@ impl<'a, 'gcx, 'tcx> Borrows<'a, 'gcx, 'tcx> {
             //
             // Now compute the activations generated by uses within
             // the statement based on that reservation state.
-            let mut find = FindPlaceUses { sets, assigned_map: &self.assigned_map };
-            find.visit_statement(location.block, stmt, location);
+            let activated = {
+                let mut find = FindPlaceUses::new(sets, &self.borrow_set.assigned_map);
+                find.visit_statement(location.block, stmt, location);
+                find.activated
+            };
+            self.check_activation_map(sets, location, &activated);
         }
 
         match stmt.kind {
             // EndRegion kills any borrows (reservations and active borrows both)
             mir::StatementKind::EndRegion(region_scope) => {
-                if let Some(borrow_indexes) = self.region_map.get(&ReScope(region_scope)) {
+                if let Some(borrow_indexes) =
+                    self.borrow_set.region_map.get(&ReScope(region_scope))
+                {
                     assert!(self.nonlexical_regioncx.is_none());
                     for idx in borrow_indexes {
                         sets.kill(&ReserveOrActivateIndex::reserved(*idx));
@@ -361,11 +334,18 @@ impl<'a, 'gcx, 'tcx> Borrows<'a, 'gcx, 'tcx> {
                 }
             }
 
-            mir::StatementKind::Assign(_, ref rhs) => {
-                // NOTE: if/when the Assign case is revised to inspect
-                // the assigned_place here, make sure to also
-                // re-consider the current implementations of the
-                // propagate_call_return method.
+            mir::StatementKind::Assign(ref lhs, ref rhs) => {
+                // NOTE: the Assign case inspects the assigned place to
+                // kill borrows of a local that is fully overwritten;
+                // the propagate_call_return method does not (yet) do
+                // the same for call destinations.
+
+                // Do the kills before any gen below, so that an
+                // assignment like `x = &x.0` keeps its own borrow.
+                if let Some((local, first_write)) = self.overwritten_local(lhs, location) {
+                    self.kill_borrows_on_local(sets, local, first_write, location,
+                                               is_activations);
+                }
 
                 if let mir::Rvalue::Ref(region, _, ref place) = *rhs {
                     if is_unsafe_place(self.tcx, self.mir, place) { return; }
@@ -375,10 +355,10 @@ impl<'a, 'gcx, 'tcx> Borrows<'a, 'gcx, 'tcx> {
                         return
                     }
 
-                    let index = self.location_map.get(&location).unwrap_or_else(|| {
+                    let index = self.borrow_set.location_map.get(&location).unwrap_or_else(|| {
                         panic!("could not find BorrowIndex for location {:?}", location);
                     });
-                    assert!(self.region_map.get(region).unwrap_or_else(|| {
+                    assert!(self.borrow_set.region_map.get(region).unwrap_or_else(|| {
                         panic!("could not find BorrowIndexs for region {:?}", region);
                     }).contains(&index));
                     sets.gen(&ReserveOrActivateIndex::reserved(*index));
@@ -387,10 +367,9 @@ impl<'a, 'gcx, 'tcx> Borrows<'a, 'gcx, 'tcx> {
 
             mir::StatementKind::StorageDead(local) => {
                 // Make sure there are no remaining borrows for locals that
-                // are gone out of scope.
-                //
-                // FIXME: expand this to variables that are assigned over.
-                if let Some(borrow_indexes) = self.local_map.get(&local) {
+                // are gone out of scope. (Locals that are assigned over
+                // are handled in the Assign case above.)
+                if let Some(borrow_indexes) = self.borrow_set.local_map.get(&local) {
                     sets.kill_all(borrow_indexes.iter()
                                   .map(|b| ReserveOrActivateIndex::reserved(*b)));
                     if is_activations {
@@ -402,6 +381,7 @@ impl<'a, 'gcx, 'tcx> Borrows<'a, 'gcx, 'tcx> {
 
             mir::StatementKind::InlineAsm { .. } |
             mir::StatementKind::SetDiscriminant { .. } |
+            mir::StatementKind::FakeRead(..) |
             mir::StatementKind::StorageLive(..) |
             mir::StatementKind::Validate(..) |
             mir::StatementKind::Nop => {}
@@ -433,8 +413,12 @@ impl<'a, 'gcx, 'tcx> Borrows<'a, 'gcx, 'tcx> {
             //
             // Now compute effect of the terminator on the activations
             // themselves in the ActiveBorrows state.
-            let mut find = FindPlaceUses { sets, assigned_map: &self.assigned_map };
-            find.visit_terminator(location.block, term, location);
+            let activated = {
+                let mut find = FindPlaceUses::new(sets, &self.borrow_set.assigned_map);
+                find.visit_terminator(location.block, term, location);
+                find.activated
+            };
+            self.check_activation_map(sets, location, &activated);
         }
 
         match term.kind {
@@ -447,7 +431,7 @@ impl<'a, 'gcx, 'tcx> Borrows<'a, 'gcx, 'tcx> {
                 // and hence most of these loans will already be dead -- but, in some cases
                 // like unwind paths, we do not always emit `EndRegion` statements, so we
                 // add some kills here as a "backup" and to avoid spurious error messages.
-                for (borrow_index, borrow_data) in self.borrows.iter_enumerated() {
+                for (borrow_index, borrow_data) in self.borrow_set.borrows.iter_enumerated() {
                     if let ReScope(scope) = borrow_data.region {
                         // Check that the scope is not actually a scope from a function that is
                         // a parent of our closure. Note that the CallSite scope itself is
@@ -479,8 +463,8 @@ impl<'a, 'gcx, 'tcx> Borrows<'a, 'gcx, 'tcx> {
 }
 
 impl<'a, 'gcx, 'tcx> ActiveBorrows<'a, 'gcx, 'tcx> {
-    pub(crate) fn borrows(&self) -> &IndexVec<BorrowIndex, BorrowData<'tcx>> {
-        self.0.borrows()
+    pub(crate) fn borrow_set(&self) -> &'a BorrowSet<'tcx> {
+        self.0.borrow_set()
     }
 
     /// Returns the span for the "end point" given region. This will
@@ -491,13 +475,231 @@ impl<'a, 'gcx, 'tcx> ActiveBorrows<'a, 'gcx, 'tcx> {
         match self.0.nonlexical_regioncx {
             Some(_) => None,
             None => {
-                match self.0.region_span_map.get(region) {
+                match self.0.borrow_set.region_span_map.get(region) {
                     Some(span) => Some(span.end_point()),
                     None => Some(self.0.mir.span.end_point())
                 }
             }
         }
     }
+
+    /// Under `-Z nll-dump-cause`, explains why the universal regions
+    /// that `borrow` has to outlive are live in the body. Does
+    /// nothing unless NLL is enabled.
+    pub(crate) fn explain_universal_liveness(&self,
+                                             borrow: &BorrowData<'tcx>,
+                                             err: &mut DiagnosticBuilder) {
+        if let Some(ref regioncx) = self.0.nonlexical_regioncx {
+            let borrow_region = borrow.region.to_region_vid();
+            regioncx.explain_universal_regions_in(self.0.tcx, self.0.mir, borrow_region, err);
+        }
+    }
+
+    /// Returns the span of an explicit `drop(x)` call, where `x` is
+    /// the local holding `borrow`, if that call is the last point the
+    /// borrow reaches. This will return `None` unless NLL is enabled,
+    /// since lexical borrows are not shortened by an earlier `drop`
+    /// anyway.
+    pub(crate) fn opt_explicit_drop_span(&self, borrow: &BorrowData<'tcx>) -> Option<Span> {
+        let regioncx = self.0.nonlexical_regioncx.as_ref()?;
+        let owner = self.borrow_owner(borrow)?;
+        let borrow_region = borrow.region.to_region_vid();
+        let tcx = self.0.tcx;
+
+        for (block, data) in self.0.mir.basic_blocks().iter_enumerated() {
+            let terminator = data.terminator();
+            let (func, args, destination) = match terminator.kind {
+                mir::TerminatorKind::Call { ref func, ref args, ref destination, .. } => {
+                    (func, args, destination)
+                }
+                _ => continue,
+            };
+
+            let location = Location { block, statement_index: data.statements.len() };
+            if !regioncx.region_contains_point(borrow_region, location) {
+                continue;
+            }
+
+            // If the borrow is still live once the call returns, then
+            // the `drop` is not what ends it.
+            if let Some((_, target)) = *destination {
+                if regioncx.region_contains_point(borrow_region, target.start_location()) {
+                    continue;
+                }
+            }
+
+            if args.len() == 1 && is_mem_drop(tcx, func) &&
+                operand_reads_local(data, &args[0], owner)
+            {
+                return Some(terminator.source_info.span);
+            }
+        }
+
+        None
+    }
+
+    /// Returns the span of the `?` token of an `expr?`, where `expr`
+    /// creates `borrow`, if the borrow is still live when that `?`
+    /// returns early: the error being returned then holds on to the
+    /// borrow. This will return `None` unless NLL is enabled.
+    pub(crate) fn opt_question_mark_return_span(&self,
+                                                borrow: &BorrowData<'tcx>)
+                                                -> Option<Span> {
+        let regioncx = self.0.nonlexical_regioncx.as_ref()?;
+        let borrow_region = borrow.region.to_region_vid();
+        let borrow_span = self.0.mir.source_info(borrow.reserve_location).span;
+        let mir = self.0.mir;
+
+        for (block, data) in mir.basic_blocks().iter_enumerated() {
+            let try_expr_span = match try_desugar_call(self.0.tcx, mir, block) {
+                Some((TryDesugarCall::FromError, span)) => span,
+                _ => continue,
+            };
+
+            let location = Location { block, statement_index: data.statements.len() };
+            if try_expr_span.contains(borrow_span) &&
+                regioncx.region_contains_point(borrow_region, location)
+            {
+                return Some(question_mark_span(try_expr_span));
+            }
+        }
+
+        None
+    }
+
+    /// Starting from `location`, which must be contained in the region
+    /// of `borrow`, follows the straight-line path of statements
+    /// (continuing past a `Goto` or the return of a `Call`) to the
+    /// statement where the region of `borrow` ends, i.e. its last use
+    /// on that path. Returns `None` if NLL is not enabled, or if the
+    /// region ends at a terminator or where the path branches.
+    pub(crate) fn opt_last_use_after(&self,
+                                     borrow: &BorrowData<'tcx>,
+                                     location: Location)
+                                     -> Option<Location> {
+        let regioncx = self.0.nonlexical_regioncx.as_ref()?;
+        let borrow_region = borrow.region.to_region_vid();
+        let mir = self.0.mir;
+
+        let mut visited = FxHashSet();
+        visited.insert(location.block);
+
+        let mut location = location;
+        loop {
+            let data = &mir[location.block];
+            let next = if location.statement_index < data.statements.len() {
+                location.successor_within_block()
+            } else {
+                match data.terminator().kind {
+                    mir::TerminatorKind::Goto { target } |
+                    mir::TerminatorKind::Call { destination: Some((_, target)), .. } => {
+                        // Give up on loops.
+                        if !visited.insert(target) {
+                            return None;
+                        }
+                        target.start_location()
+                    }
+                    _ => return None,
+                }
+            };
+
+            if !regioncx.region_contains_point(borrow_region, next) {
+                if location.statement_index < data.statements.len() {
+                    return Some(location);
+                } else {
+                    return None;
+                }
+            }
+            location = next;
+        }
+    }
+
+    /// Returns the local that ends up holding `borrow`. The borrow is
+    /// first assigned to some place, often a temporary, which may
+    /// then be moved (possibly as part of an aggregate, as in
+    /// `let guard = Guard(&mut x)`) into another local, or into a
+    /// field of one (as in `a.0 = Some(&x)`), in the same block; we
+    /// follow such moves.
+    pub(crate) fn borrow_owner(&self, borrow: &BorrowData<'tcx>) -> Option<mir::Local> {
+        let mut owner = match borrow.assigned_place {
+            Place::Local(local) => local,
+            _ => return None,
+        };
+
+        let location = borrow.reserve_location;
+        let statements = &self.0.mir[location.block].statements;
+        for statement in &statements[location.statement_index + 1..] {
+            if let mir::StatementKind::Assign(ref dest, ref rvalue) = statement.kind {
+                let dest = match owning_local(dest) {
+                    Some(dest) => dest,
+                    None => continue,
+                };
+                let moves_owner = move |operand: &mir::Operand| match *operand {
+                    mir::Operand::Move(Place::Local(local)) => local == owner,
+                    _ => false,
+                };
+                let moved = match *rvalue {
+                    mir::Rvalue::Use(ref operand) => moves_owner(operand),
+                    mir::Rvalue::Aggregate(_, ref operands) => operands.iter().any(moves_owner),
+                    _ => false,
+                };
+                if moved {
+                    owner = dest;
+                }
+            }
+        }
+
+        Some(owner)
+    }
+}
+
+/// Returns the local that owns the memory of `place`, i.e. its base
+/// local if no projection dereferences a pointer.
+fn owning_local(place: &Place) -> Option<mir::Local> {
+    match *place {
+        Place::Local(local) => Some(local),
+        Place::Static(_) => None,
+        Place::Projection(ref proj) => match proj.elem {
+            mir::ProjectionElem::Deref => None,
+            _ => owning_local(&proj.base),
+        },
+    }
+}
+
+/// Returns whether `func` is the function `std::mem::drop`.
+fn is_mem_drop(tcx: TyCtxt, func: &mir::Operand) -> bool {
+    if let mir::Operand::Constant(ref constant) = *func {
+        if let ty::TyFnDef(def_id, _) = constant.ty.sty {
+            return tcx.absolute_item_path_str(def_id) == "core::mem::drop";
+        }
+    }
+    false
+}
+
+/// Returns whether the call argument `operand` in `data` reads from
+/// `local`, either directly or through the temporary it was moved or
+/// copied into.
+fn operand_reads_local(data: &mir::BasicBlockData, operand: &mir::Operand, local: mir::Local)
+                       -> bool {
+    let arg = match *operand {
+        mir::Operand::Move(Place::Local(arg)) |
+        mir::Operand::Copy(Place::Local(arg)) => arg,
+        _ => return false,
+    };
+    if arg == local {
+        return true;
+    }
+
+    data.statements.iter().rev().any(|statement| match statement.kind {
+        mir::StatementKind::Assign(Place::Local(dest), mir::Rvalue::Use(ref source)) => {
+            dest == arg && match *source {
+                mir::Operand::Move(Place::Local(source)) |
+                mir::Operand::Copy(Place::Local(source)) => source == local,
+                _ => false,
+            }
+        }
+        _ => false,
+    })
 }
 
 /// `FindPlaceUses` is a MIR visitor that updates `self.sets` for all
@@ -512,46 +714,26 @@ impl<'a, 'gcx, 'tcx> ActiveBorrows<'a, 'gcx, 'tcx> {
 ///
 /// This struct will seek out all places that are assignment-targets
 /// for borrows (gathered in `self.assigned_map`; see also the
-/// `assigned_map` in `struct Borrows`), and set the corresponding
+/// `assigned_map` in `struct BorrowSet`), and set the corresponding
 /// gen-bits for activations of those borrows in `self.sets`
 struct FindPlaceUses<'a, 'b: 'a, 'tcx: 'a> {
     assigned_map: &'a FxHashMap<Place<'tcx>, FxHashSet<BorrowIndex>>,
     sets: &'a mut BlockSets<'b, ReserveOrActivateIndex>,
+
+    /// The borrows whose activation gen-bit was set, recorded for
+    /// `Borrows::check_activation_map`.
+    activated: FxHashSet<BorrowIndex>,
 }
 
 impl<'a, 'b, 'tcx> FindPlaceUses<'a, 'b, 'tcx> {
-    fn has_been_reserved(&self, b: &BorrowIndex) -> bool {
-        self.sets.on_entry.contains(&ReserveOrActivateIndex::reserved(*b))
+    fn new(sets: &'a mut BlockSets<'b, ReserveOrActivateIndex>,
+           assigned_map: &'a FxHashMap<Place<'tcx>, FxHashSet<BorrowIndex>>)
+           -> Self {
+        FindPlaceUses { assigned_map, sets, activated: FxHashSet() }
     }
 
-    /// return whether `context` should be considered a "use" of a
-    /// place found in that context. "Uses" activate associated
-    /// borrows (at least when such uses occur while the borrow also
-    /// has a reservation at the time).
-    fn is_potential_use(context: PlaceContext) -> bool {
-        match context {
-            // storage effects on an place do not activate it
-            PlaceContext::StorageLive | PlaceContext::StorageDead => false,
-
-            // validation effects do not activate an place
-            //
-            // FIXME: Should they? Is it just another read? Or can we
-            // guarantee it won't dereference the stored address? How
-            // "deep" does validation go?
-            PlaceContext::Validate => false,
-
-            // pure overwrites of an place do not activate it. (note
-            // PlaceContext::Call is solely about dest place)
-            PlaceContext::Store | PlaceContext::Call => false,
-
-            // reads of an place *do* activate it
-            PlaceContext::Move |
-            PlaceContext::Copy |
-            PlaceContext::Drop |
-            PlaceContext::Inspect |
-            PlaceContext::Borrow { .. } |
-            PlaceContext::Projection(..) => true,
-        }
+    fn has_been_reserved(&self, b: &BorrowIndex) -> bool {
+        self.sets.on_entry.contains(&ReserveOrActivateIndex::reserved(*b))
     }
 }
 
@@ -563,7 +745,7 @@ impl<'a, 'b, 'tcx> Visitor<'tcx> for FindPlaceUses<'a, 'b, 'tcx> {
         debug!("FindPlaceUses place: {:?} assigned from borrows: {:?} \
                 used in context: {:?} at location: {:?}",
                place, self.assigned_map.get(place), context, location);
-        if Self::is_potential_use(context) {
+        if is_potential_use(context) {
             if let Some(borrows) = self.assigned_map.get(place) {
                 for borrow_idx in borrows {
                     debug!("checking if index {:?} for {:?} is reserved ({}) \
@@ -571,6 +753,7 @@ impl<'a, 'b, 'tcx> Visitor<'tcx> for FindPlaceUses<'a, 'b, 'tcx> {
                            borrow_idx, place, self.has_been_reserved(&borrow_idx), self.sets);
                     if self.has_been_reserved(&borrow_idx) {
                         self.sets.gen(&ReserveOrActivateIndex::active(*borrow_idx));
+                        self.activated.insert(*borrow_idx);
                     } else {
                         // (This can certainly happen in valid code. I
                         // just want to know about it in the short
@@ -592,7 +775,7 @@ impl<'a, 'gcx, 'tcx> BitDenotation for Reservations<'a, 'gcx, 'tcx> {
     type Idx = ReserveOrActivateIndex;
     fn name() -> &'static str { "reservations" }
     fn bits_per_block(&self) -> usize {
-        self.0.borrows.len() * 2
+        self.0.borrow_set.borrows.len() * 2
     }
     fn start_block_effect(&self, _entry_set: &mut IdxSet<ReserveOrActivateIndex>)  {
         // no borrows of code region_scopes have been taken prior to
@@ -638,7 +821,7 @@ impl<'a, 'gcx, 'tcx> BitDenotation for ActiveBorrows<'a, 'gcx, 'tcx> {
     fn accumulates_intrablock_state() -> bool { true }
 
     fn bits_per_block(&self) -> usize {
-        self.0.borrows.len() * 2
+        self.0.borrow_set.borrows.len() * 2
     }
 
     fn start_block_effect(&self, _entry_sets: &mut IdxSet<ReserveOrActivateIndex>)  {
@@ -695,34 +878,12 @@ impl<'a, 'gcx, 'tcx> InitialFlow for Reservations<'a, 'gcx, 'tcx> {
     }
 }
 
-fn is_unsafe_place<'a, 'gcx: 'tcx, 'tcx: 'a>(
-    tcx: TyCtxt<'a, 'gcx, 'tcx>,
-    mir: &'a Mir<'tcx>,
-    place: &mir::Place<'tcx>
-) -> bool {
-    use self::mir::Place::*;
-    use self::mir::ProjectionElem;
-
+fn place_contains_deref(place: &mir::Place<'_>) -> bool {
     match *place {
-        Local(_) => false,
-        Static(ref static_) => tcx.is_static_mut(static_.def_id),
-        Projection(ref proj) => {
-            match proj.elem {
-                ProjectionElem::Field(..) |
-                ProjectionElem::Downcast(..) |
-                ProjectionElem::Subslice { .. } |
-                ProjectionElem::ConstantIndex { .. } |
-                ProjectionElem::Index(_) => {
-                    is_unsafe_place(tcx, mir, &proj.base)
-                }
-                ProjectionElem::Deref => {
-                    let ty = proj.base.ty(mir, tcx).to_ty(tcx);
-                    match ty.sty {
-                        ty::TyRawPtr(..) => true,
-                        _ => is_unsafe_place(tcx, mir, &proj.base),
-                    }
-                }
-            }
-        }
+        mir::Place::Local(_) | mir::Place::Static(_) => false,
+        mir::Place::Projection(ref proj) => match proj.elem {
+            mir::ProjectionElem::Deref => true,
+            _ => place_contains_deref(&proj.base),
+        },
     }
 }
@@ -110,7 +110,7 @@ impl<'b, 'a, 'gcx, 'tcx> Gatherer<'b, 'a, 'gcx, 'tcx> {
             Place::Local(local) => Ok(self.builder.data.rev_lookup.locals[local]),
             Place::Static(..) => {
                 let span = self.builder.mir.source_info(self.loc).span;
-                Err(MoveError::cannot_move_out_of(span, Static))
+                Err(MoveError::cannot_move_out_of(span, place, Static))
             }
             Place::Projection(ref proj) => {
                 self.move_path_for_projection(place, proj)
@@ -129,16 +129,30 @@ impl<'b, 'a, 'gcx, 'tcx> Gatherer<'b, 'a, 'gcx, 'tcx> {
                                 proj: &PlaceProjection<'tcx>)
                                 -> Result<MovePathIndex, MoveError<'tcx>>
     {
-        let base = try!(self.move_path_for(&proj.base));
         let mir = self.builder.mir;
         let tcx = self.builder.tcx;
         let place_ty = proj.base.ty(mir, tcx).to_ty(tcx);
+
+        // Indexing a slice is an error whether or not the slice itself
+        // may be moved out of. Check it first, so that `s[i]` for a
+        // borrowed slice `s` is reported as a move out of the slice,
+        // not as a move out of the borrowed content `*s`.
+        if let (&ty::TySlice(_), &ProjectionElem::Index(..)) = (&place_ty.sty, &proj.elem) {
+            return Err(MoveError::cannot_move_out_of(
+                mir.source_info(self.loc).span,
+                place,
+                InteriorOfSliceOrArray { ty: place_ty, is_index: true }));
+        }
+
+        let base = try!(self.move_path_for(&proj.base));
         match place_ty.sty {
             ty::TyRef(..) | ty::TyRawPtr(..) =>
                 return Err(MoveError::cannot_move_out_of(mir.source_info(self.loc).span,
+                                                         place,
                                                          BorrowedContent)),
             ty::TyAdt(adt, _) if adt.has_dtor(tcx) && !adt.is_box() =>
                 return Err(MoveError::cannot_move_out_of(mir.source_info(self.loc).span,
+                                                         place,
                                                          InteriorOfTypeWithDestructor {
                     container_ty: place_ty
                 })),
@@ -148,6 +162,7 @@ impl<'b, 'a, 'gcx, 'tcx> Gatherer<'b, 'a, 'gcx, 'tcx> {
             ty::TySlice(_) =>
                 return Err(MoveError::cannot_move_out_of(
                     mir.source_info(self.loc).span,
+                    place,
                     InteriorOfSliceOrArray {
                         ty: place_ty, is_index: match proj.elem {
                             ProjectionElem::Index(..) => true,
@@ -158,6 +173,7 @@ impl<'b, 'a, 'gcx, 'tcx> Gatherer<'b, 'a, 'gcx, 'tcx> {
                 ProjectionElem::Index(..) =>
                     return Err(MoveError::cannot_move_out_of(
                         mir.source_info(self.loc).span,
+                        place,
                         InteriorOfSliceOrArray {
                             ty: place_ty, is_index: true
                         })),
@@ -287,6 +303,7 @@ impl<'b, 'a, 'gcx, 'tcx> Gatherer<'b, 'a, 'gcx, 'tcx> {
                           "SetDiscriminant should not exist during borrowck");
             }
             StatementKind::InlineAsm { .. } |
+            StatementKind::FakeRead(..) |
             StatementKind::EndRegion(_) |
             StatementKind::Validate(..) |
             StatementKind::Nop => {}
@@ -348,6 +365,8 @@ impl<'b, 'a, 'gcx, 'tcx> Gatherer<'b, 'a, 'gcx, 'tcx> {
             }
 
             TerminatorKind::Yield { ref value, .. } => {
+                // The resume place is initialized by the assignment
+                // that starts the `resume` block, not by the terminator.
                 self.gather_operand(value);
             }
 
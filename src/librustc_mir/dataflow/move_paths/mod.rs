@@ -275,6 +275,9 @@ impl<'tcx> MovePathLookup<'tcx> {
 #[derive(Debug)]
 pub struct IllegalMoveOrigin<'tcx> {
     pub(crate) span: Span,
+    /// The place that we may not move out of: for example, the
+    /// `*x` in a move of `(*x).f`.
+    pub(crate) place: Place<'tcx>,
     pub(crate) kind: IllegalMoveOriginKind<'tcx>,
 }
 
@@ -293,8 +296,11 @@ pub enum MoveError<'tcx> {
 }
 
 impl<'tcx> MoveError<'tcx> {
-    fn cannot_move_out_of(span: Span, kind: IllegalMoveOriginKind<'tcx>) -> Self {
-        let origin = IllegalMoveOrigin { span, kind };
+    fn cannot_move_out_of(span: Span,
+                          place: &Place<'tcx>,
+                          kind: IllegalMoveOriginKind<'tcx>)
+                          -> Self {
+        let origin = IllegalMoveOrigin { span, place: place.clone(), kind };
         MoveError::IllegalMove { cannot_move_out_of: origin }
     }
 }
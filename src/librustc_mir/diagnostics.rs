@@ -1768,6 +1768,153 @@ b.resume();
 ```
 "##,
 
+E0689: r##"
+A function body requires one of its lifetime parameters to outlive another,
+but the function signature does not guarantee that relationship. This error
+is reported by the MIR borrow checker (`-Z nll`).
+
+Erroneous code example:
+
+```ignore (requires-nll)
+fn foo<'a, 'b>(x: &'a u32) -> &'b u32 {
+    x // error: free region `'a` does not outlive free region `'b`
+}
+```
+
+The returned reference is only known to be valid for `'a`, but the signature
+promises it is valid for `'b`. Nothing in the signature says that `'a` lives
+at least as long as `'b`, so the body cannot be accepted.
+
+When a closure body relates two regions that the closure's creator cannot
+prove to be related, the error is reported at the creator as E0690 instead.
+When data borrowed inside a closure body must outlive the closure itself, it
+is reported as E0692.
+
+To fix this error, either declare the relationship in the signature:
+
+```
+fn foo<'a: 'b, 'b>(x: &'a u32) -> &'b u32 {
+    x // ok: `'a` outlives `'b`
+}
+```
+
+or change the body so that it no longer needs the relationship.
+"##,
+
+E0690: r##"
+A closure body requires a relationship between two lifetimes that does not
+hold at the place where the closure is created. This error is reported by the
+MIR borrow checker (`-Z nll`) when it checks the requirements that a closure
+body imposes on its creator.
+
+Erroneous code example:
+
+```ignore (requires-nll)
+use std::cell::Cell;
+
+fn foo<'a, 'b>(x: Cell<&'a u32>, y: Cell<&'b u32>) {
+    let f = || { // error: free region `'a` does not outlive free region `'b`
+        y.set(x.get()); // requires that `'a` outlive `'b`
+    };
+    f();
+}
+```
+
+Storing `x.get()` into `y` is only valid if `'a` outlives `'b`, but the
+signature of `foo` says nothing about how the two lifetimes relate. The
+closure body is fine on its own; the error is reported where the closure is
+created, with a label pointing at the statement inside the closure body that
+introduced the requirement.
+
+To fix this error, declare the relationship in the creator's signature:
+
+```
+use std::cell::Cell;
+
+fn foo<'a: 'b, 'b>(x: Cell<&'a u32>, y: Cell<&'b u32>) {
+    let f = || {
+        y.set(x.get()); // ok: `'a` outlives `'b`
+    };
+    f();
+}
+```
+"##,
+
+E0691: r##"
+The hidden type of an `impl Trait` return type captures a lifetime that does
+not appear in the bounds of the `impl Trait`. This error is reported by the
+MIR borrow checker (`-Z nll`).
+
+Erroneous code example:
+
+```ignore (requires-nll)
+#![feature(conservative_impl_trait)]
+
+use std::fmt::Debug;
+
+fn foo<'a>(x: &'a u32, y: &u32) -> impl Debug + 'a {
+    (x, y) // error: hidden type for `impl Trait` captures lifetime that does
+           //        not appear in bounds
+}
+```
+
+Callers of `foo` only know that the returned value is valid for `'a`, but the
+returned tuple also holds on to `y`, whose lifetime is unrelated to `'a`.
+
+To fix this error, make sure every lifetime the returned value holds on to
+outlives the bound of the `impl Trait`:
+
+```
+#![feature(conservative_impl_trait)]
+
+use std::fmt::Debug;
+
+fn foo<'a>(x: &'a u32, y: &'a u32) -> impl Debug + 'a {
+    (x, y) // ok: both references are valid for `'a`
+}
+```
+"##,
+
+E0692: r##"
+Data borrowed within a closure body has to outlive a lifetime that the
+closure's creator or signature imposes, so the borrow would escape the
+closure. This error is reported by the MIR borrow checker (`-Z nll`).
+
+Erroneous code example:
+
+```ignore (requires-nll)
+use std::cell::Cell;
+
+fn foo<F>(f: F) where F: for<'x> FnOnce(Cell<&'x u32>) {}
+
+fn bar(cell: Cell<&'static u32>) {
+    foo(|x| {
+        cell.set(x.get()); // error: borrowed data escapes the closure
+    });
+}
+```
+
+The closure only knows that `x` holds a reference valid for some lifetime
+`'x` that its caller picks, but storing that reference into `cell` requires
+it to be valid for `'static`. The reference would outlive the call of the
+closure.
+
+To fix this error, only store data that is known to live long enough, for
+example by making the closure's argument type say so:
+
+```
+use std::cell::Cell;
+
+fn foo<F>(f: F) where F: FnOnce(Cell<&'static u32>) {}
+
+fn bar(cell: Cell<&'static u32>) {
+    foo(|x| {
+        cell.set(x.get()); // ok: `x` holds a `&'static u32`
+    });
+}
+```
+"##,
+
 }
 
 register_diagnostics! {
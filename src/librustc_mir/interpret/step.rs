@@ -133,6 +133,9 @@ impl<'a, 'tcx, M: Machine<'tcx>> EvalContext<'a, 'tcx, M> {
                 M::end_region(self, Some(ce))?;
             }
 
+            // Only there for borrowck.
+            FakeRead(..) => {}
+
             // Defined to do nothing. These are added by optimization passes, to avoid changing the
             // size of MIR constantly.
             Nop => {}
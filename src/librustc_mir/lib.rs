@@ -68,6 +68,8 @@ pub mod interpret;
 
 use rustc::ty::maps::Providers;
 
+pub use borrow_check::check_crate as mir_borrowck_crate;
+
 pub fn provide(providers: &mut Providers) {
     borrow_check::provide(providers);
     shim::provide(providers);
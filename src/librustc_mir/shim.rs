@@ -27,6 +27,7 @@ use syntax_pos::Span;
 use std::fmt;
 use std::iter;
 
+use borrow_check;
 use transform::{add_moves_for_packed_drops, add_call_guards};
 use transform::{no_landing_pads, simplify};
 use util::elaborate_drops::{self, DropElaborator, DropStyle, DropFlagMode};
@@ -115,6 +116,9 @@ fn make_shim<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
         }
     };
     debug!("make_shim({:?}) = untransformed {:?}", instance, result);
+    if tcx.sess.opts.debugging_opts.nll && tcx.sess.opts.debugging_opts.nll_shims {
+        borrow_check::check_shim(tcx, instance, &result);
+    }
     add_moves_for_packed_drops::add_moves_for_packed_drops(
         tcx, &mut result, instance.def_id());
     no_landing_pads::no_landing_pads(tcx, &mut result);
@@ -110,6 +110,12 @@ impl<'a, 'tcx> Visitor<'tcx> for UnsafetyChecker<'a, 'tcx> {
             StatementKind::InlineAsm { .. } => {
                 self.require_unsafe("use of inline assembly")
             },
+
+            StatementKind::FakeRead(..) => {
+                // The place is really used elsewhere, and that use
+                // is what gets checked.
+                return;
+            }
         }
         self.super_statement(block, statement, location);
     }
@@ -0,0 +1,44 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This module provides one pass, `CleanFakeReads`, that removes all
+//! `FakeRead` statements from the MIR. They only exist for the sake
+//! of the borrow checker, so they are removed as soon as it has run,
+//! and no later pass (nor trans) ever sees them.
+
+use rustc::mir::{BasicBlock, Location, Mir, Statement, StatementKind};
+use rustc::mir::visit::MutVisitor;
+use rustc::ty::TyCtxt;
+use transform::{MirPass, MirSource};
+
+pub struct CleanFakeReads;
+
+struct DeleteFakeReads;
+
+impl MirPass for CleanFakeReads {
+    fn run_pass<'a, 'tcx>(&self,
+                          _tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                          _source: MirSource,
+                          mir: &mut Mir<'tcx>) {
+        DeleteFakeReads.visit_mir(mir);
+    }
+}
+
+impl<'tcx> MutVisitor<'tcx> for DeleteFakeReads {
+    fn visit_statement(&mut self,
+                       block: BasicBlock,
+                       statement: &mut Statement<'tcx>,
+                       location: Location) {
+        if let StatementKind::FakeRead(..) = statement.kind {
+            statement.kind = StatementKind::Nop;
+        }
+        self.super_statement(block, statement, location);
+    }
+}
@@ -25,6 +25,7 @@ use syntax_pos::Span;
 pub mod add_validation;
 pub mod add_moves_for_packed_drops;
 pub mod clean_end_regions;
+pub mod clean_fake_reads;
 pub mod check_unsafety;
 pub mod simplify_branches;
 pub mod simplify;
@@ -227,6 +228,7 @@ fn optimized_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> &'tcx
     let mut mir = tcx.mir_validated(def_id).steal();
     run_passes![tcx, mir, def_id, 2;
         // Remove all things not needed by analysis
+        clean_fake_reads::CleanFakeReads,
         no_landing_pads::NoLandingPads,
         simplify_branches::SimplifyBranches::new("initial"),
         remove_noop_landing_pads::RemoveNoopLandingPads,
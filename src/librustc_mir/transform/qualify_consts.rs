@@ -921,6 +921,7 @@ impl<'a, 'tcx> Visitor<'tcx> for Qualifier<'a, 'tcx, 'tcx> {
                 StatementKind::StorageLive(_) |
                 StatementKind::StorageDead(_) |
                 StatementKind::InlineAsm {..} |
+                StatementKind::FakeRead(..) |
                 StatementKind::EndRegion(_) |
                 StatementKind::Validate(..) |
                 StatementKind::Nop => {}
@@ -43,10 +43,12 @@ impl RemoveNoopLandingPads {
                 StatementKind::StorageLive(_) |
                 StatementKind::StorageDead(_) |
                 StatementKind::EndRegion(_) |
+                StatementKind::FakeRead(..) |
                 StatementKind::Nop => {
                     // These are all nops in a landing pad (there's some
-                    // borrowck interaction between EndRegion and storage
-                    // instructions, but this should all run after borrowck).
+                    // borrowck interaction between EndRegion, FakeRead and
+                    // storage instructions, but this should all run after
+                    // borrowck).
                 }
 
                 StatementKind::Assign(Place::Local(_), Rvalue::Use(_)) => {
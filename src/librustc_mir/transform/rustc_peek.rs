@@ -19,6 +19,8 @@ use rustc_data_structures::indexed_vec::Idx;
 use transform::{MirPass, MirSource};
 
 use dataflow::{do_dataflow, DebugFormatted};
+use dataflow::{ActiveBorrows, FlowAtLocation, FlowsAtLocation};
+use dataflow::indexes::BorrowIndex;
 use dataflow::MoveDataParamEnv;
 use dataflow::BitDenotation;
 use dataflow::DataflowResults;
@@ -161,6 +163,7 @@ fn each_block<'a, 'tcx, O>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
             mir::StatementKind::StorageLive(_) |
             mir::StatementKind::StorageDead(_) |
             mir::StatementKind::InlineAsm { .. } |
+            mir::StatementKind::FakeRead(..) |
             mir::StatementKind::EndRegion(_) |
             mir::StatementKind::Validate(..) |
             mir::StatementKind::Nop => continue,
@@ -214,9 +217,135 @@ fn each_block<'a, 'tcx, O>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                       form `&expr`"));
 }
 
-fn is_rustc_peek<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
-                           terminator: &'a Option<mir::Terminator<'tcx>>)
-                           -> Option<(&'a [mir::Operand<'tcx>], Span)> {
+/// Like `sanity_check_via_rustc_peek`, but for the borrows analysis,
+/// which is only available to the borrow checker once region
+/// inference is done (see `#[rustc_mir(rustc_peek_borrows)]`).
+///
+/// For each call `rustc_peek(&expr)`, emits an error listing the
+/// borrows of the place `expr` (or of places within it) that are in
+/// scope right before the call, or saying that there are none. Tests
+/// pin the results of the analysis by matching on these errors.
+pub(crate) fn sanity_check_borrows_via_rustc_peek<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+    flow_borrows: &mut FlowAtLocation<ActiveBorrows<'a, 'gcx, 'tcx>>,
+) {
+    for bb in mir.basic_blocks().indices() {
+        each_block_borrows(tcx, mir, flow_borrows, bb);
+    }
+}
+
+fn each_block_borrows<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+    flow_borrows: &mut FlowAtLocation<ActiveBorrows<'a, 'gcx, 'tcx>>,
+    bb: mir::BasicBlock,
+) {
+    let mir::BasicBlockData { ref statements, ref terminator, is_cleanup: _ } = mir[bb];
+
+    let (args, span) = match is_rustc_peek(tcx, terminator) {
+        Some(args_and_span) => args_and_span,
+        None => return,
+    };
+    assert!(args.len() == 1);
+    let peek_arg_place = match args[0] {
+        mir::Operand::Copy(ref place @ mir::Place::Local(_)) |
+        mir::Operand::Move(ref place @ mir::Place::Local(_)) => place,
+        _ => {
+            tcx.sess.diagnostic().span_err(
+                span, "dataflow::sanity_check cannot feed a non-temp to rustc_peek.");
+            return;
+        }
+    };
+
+    // Replay the statements of the block up to (but not including)
+    // the borrow that is passed to `rustc_peek`.
+    flow_borrows.reset_to_entry_of(bb);
+    for (j, stmt) in statements.iter().enumerate() {
+        if let mir::StatementKind::Assign(ref place, ref rvalue) = stmt.kind {
+            if place == peek_arg_place {
+                match *rvalue {
+                    mir::Rvalue::Ref(_, mir::BorrowKind::Shared, ref peeking_at_place) => {
+                        report_borrows_in_scope(tcx, flow_borrows, peeking_at_place, span);
+                    }
+                    _ => {
+                        let msg = "rustc_peek: argument expression \
+                                   must be immediate borrow of form `&expr`";
+                        tcx.sess.span_err(span, msg);
+                    }
+                }
+                return;
+            }
+        }
+
+        let location = Location { block: bb, statement_index: j };
+        flow_borrows.reconstruct_statement_effect(location);
+        flow_borrows.apply_local_effect(location);
+    }
+
+    tcx.sess.span_err(span, &format!("rustc_peek: MIR did not match \
+                                      anticipated pattern; note that \
+                                      rustc_peek expects input of \
+                                      form `&expr`"));
+}
+
+fn report_borrows_in_scope<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    flow_borrows: &FlowAtLocation<ActiveBorrows<'a, 'gcx, 'tcx>>,
+    peeking_at_place: &mir::Place<'tcx>,
+    span: Span,
+) {
+    let borrow_set = flow_borrows.operator().borrow_set();
+
+    // A borrow is in scope once it is reserved; both its reservation
+    // and its activation bit map to the same index.
+    let mut in_scope: Vec<BorrowIndex> = vec![];
+    flow_borrows.each_state_bit(|i| {
+        let borrow_index = i.borrow_index();
+        let borrowed_place = &borrow_set.borrows[borrow_index].borrowed_place;
+        if !in_scope.contains(&borrow_index) && is_within(borrowed_place, peeking_at_place) {
+            in_scope.push(borrow_index);
+        }
+    });
+    in_scope.sort();
+
+    if in_scope.is_empty() {
+        tcx.sess.span_err(
+            span,
+            &format!("rustc_peek: no borrows of `{:?}` in scope", peeking_at_place),
+        );
+    } else {
+        let borrows: Vec<String> = in_scope
+            .iter()
+            .map(|&i| format!("{:?} ({})", i, borrow_set.borrows[i]))
+            .collect();
+        tcx.sess.span_err(
+            span,
+            &format!(
+                "rustc_peek: borrows of `{:?}` in scope: {}",
+                peeking_at_place,
+                borrows.join(", ")
+            ),
+        );
+    }
+}
+
+/// True if `place` is `base` or a projection of it.
+fn is_within<'tcx>(mut place: &mir::Place<'tcx>, base: &mir::Place<'tcx>) -> bool {
+    loop {
+        if place == base {
+            return true;
+        }
+        match *place {
+            mir::Place::Projection(ref proj) => place = &proj.base,
+            mir::Place::Local(_) | mir::Place::Static(_) => return false,
+        }
+    }
+}
+
+fn is_rustc_peek<'a, 'gcx, 'tcx>(tcx: TyCtxt<'_, 'gcx, 'tcx>,
+                                 terminator: &'a Option<mir::Terminator<'tcx>>)
+                                 -> Option<(&'a [mir::Operand<'tcx>], Span)> {
     if let Some(mir::Terminator { ref kind, source_info, .. }) = *terminator {
         if let mir::TerminatorKind::Call { func: ref oper, ref args, .. } = *kind {
             if let mir::Operand::Constant(ref func) = *oper {
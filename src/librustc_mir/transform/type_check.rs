@@ -11,12 +11,15 @@
 //! This pass type-checks the MIR to ensure it is not broken.
 #![allow(unreachable_code)]
 
+use borrow_check::nll::delayed_bugs::delay_nll_bug;
 use borrow_check::nll::region_infer::ClosureRegionRequirementsExt;
+use rustc::hir::def_id::DefId;
 use rustc::infer::{InferCtxt, InferOk, InferResult, LateBoundRegionConversionTime, UnitResult};
-use rustc::infer::region_constraints::RegionConstraintData;
+use rustc::infer::region_constraints::{GenericKind, RegionConstraintData};
+use rustc::infer::type_variable::TypeVariableOrigin;
 use rustc::traits::{self, FulfillmentContext};
 use rustc::ty::error::TypeError;
-use rustc::ty::fold::TypeFoldable;
+use rustc::ty::fold::{BottomUpFolder, TypeFoldable};
 use rustc::ty::{self, ToPolyTraitRef, Ty, TyCtxt, TypeVariants};
 use rustc::middle::const_val::ConstVal;
 use rustc::mir::*;
@@ -32,7 +35,13 @@ use rustc_data_structures::indexed_vec::Idx;
 
 /// Type checks the given `mir` in the context of the inference
 /// context `infcx`. Returns any region constraints that have yet to
-/// be proven.
+/// be proven. The `region_bound_pairs` are the implied bounds of the
+/// fn signature (e.g., `T: 'a` from an `&'a T` argument); they are
+/// consulted when proving that a type outlives a region.
+///
+/// If the declared return type mentions `impl Trait` types, it is
+/// given as `anon_output_ty`; the return place then holds the hidden
+/// types instead (see `TypeChecker::equate_anon_types`).
 ///
 /// This phase of type-check ought to be infallible -- this is because
 /// the original, HIR-based type-check succeeded. So if any errors
@@ -42,8 +51,10 @@ pub fn type_check<'a, 'gcx, 'tcx>(
     body_id: ast::NodeId,
     param_env: ty::ParamEnv<'gcx>,
     mir: &Mir<'tcx>,
+    region_bound_pairs: &[(ty::Region<'tcx>, GenericKind<'tcx>)],
+    anon_output_ty: Option<Ty<'tcx>>,
 ) -> MirTypeckRegionConstraints<'tcx> {
-    let mut checker = TypeChecker::new(infcx, body_id, param_env);
+    let mut checker = TypeChecker::new(infcx, body_id, param_env, region_bound_pairs);
     let errors_reported = {
         let mut verifier = TypeVerifier::new(&mut checker, mir);
         verifier.visit_mir(mir);
@@ -53,6 +64,9 @@ pub fn type_check<'a, 'gcx, 'tcx>(
     if !errors_reported {
         // if verifier failed, don't do further checks to avoid ICEs
         checker.typeck_mir(mir);
+        if let Some(anon_output_ty) = anon_output_ty {
+            checker.equate_anon_types(mir, anon_output_ty);
+        }
     }
 
     checker.constraints
@@ -60,9 +74,9 @@ pub fn type_check<'a, 'gcx, 'tcx>(
 
 fn mirbug(tcx: TyCtxt, span: Span, msg: &str) {
     // We sometimes see MIR failures (notably predicate failures) due to
-    // the fact that we check rvalue sized predicates here. So use `delay_span_bug`
+    // the fact that we check rvalue sized predicates here. So delay the bug
     // to avoid reporting bugs in those cases.
-    tcx.sess.diagnostic().delay_span_bug(span, msg);
+    delay_nll_bug(tcx.sess, "type check", span, msg);
 }
 
 macro_rules! span_mirbug {
@@ -508,6 +522,7 @@ pub struct TypeChecker<'a, 'gcx: 'a + 'tcx, 'tcx: 'a> {
     param_env: ty::ParamEnv<'gcx>,
     last_span: Span,
     body_id: ast::NodeId,
+    region_bound_pairs: &'a [(ty::Region<'tcx>, GenericKind<'tcx>)],
     reported_errors: FxHashSet<(Ty<'tcx>, Span)>,
     constraints: MirTypeckRegionConstraints<'tcx>,
 }
@@ -529,6 +544,29 @@ pub struct MirTypeckRegionConstraints<'tcx> {
     /// constraints due to performing subtyping operations or solving
     /// traits. These are accumulated into this vector for later use.
     pub outlives_sets: Vec<OutlivesSet<'tcx>>,
+
+    /// The hidden types inferred for the `impl Trait`s of the return
+    /// type. Which regions they may capture is checked once the
+    /// region values are known.
+    pub anon_types: Vec<AnonTypeDefinition<'tcx>>,
+}
+
+/// The hidden type of an `impl Trait` in the return type of the fn.
+#[derive(Debug)]
+pub struct AnonTypeDefinition<'tcx> {
+    /// The `impl Trait` type.
+    pub def_id: DefId,
+
+    /// The type of the value that the fn actually returns, in terms
+    /// of the region variables of the MIR.
+    pub hidden_ty: Ty<'tcx>,
+
+    /// The regions that the hidden type may capture: the lifetime
+    /// bounds of the `impl Trait` (e.g. `'a` in `impl Debug + 'a`)
+    /// if it has any, and otherwise the lifetimes it is given, all of
+    /// which are universal regions of the fn. If this is empty, only
+    /// `'static` may be captured.
+    pub bound_regions: Vec<ty::Region<'tcx>>,
 }
 
 /// Outlives relationships between regions and types created at a
@@ -541,6 +579,23 @@ pub struct OutlivesSet<'tcx> {
     /// a constraint `R1: R2 @ P`, the data in there specifies things
     /// like `R1: R2`.
     pub data: RegionConstraintData<'tcx>,
+
+    /// If these constraints relate a value to a type that the user
+    /// declared, which one.
+    pub declared_type: Option<DeclaredType>,
+}
+
+/// A type declared outside of the body, as the type of a value of the
+/// MIR. The lifetime bound of a trait object type that is written
+/// there without one defaults to `'static`, and error reporting needs
+/// to know where that type was written to explain such a bound.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DeclaredType {
+    /// The type of the field of a struct or enum variant.
+    Field(DefId),
+
+    /// The return type of the function.
+    Return,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -561,12 +616,14 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
         infcx: &'a InferCtxt<'a, 'gcx, 'tcx>,
         body_id: ast::NodeId,
         param_env: ty::ParamEnv<'gcx>,
+        region_bound_pairs: &'a [(ty::Region<'tcx>, GenericKind<'tcx>)],
     ) -> Self {
         TypeChecker {
             infcx,
             last_span: DUMMY_SP,
             body_id,
             param_env,
+            region_bound_pairs,
             reported_errors: FxHashSet(),
             constraints: MirTypeckRegionConstraints::default(),
         }
@@ -591,14 +648,18 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
             span_mirbug!(self, "", "errors selecting obligation: {:?}", e);
         }
 
-        self.infcx
-            .process_registered_region_obligations(&[], None, self.param_env, self.body_id);
+        self.infcx.process_registered_region_obligations(
+            self.region_bound_pairs,
+            None,
+            self.param_env,
+            self.body_id,
+        );
 
         let data = self.infcx.take_and_reset_region_constraints();
         if !data.is_empty() {
             self.constraints
                 .outlives_sets
-                .push(OutlivesSet { locations, data });
+                .push(OutlivesSet { locations, data, declared_type: None });
         }
 
         Ok(value)
@@ -617,6 +678,23 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
         })
     }
 
+    /// Like `sub_types`, where `sup` is the declared type
+    /// `declared_type`, which we note on the resulting constraints.
+    fn sub_declared_type(
+        &mut self,
+        sub: Ty<'tcx>,
+        sup: Ty<'tcx>,
+        locations: Locations,
+        declared_type: Option<DeclaredType>,
+    ) -> UnitResult<'tcx> {
+        let first_set = self.constraints.outlives_sets.len();
+        let result = self.sub_types(sub, sup, locations);
+        for outlives_set in &mut self.constraints.outlives_sets[first_set..] {
+            outlives_set.declared_type = declared_type;
+        }
+        result
+    }
+
     fn eq_types(&mut self, a: Ty<'tcx>, b: Ty<'tcx>, locations: Locations) -> UnitResult<'tcx> {
         self.fully_perform_op(locations, |this| {
             this.infcx
@@ -636,9 +714,13 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
             StatementKind::Assign(ref place, ref rv) => {
                 let place_ty = place.ty(mir, tcx).to_ty(tcx);
                 let rv_ty = rv.ty(mir, tcx);
-                if let Err(terr) =
-                    self.sub_types(rv_ty, place_ty, location.at_successor_within_block())
-                {
+                let declared_type = return_place_declared_type(place);
+                if let Err(terr) = self.sub_declared_type(
+                    rv_ty,
+                    place_ty,
+                    location.at_successor_within_block(),
+                    declared_type,
+                ) {
                     span_mirbug!(
                         self,
                         stmt,
@@ -678,6 +760,7 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
             StatementKind::StorageLive(_) |
             StatementKind::StorageDead(_) |
             StatementKind::InlineAsm { .. } |
+            StatementKind::FakeRead(..) |
             StatementKind::EndRegion(_) |
             StatementKind::Validate(..) |
             StatementKind::Nop => {}
@@ -864,7 +947,10 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
                     from_location: term_location,
                     at_location: target_block.start_location(),
                 };
-                if let Err(terr) = self.sub_types(sig.output(), dest_ty, locations) {
+                let declared_type = return_place_declared_type(dest);
+                if let Err(terr) =
+                    self.sub_declared_type(sig.output(), dest_ty, locations, declared_type)
+                {
                     span_mirbug!(
                         self,
                         term,
@@ -1188,6 +1274,12 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
 
             Rvalue::Cast(cast_kind, op, ty) => match cast_kind {
                 CastKind::ReifyFnPointer => {
+                    // Note that the late-bound regions of `fn_sig`
+                    // (including those of foreign fns, which are
+                    // reified here too) remain bound and are
+                    // related by `eq_types` like those of any other
+                    // higher-ranked type, so they never become
+                    // universal regions of the body being checked.
                     let fn_sig = op.ty(mir, tcx).fn_sig(tcx);
 
                     // The type that we see in the fcx is like
@@ -1311,9 +1403,20 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
                 }
             };
             let operand_ty = operand.ty(mir, tcx);
-            if let Err(terr) =
-                self.sub_types(operand_ty, field_ty, location.at_successor_within_block())
-            {
+            let declared_type = match *aggregate_kind {
+                AggregateKind::Adt(def, variant_index, _, active_field_index) => {
+                    let variant = &def.variants[variant_index];
+                    let field = &variant.fields[active_field_index.unwrap_or(i)];
+                    Some(DeclaredType::Field(field.did))
+                }
+                _ => None,
+            };
+            if let Err(terr) = self.sub_declared_type(
+                operand_ty,
+                field_ty,
+                location.at_successor_within_block(),
+                declared_type,
+            ) {
                 span_mirbug!(
                     self,
                     rvalue,
@@ -1364,20 +1467,30 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
             // desugaring. A closure gets desugared to a struct, and
             // these extra requirements are basically like where
             // clauses on the struct.
+            //
+            // This is the only place where the requirements are
+            // applied, so each one yields a single constraint, located
+            // at the statement that creates the closure and blamed on
+            // the closure body through its `ClosureRequirement` origin.
             AggregateKind::Closure(def_id, substs) => {
-                if let Some(closure_region_requirements) = tcx.mir_borrowck(*def_id) {
-                    closure_region_requirements.apply_requirements(
-                        self.infcx,
-                        location,
-                        *def_id,
-                        *substs,
-                    );
-                }
+                self.apply_closure_requirements(
+                    *def_id,
+                    tcx.mk_closure(*def_id, *substs),
+                    location,
+                );
 
                 tcx.predicates_of(*def_id).instantiate(tcx, substs.substs)
             }
 
-            AggregateKind::Generator(def_id, substs, _) => {
+            // Generators are checked like closures, and so can have
+            // extra requirements for us in just the same way.
+            AggregateKind::Generator(def_id, substs, interior) => {
+                self.apply_closure_requirements(
+                    *def_id,
+                    tcx.mk_generator(*def_id, *substs, *interior),
+                    location,
+                );
+
                 tcx.predicates_of(*def_id).instantiate(tcx, substs.substs)
             }
 
@@ -1389,6 +1502,35 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
         self.prove_predicates(&predicates, location);
     }
 
+    /// Applies the requirements, if any, that borrow-checking the
+    /// closure or generator `def_id` found it to impose on its creator.
+    fn apply_closure_requirements(
+        &mut self,
+        def_id: DefId,
+        closure_ty: Ty<'tcx>,
+        location: Location,
+    ) {
+        let tcx = self.tcx();
+        let closure_region_requirements = match tcx.mir_borrowck(def_id).closure_requirements {
+            Some(closure_region_requirements) => closure_region_requirements,
+            None => return,
+        };
+
+        // The requirements only name the global and external regions
+        // of the closure, which come first among its universal regions.
+        if let Some(summary) = tcx.universal_regions_summary(def_id) {
+            debug_assert_eq!(closure_region_requirements.num_external_vids,
+                             summary.first_local_index);
+        }
+
+        closure_region_requirements.apply_requirements(
+            self.infcx,
+            self.body_id,
+            location,
+            closure_ty,
+        );
+    }
+
     fn prove_trait_ref(&mut self, trait_ref: ty::TraitRef<'tcx>, location: Location) {
         self.prove_predicates(
             &[
@@ -1445,6 +1587,70 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
         }
     }
 
+    /// The return place of a fn returning `impl Trait` has the hidden
+    /// types, as the body computed them, rather than the `impl Trait`
+    /// types of the declared return type `anon_output_ty`. We relate
+    /// the two by replacing each `impl Trait` with a fresh type
+    /// variable, equating the result with the type of the return
+    /// place, and recording what each variable turned out to be.
+    fn equate_anon_types(&mut self, mir: &Mir<'tcx>, anon_output_ty: Ty<'tcx>) {
+        let infcx = self.infcx;
+        let tcx = infcx.tcx;
+        let span = mir.span;
+
+        let mut anon_types = vec![];
+        let instantiated_output_ty = anon_output_ty.fold_with(&mut BottomUpFolder {
+            tcx,
+            fldop: |ty| match ty.sty {
+                ty::TyAnon(def_id, substs) => {
+                    let ty_var = infcx.next_ty_var(TypeVariableOrigin::TypeInference(span));
+                    anon_types.push((ty, def_id, substs, ty_var));
+                    ty_var
+                }
+                _ => ty,
+            },
+        });
+
+        let return_ty = mir.local_decls[RETURN_PLACE].ty;
+        let locations = Location { block: START_BLOCK, statement_index: 0 }.at_self();
+        if let Err(terr) = self.eq_types(return_ty, instantiated_output_ty, locations) {
+            span_mirbug!(
+                self,
+                anon_output_ty,
+                "bad return type ({:?} = {:?}): {:?}",
+                return_ty,
+                instantiated_output_ty,
+                terr
+            );
+            return;
+        }
+
+        for (anon_ty, def_id, substs, ty_var) in anon_types {
+            // As in the `regionck` of the HIR type check, a hidden
+            // type is only required to outlive the lifetime bounds of
+            // the `impl Trait` if there are any; only the lifetimes
+            // declared on the `impl Trait` itself (rather than those
+            // of the fn it inherits) are given to it.
+            let predicates = tcx.predicates_of(def_id).instantiate(tcx, substs).predicates;
+            let required_region_bounds = tcx.required_region_bounds(anon_ty, predicates);
+            let bound_regions = if !required_region_bounds.is_empty() {
+                required_region_bounds
+            } else {
+                tcx.generics_of(def_id)
+                    .regions
+                    .iter()
+                    .map(|region_def| substs.region_at(region_def.index as usize))
+                    .collect()
+            };
+
+            self.constraints.anon_types.push(AnonTypeDefinition {
+                def_id,
+                hidden_ty: infcx.resolve_type_vars_if_possible(&ty_var),
+                bound_regions,
+            });
+        }
+    }
+
     fn normalize<T>(&mut self, value: &T, location: Location) -> T
     where
         T: fmt::Debug + TypeFoldable<'tcx>,
@@ -1459,6 +1665,15 @@ impl<'a, 'gcx, 'tcx> TypeChecker<'a, 'gcx, 'tcx> {
     }
 }
 
+/// The declared type of `place`, if it is the return place: only then
+/// is its type written outside of the body.
+fn return_place_declared_type(place: &Place<'_>) -> Option<DeclaredType> {
+    match *place {
+        Place::Local(RETURN_PLACE) => Some(DeclaredType::Return),
+        _ => None,
+    }
+}
+
 pub struct TypeckMir;
 
 impl MirPass for TypeckMir {
@@ -1474,7 +1689,7 @@ impl MirPass for TypeckMir {
         }
         let param_env = tcx.param_env(def_id);
         tcx.infer_ctxt().enter(|infcx| {
-            let _region_constraint_sets = type_check(&infcx, id, param_env, mir);
+            let _region_constraint_sets = type_check(&infcx, id, param_env, mir, &[], None);
 
             // For verification purposes, we just ignore the resulting
             // region constraint sets. Not our problem. =)
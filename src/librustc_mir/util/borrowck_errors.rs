@@ -424,6 +424,22 @@ pub trait BorrowckErrors {
         self.cancel_if_wrong_origin(err, o)
     }
 
+    fn path_dropped_before_earlier_declared_borrower(&self,
+                                                     span: Span,
+                                                     path: &str,
+                                                     borrower: &str,
+                                                     o: Origin)
+                                                     -> DiagnosticBuilder
+    {
+        let err = struct_span_err!(self, span, E0597,
+                                   "{} does not live long enough; {} borrows from {} and is \
+                                    dropped after it because it was declared first; consider \
+                                    reordering the declarations{OGN}",
+                                   path, borrower, path, OGN=o);
+
+        self.cancel_if_wrong_origin(err, o)
+    }
+
     fn lifetime_too_short_for_reborrow(&self,
                                        span: Span,
                                        path: &str,
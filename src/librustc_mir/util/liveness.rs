@@ -251,7 +251,10 @@ impl<'tcx> Visitor<'tcx> for DefsUsesVisitor {
             PlaceContext::Call |
 
             // Storage live and storage dead aren't proper defines, but we can ignore
-            // values that come before them.
+            // values that come before them. In particular, a local is never live
+            // past its `StorageDead`, and so neither are the regions in its type
+            // (for NLL), whether or not its address was taken: a borrow of the
+            // local has a region of its own.
             PlaceContext::StorageLive |
             PlaceContext::StorageDead => {
                 self.defs_uses.add_def(local);
@@ -396,7 +399,7 @@ pub fn write_mir_fn<'a, 'tcx>(
     w: &mut Write,
     result: &LivenessResult,
 ) -> io::Result<()> {
-    write_mir_intro(tcx, src, mir, w)?;
+    write_mir_intro(tcx, src, mir, &mut |_, _| Ok(()), w)?;
     for block in mir.basic_blocks().indices() {
         let print = |w: &mut Write, prefix, result: &IndexVec<BasicBlock, LocalSet>| {
             let live: Vec<String> = mir.local_decls
@@ -44,6 +44,10 @@ pub enum PassWhere {
 
     /// We just dumped the given statement or terminator.
     AfterLocation(Location),
+
+    /// We just dumped the declaration of the given local, at the top
+    /// of the MIR.
+    AfterLocalDecl(Local),
 }
 
 /// If the session is properly configured, dumps a human-readable
@@ -283,7 +287,7 @@ pub fn write_mir_fn<'a, 'gcx, 'tcx, F>(
 where
     F: FnMut(PassWhere, &mut Write) -> io::Result<()>,
 {
-    write_mir_intro(tcx, src, mir, w)?;
+    write_mir_intro(tcx, src, mir, extra_data, w)?;
     for block in mir.basic_blocks().indices() {
         extra_data(PassWhere::BeforeBlock(block), w)?;
         write_basic_block(tcx, block, mir, extra_data, w)?;
@@ -450,14 +454,18 @@ fn comment(tcx: TyCtxt, SourceInfo { span, scope }: SourceInfo) -> String {
 /// Prints user-defined variables in a scope tree.
 ///
 /// Returns the total number of variables printed.
-fn write_scope_tree(
+fn write_scope_tree<F>(
     tcx: TyCtxt,
     mir: &Mir,
     scope_tree: &FxHashMap<VisibilityScope, Vec<VisibilityScope>>,
+    extra_data: &mut F,
     w: &mut Write,
     parent: VisibilityScope,
     depth: usize,
-) -> io::Result<()> {
+) -> io::Result<()>
+where
+    F: FnMut(PassWhere, &mut Write) -> io::Result<()>,
+{
     let indent = depth * INDENT.len();
 
     let children = match scope_tree.get(&parent) {
@@ -503,9 +511,10 @@ fn write_scope_tree(
                 name,
                 comment(tcx, source_info)
             )?;
+            extra_data(PassWhere::AfterLocalDecl(local), w)?;
         }
 
-        write_scope_tree(tcx, mir, scope_tree, w, child, depth + 1)?;
+        write_scope_tree(tcx, mir, scope_tree, extra_data, w, child, depth + 1)?;
 
         writeln!(w, "{0:1$}}}", "", depth * INDENT.len())?;
     }
@@ -515,12 +524,16 @@ fn write_scope_tree(
 
 /// Write out a human-readable textual representation of the MIR's `fn` type and the types of its
 /// local variables (both user-defined bindings and compiler temporaries).
-pub fn write_mir_intro<'a, 'gcx, 'tcx>(
+pub fn write_mir_intro<'a, 'gcx, 'tcx, F>(
     tcx: TyCtxt<'a, 'gcx, 'tcx>,
     src: MirSource,
     mir: &Mir,
+    extra_data: &mut F,
     w: &mut Write,
-) -> io::Result<()> {
+) -> io::Result<()>
+where
+    F: FnMut(PassWhere, &mut Write) -> io::Result<()>,
+{
     write_mir_sig(tcx, src, mir, w)?;
     writeln!(w, " {{")?;
 
@@ -546,10 +559,11 @@ pub fn write_mir_intro<'a, 'gcx, 'tcx>(
     writeln!(w, "{0:1$} // return place",
              indented_retptr,
              ALIGN)?;
+    extra_data(PassWhere::AfterLocalDecl(RETURN_PLACE), w)?;
 
-    write_scope_tree(tcx, mir, &scope_tree, w, ARGUMENT_VISIBILITY_SCOPE, 1)?;
+    write_scope_tree(tcx, mir, &scope_tree, extra_data, w, ARGUMENT_VISIBILITY_SCOPE, 1)?;
 
-    write_temp_decls(mir, w)?;
+    write_temp_decls(mir, extra_data, w)?;
 
     // Add an empty line before the first block is printed.
     writeln!(w, "")?;
@@ -594,7 +608,10 @@ fn write_mir_sig(tcx: TyCtxt, src: MirSource, mir: &Mir, w: &mut Write) -> io::R
     }
 }
 
-fn write_temp_decls(mir: &Mir, w: &mut Write) -> io::Result<()> {
+fn write_temp_decls<F>(mir: &Mir, extra_data: &mut F, w: &mut Write) -> io::Result<()>
+where
+    F: FnMut(PassWhere, &mut Write) -> io::Result<()>,
+{
     // Compiler-introduced temporary types.
     for temp in mir.temps_iter() {
         writeln!(
@@ -604,6 +621,7 @@ fn write_temp_decls(mir: &Mir, w: &mut Write) -> io::Result<()> {
             temp,
             mir.local_decls[temp].ty
         )?;
+        extra_data(PassWhere::AfterLocalDecl(temp), w)?;
     }
 
     Ok(())
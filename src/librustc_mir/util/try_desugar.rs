@@ -0,0 +1,107 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Recognizing the MIR that HIR lowering generates for the `?`
+//! operator. `expr?` becomes
+//!
+//! ```text
+//! match Try::into_result(expr) {
+//!     Ok(val) => val,
+//!     Err(err) => return Try::from_error(From::from(err)),
+//! }
+//! ```
+//!
+//! where every piece carries the span of the whole `expr?`. Errors
+//! blamed on that code should point at the `?` token instead.
+
+use rustc::hir::def_id::DefId;
+use rustc::mir::{BasicBlock, Mir, Operand, TerminatorKind};
+use rustc::ty::{self, TyCtxt};
+use syntax_pos::{BytePos, Span};
+use syntax_pos::hygiene::CompilerDesugaringKind;
+
+/// Which of the calls generated for a `?` a terminator is.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TryDesugarCall {
+    /// `Try::into_result(expr)`, which evaluates the operand.
+    IntoResult,
+    /// `From::from(err)`, which converts the error.
+    Conversion,
+    /// `Try::from_error(..)`, whose result is returned early.
+    FromError,
+}
+
+/// If `block` is terminated by one of the calls generated for an
+/// `expr?`, returns which one, along with the span of `expr?`.
+pub fn try_desugar_call<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    mir: &Mir<'tcx>,
+    block: BasicBlock,
+) -> Option<(TryDesugarCall, Span)> {
+    let terminator = mir[block].terminator();
+    let (def_id, func_span) = callee(&terminator.kind)?;
+    let span = terminator.source_info.span;
+
+    // Only the paths of `Try::into_result` and `Try::from_error` are
+    // marked as coming from the desugaring; `From::from` has to be
+    // recognized by being called for the same expression as one of
+    // them.
+    let kind = if func_span.is_compiler_desugaring(CompilerDesugaringKind::QuestionMark) {
+        if tcx.item_name(def_id) == "from_error" {
+            TryDesugarCall::FromError
+        } else {
+            TryDesugarCall::IntoResult
+        }
+    } else if tcx.absolute_item_path_str(def_id) == "core::convert::From::from"
+        && is_try_expr_span(mir, span)
+    {
+        TryDesugarCall::Conversion
+    } else {
+        return None;
+    };
+
+    Some((kind, span))
+}
+
+/// Given the span of an `expr?` expression, returns the span of the
+/// `?` token itself.
+pub fn question_mark_span(try_expr_span: Span) -> Span {
+    try_expr_span.with_lo(try_expr_span.hi() - BytePos(1))
+}
+
+/// Whether `span` is that of an `expr?` whose `Try` calls appear in
+/// `mir`.
+fn is_try_expr_span(mir: &Mir, span: Span) -> bool {
+    mir.basic_blocks().iter().any(|data| {
+        let terminator = data.terminator();
+        terminator.source_info.span == span && match callee(&terminator.kind) {
+            Some((_, func_span)) => {
+                func_span.is_compiler_desugaring(CompilerDesugaringKind::QuestionMark)
+            }
+            None => false,
+        }
+    })
+}
+
+/// The function called by `kind`, and the span of the path naming it,
+/// if `kind` is a call of a statically known function.
+fn callee(kind: &TerminatorKind) -> Option<(DefId, Span)> {
+    let func = match *kind {
+        TerminatorKind::Call { ref func, .. } => func,
+        _ => return None,
+    };
+    match *func {
+        Operand::Constant(ref constant) => match constant.ty.sty {
+            ty::TyFnDef(def_id, _) => Some((def_id, constant.span)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -335,6 +335,7 @@ impl<'a, 'tcx> MirConstContext<'a, 'tcx> {
                     mir::StatementKind::EndRegion(_) |
                     mir::StatementKind::Nop => {}
                     mir::StatementKind::InlineAsm { .. } |
+                    mir::StatementKind::FakeRead(..) |
                     mir::StatementKind::SetDiscriminant{ .. } => {
                         span_bug!(span, "{:?} should not appear in constants?", statement.kind);
                     }
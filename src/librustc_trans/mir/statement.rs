@@ -85,6 +85,9 @@ impl<'a, 'tcx> MirContext<'a, 'tcx> {
             mir::StatementKind::EndRegion(_) |
             mir::StatementKind::Validate(..) |
             mir::StatementKind::Nop => bcx,
+            mir::StatementKind::FakeRead(..) => {
+                span_bug!(statement.source_info.span, "FakeRead should have been removed")
+            }
         }
     }
 }
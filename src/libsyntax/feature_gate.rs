@@ -728,6 +728,18 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
                                      is just used for rustc unit tests \
                                      and will never be stable",
                                     cfg_fn!(rustc_attrs))),
+    ("rustc_universal_regions", Normal, Gated(Stability::Unstable,
+                                              "rustc_attrs",
+                                              "the `#[rustc_universal_regions]` attribute \
+                                               is just used for rustc unit tests \
+                                               and will never be stable",
+                                              cfg_fn!(rustc_attrs))),
+    ("rustc_nll_delay_bug", Normal, Gated(Stability::Unstable,
+                                          "rustc_attrs",
+                                          "the `#[rustc_nll_delay_bug]` attribute \
+                                           is just used for rustc unit tests \
+                                           and will never be stable",
+                                          cfg_fn!(rustc_attrs))),
     ("rustc_error", Whitelisted, Gated(Stability::Unstable,
                                        "rustc_attrs",
                                        "the `#[rustc_error]` attribute \
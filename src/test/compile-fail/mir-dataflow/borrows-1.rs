@@ -0,0 +1,38 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test of the borrows computed by MIR dataflow under NLL: a borrow
+// through a reference is no longer in scope once the reference is
+// overwritten.
+
+// compile-flags: -Znll -Zborrowck=mir
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+#[rustc_mir(rustc_peek_borrows)]
+fn overwrite(mut a: u32, mut b: u32) -> u32 {
+    let mut p = &mut a;
+    let r = &*p;
+    unsafe { rustc_peek(&p); } //~ ERROR rustc_peek: borrows of
+
+    // Overwriting `p` kills the borrows of `*p`: `r` still refers
+    // to `a`, which is not reachable through `p` any more.
+    p = &mut b;
+    unsafe { rustc_peek(&p); } //~ ERROR rustc_peek: no borrows of
+
+    *p += *r;
+    *p
+}
+
+fn main() {
+    overwrite(1, 2);
+}
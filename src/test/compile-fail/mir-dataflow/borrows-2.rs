@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test of the borrows computed by MIR dataflow under NLL: a borrow
+// goes out of scope where its region ends, after the last use of the
+// reference, and not at the end of the enclosing block.
+
+// compile-flags: -Znll -Zborrowck=mir
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+#[rustc_mir(rustc_peek_borrows)]
+fn region_ends(mut x: u32) -> u32 {
+    let r = &x;
+    unsafe { rustc_peek(&x); } //~ ERROR rustc_peek: borrows of
+
+    let y = *r;
+    unsafe { rustc_peek(&x); } //~ ERROR rustc_peek: no borrows of
+
+    x += y;
+    x
+}
+
+fn main() {
+    region_ends(1);
+}
@@ -46,7 +46,7 @@ fn bar<'a>(x: &'a u32) -> &'static u32 {
     let f: fn(_) -> _ = foo;
     //~^ WARNING not reporting region error due to -Znll
     f(x)
-    //~^ ERROR free region `'_#1r` does not outlive free region `'static`
+    //~^ ERROR free region `'a` does not outlive free region `'static`
 }
 
 fn main() {}
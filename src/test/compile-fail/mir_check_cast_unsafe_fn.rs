@@ -18,7 +18,7 @@ fn bar<'a>(input: &'a u32, f: fn(&'a u32) -> &'a u32) -> &'static u32 {
     let g: unsafe fn(_) -> _ = f;
     //~^ WARNING not reporting region error due to -Znll
     unsafe { g(input) }
-    //~^ ERROR free region `'_#1r` does not outlive free region `'static`
+    //~^ ERROR free region `'a` does not outlive free region `'static`
 }
 
 fn main() {}
@@ -0,0 +1,38 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that an erased region coming back from the normalization of
+// an associated type is treated as `'static` rather than crashing
+// NLL. The borrow error below is real; it also ensures that the
+// delayed bug recorded for the erased region is not reported.
+
+// compile-flags: -Z borrowck=mir -Z nll
+
+trait Project {
+    type Out;
+}
+
+impl<'a> Project for &'a u32 {
+    type Out = &'a u32;
+}
+
+fn project<T: Project>(x: T::Out) -> T::Out {
+    x
+}
+
+fn main() {
+    let r;
+    {
+        let x = 22;
+        r = project::<&u32>(&x);
+        //~^ ERROR `x` does not live long enough
+    }
+    println!("{}", r);
+}
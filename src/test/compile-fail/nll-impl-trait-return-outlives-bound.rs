@@ -0,0 +1,24 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a fn returning an `impl Trait` whose hidden type does
+// not outlive the bound is still an error under MIR borrowck.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(conservative_impl_trait)]
+
+fn capture_static<'a>(x: &'a u32) -> impl Fn() -> u32 + 'static {
+    move || *x
+    //~^ ERROR hidden type for `impl Trait` captures lifetime that does not appear in bounds
+    //~| WARNING not reporting region error due to -Znll
+}
+
+fn main() {}
@@ -0,0 +1,38 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that MIR borrowck does not ICE on bodies that contain an
+// unresolved macro in statement position, whose MIR is only a
+// stand-in produced by error recovery.
+
+// compile-flags: -Z borrowck=mir -Z nll
+
+fn with_args<'a>(x: &'a u32, y: &u32) -> &'a u32 {
+    not_a_macro!(y);
+    //~^ ERROR cannot find macro `not_a_macro!` in this scope
+    x
+}
+
+fn in_closure() {
+    let f = |x: &u32| {
+        also_not_a_macro!();
+        //~^ ERROR cannot find macro `also_not_a_macro!` in this scope
+        *x
+    };
+    f(&22);
+}
+
+const C: &u32 = {
+    not_a_macro_either!();
+    //~^ ERROR cannot find macro `not_a_macro_either!` in this scope
+    &22
+};
+
+fn main() {}
@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that when a local is fully reinitialized through a sequence of
+// field assignments, a borrow taken in between those assignments is
+// not killed along with the borrows of the old value.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn main() {
+    let mut x = (0, 0);
+    let r;
+    x.0 = 1;
+    r = &x.0;
+    x.1 = 2;
+    x.0 = 3; //~ ERROR cannot assign to `x.0` because it is borrowed [E0506]
+    drop(r);
+}
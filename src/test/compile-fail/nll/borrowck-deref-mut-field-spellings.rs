@@ -0,0 +1,113 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Borrows of a `&mut` referent and of its fields must conflict (or not)
+// the same way however the places are spelled: through an explicit
+// deref or through autoderef, and in either order.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(unused_variables)]
+
+struct S { f: u32, g: u32 }
+
+fn whole_then_auto_field(r: &mut S) {
+    let a = &mut *r;
+    let b = &r.f; //~ ERROR [E0502]
+    a.g = 1;
+}
+
+fn whole_then_explicit_field(r: &mut S) {
+    let a = &mut *r;
+    let b = &(*r).f; //~ ERROR [E0502]
+    a.g = 1;
+}
+
+fn auto_field_then_whole(r: &mut S) {
+    let a = &r.f;
+    let b = &mut *r; //~ ERROR [E0502]
+    drop(a);
+}
+
+fn explicit_field_then_whole(r: &mut S) {
+    let a = &(*r).f;
+    let b = &mut *r; //~ ERROR [E0502]
+    drop(a);
+}
+
+fn auto_field_then_explicit_field(r: &mut S) {
+    let a = &mut r.f;
+    let b = &(*r).f; //~ ERROR [E0502]
+    *a = 1;
+}
+
+fn explicit_field_then_auto_field(r: &mut S) {
+    let a = &mut (*r).f;
+    let b = &r.f; //~ ERROR [E0502]
+    *a = 1;
+}
+
+fn shared_auto_field_then_mut_explicit_field(r: &mut S) {
+    let a = &r.f;
+    let b = &mut (*r).f; //~ ERROR [E0502]
+    drop(a);
+}
+
+fn shared_explicit_field_then_mut_auto_field(r: &mut S) {
+    let a = &(*r).f;
+    let b = &mut r.f; //~ ERROR [E0502]
+    drop(a);
+}
+
+fn auto_field_then_explicit_other_field(r: &mut S) {
+    let a = &mut r.f;
+    let b = &(*r).g; // OK, disjoint fields
+    *a = 1;
+}
+
+fn explicit_field_then_auto_other_field(r: &mut S) {
+    let a = &mut (*r).f;
+    let b = &r.g; // OK, disjoint fields
+    *a = 1;
+}
+
+fn double_deref_then_auto_field(rr: &mut &mut S) {
+    let a = &mut **rr;
+    let b = &rr.f; //~ ERROR [E0502]
+    a.g = 1;
+}
+
+fn double_deref_then_explicit_field(rr: &mut &mut S) {
+    let a = &mut **rr;
+    let b = &(**rr).f; //~ ERROR [E0502]
+    a.g = 1;
+}
+
+fn auto_field_then_double_deref(rr: &mut &mut S) {
+    let a = &rr.f;
+    let b = &mut **rr; //~ ERROR [E0502]
+    drop(a);
+}
+
+fn explicit_field_then_double_deref(rr: &mut &mut S) {
+    let a = &(**rr).f;
+    let b = &mut **rr; //~ ERROR [E0502]
+    drop(a);
+}
+
+fn inner_reference_then_referent_field(rr: &mut &mut S) {
+    // `*rr` is the inner reference, which the shared borrow of
+    // `(**rr).f` reads through, so the two still overlap.
+    let a = &mut *rr;
+    let b = &(**rr).f; //~ ERROR [E0502]
+    a.g = 1;
+}
+
+fn main() { }
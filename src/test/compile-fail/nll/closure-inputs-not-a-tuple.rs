@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the MIR borrow checker does not ICE on a closure whose
+// expected signature, written with the angle-bracket form of `Fn`
+// inside a macro, does not take its inputs as a tuple, but lets the
+// original error stand.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(unboxed_closures)]
+
+macro_rules! with_callback {
+    ($arg:ty, $body:expr) => {{
+        fn call<F: Fn<$arg, Output = ()>>(_: F) {}
+        call($body)
+    }};
+}
+
+fn main() {
+    with_callback!(&u32, |x: &u32| ());
+    //~^ ERROR type mismatch in closure arguments [E0631]
+}
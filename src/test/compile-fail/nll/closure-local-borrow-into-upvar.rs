@@ -0,0 +1,28 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a borrow of a local of a closure, which must outlive the
+// region of an upvar, is reported in the closure, rather than being
+// passed on to the creator as a requirement: the creator could not
+// keep the local alive anyway.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn main() {
+    let mut out: Option<&u32> = None;
+    {
+        let mut store = || {
+            let x = 22;
+            out = Some(&x); //~ ERROR `x` does not live long enough [E0597]
+        };
+        store();
+    }
+    println!("{:?}", out);
+}
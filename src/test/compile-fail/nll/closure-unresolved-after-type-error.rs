@@ -0,0 +1,21 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the MIR borrow checker does not ICE on a closure whose
+// types were never inferred because of an earlier type error, but
+// lets the type errors stand.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn main() {
+    let x: u32 = "not a number"; //~ ERROR mismatched types
+    let f = |y| y;
+    //~^ ERROR type annotations needed
+}
@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the initializer of a constant is checked against the
+// declared type of the constant: here a const fn ties the region of
+// its result to that of its argument, which is the `'a` of the impl,
+// and so does not live as long as the declared `'static`.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(const_fn)]
+
+const fn id<'a>(x: &'a u32) -> &'a u32 {
+    x
+}
+
+struct Holder<'a>(&'a u32);
+
+impl<'a> Holder<'a> {
+    const SHORT: &'a u32 = &44;
+    const LONG: &'static u32 = id(Self::SHORT);
+    //~^ ERROR free region `'a` does not outlive free region `'static`
+    //~| WARNING not reporting region error due to -Znll
+}
+
+fn main() {
+    let _ = Holder::LONG;
+}
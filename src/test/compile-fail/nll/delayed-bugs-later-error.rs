@@ -0,0 +1,23 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the bugs delayed by the MIR borrow checker are dropped
+// if an error is reported after borrowck is done, here by the dead
+// code lint, and not only if one was reported before.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(rustc_attrs)]
+#![deny(dead_code)]
+
+#[rustc_nll_delay_bug]
+fn unused() { } //~ ERROR function is never used: `unused`
+
+fn main() { }
@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that we do not blame the declaration order of `a` and `b`
+// (and suggest swapping them) when the borrow of `b` is held by a
+// local of an outer block, which reordering `a` and `b` cannot fix.
+// The ordinary error, pointing at the borrow, is reported instead.
+
+// compile-flags: -Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+struct Foo<'x>(Option<&'x u32>, u32);
+
+impl<'x> Drop for Foo<'x> {
+    fn drop(&mut self) {}
+}
+
+fn borrower_in_outer_block() {
+    let mut c = Foo(None, 0);
+    {
+        let mut a = Foo(None, 0);
+        let b = 22;
+        c.0 = Some(&b); //~ ERROR `b` does not live long enough
+    }
+}
+
+fn main() { }
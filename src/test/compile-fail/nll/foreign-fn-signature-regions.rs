@@ -0,0 +1,36 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the late-bound regions of a foreign fn signature are
+// instantiated with fresh variables at each use in the body being
+// checked, so that they still relate the arguments to the result:
+// the result of `pick` cannot outlive a local passed as its first
+// argument, whether `pick` is called directly or through a pointer.
+
+// compile-flags: -Z borrowck=mir -Z nll
+
+#![allow(dead_code)]
+
+extern "C" {
+    fn pick<'a>(x: &'a u8, y: &u8) -> &'a u8;
+}
+
+fn call_directly<'a>(a: &'a u8) -> &'a u8 {
+    let b = 3;
+    unsafe { pick(&b, a) } //~ ERROR `b` does not live long enough
+}
+
+fn call_through_pointer<'a>(a: &'a u8) -> &'a u8 {
+    let f: unsafe extern "C" fn(&u8, &u8) -> &u8 = pick;
+    let b = 3;
+    unsafe { f(&b, a) } //~ ERROR `b` does not live long enough
+}
+
+fn main() {}
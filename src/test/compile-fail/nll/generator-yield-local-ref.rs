@@ -0,0 +1,27 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the values a generator yields must outlive its yield
+// type, which is a universal region of the generator body: a
+// reference to a local of the generator cannot be yielded.
+
+// compile-flags: -Z borrowck=mir -Z nll
+
+#![feature(generators)]
+#![allow(dead_code)]
+
+fn yield_local_ref() {
+    let _gen = || {
+        let y = 22;
+        yield &y; //~ ERROR `y` does not live long enough
+    };
+}
+
+fn main() {}
@@ -0,0 +1,44 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that an unsatisfiable higher-ranked where-clause is reported
+// as an error under the MIR borrow checker, rather than crashing it
+// with the skolemized regions that it brings into the constraints.
+
+// compile-flags: -Z borrowck=mir -Z nll
+
+#![allow(dead_code)]
+
+trait Foo<X> {
+    fn foo(&self, x: X) { }
+}
+
+fn want_hrtb<T>()
+where
+    T: for<'a> Foo<&'a u32>,
+{
+}
+
+// `AnyInt` implements `Foo<&'a u32>` for any `'a`.
+struct AnyInt;
+impl<'a> Foo<&'a u32> for AnyInt { }
+fn give_any() {
+    want_hrtb::<AnyInt>()
+}
+
+// `StaticInt` only implements `Foo<&'static u32>`.
+struct StaticInt;
+impl Foo<&'static u32> for StaticInt { }
+fn give_static() {
+    want_hrtb::<StaticInt>()
+    //~^ ERROR `for<'a> StaticInt: Foo<&'a u32>` is not satisfied
+}
+
+fn main() {}
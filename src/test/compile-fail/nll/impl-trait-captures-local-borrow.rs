@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the hidden type of an `impl Trait` cannot capture the
+// region of a borrow of a local: the regions of the hidden type are
+// not universal, but they are live until the function returns.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(conservative_impl_trait)]
+
+use std::fmt::Debug;
+
+fn local() -> impl Debug {
+    let x = 22;
+    &x //~ ERROR `x` does not live long enough [E0597]
+}
+
+fn local_in_tuple<'a>(y: &'a u32) -> impl Debug + 'a {
+    let x = 22;
+    (y, &x) //~ ERROR `x` does not live long enough [E0597]
+}
+
+fn promoted() -> impl Debug {
+    &22
+}
+
+fn main() {}
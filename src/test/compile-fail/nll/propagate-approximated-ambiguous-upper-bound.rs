@@ -0,0 +1,62 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test a case where we are trying to prove `'x: 'y` and the smallest
+// regions known to outlive `'y` that our creator understands are `'a`
+// and `'b`, neither of which outlives the other. Rather than asking
+// the creator to prove `'c: 'static`, which is too strong, we ask it
+// to prove both `'c: 'a` and `'c: 'b`. It can only prove the first.
+//
+// See `ui/nll/closure-requirements/propagate-approximated-ambiguous-upper-bound-related.rs`
+// for the same code with `'a: 'b` added, which compiles.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+use std::cell::Cell;
+
+fn establish_relationships<'a, 'b, 'c, F>(
+    _cell_a: &Cell<&'a u32>,
+    _cell_b: &Cell<&'b u32>,
+    _cell_c: &Cell<&'c u32>,
+    _closure: F,
+) where
+    F: for<'x, 'y> FnMut(
+        &Cell<&'c &'x u32>, // shows that 'x: 'c
+        &Cell<&'y &'a u32>, // shows that 'a: 'y
+        &Cell<&'y &'b u32>, // shows that 'b: 'y
+        &Cell<&'x u32>,
+        &Cell<&'y u32>,
+    ),
+{
+}
+
+fn demand_y<'x, 'y>(_cell_x: &Cell<&'x u32>, _cell_y: &Cell<&'y u32>, _y: &'y u32) {}
+
+fn supply<'a, 'b, 'c>(
+    cell_a: Cell<&'a u32>,
+    cell_b: Cell<&'b u32>,
+    cell_c: Cell<&'c u32>,
+    _outlives: &'a &'c u32, // shows that 'c: 'a
+) {
+    establish_relationships(
+        &cell_a,
+        &cell_b,
+        &cell_c,
+        |_outlives1, _outlives2, _outlives3, x, y| {
+            //~^ ERROR free region `'c` does not outlive free region `'b`
+            // Only works if 'x: 'y:
+            let p = x.get();
+            //~^ WARN not reporting region error due to -Znll
+            demand_y(x, y, p)
+        },
+    );
+}
+
+fn main() {}
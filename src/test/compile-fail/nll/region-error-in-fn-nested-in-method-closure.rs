@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a region error in a fn nested in closures inside an impl
+// method is reported against the nested fn alone: its lifetimes are
+// neither those of the method nor those of the impl.
+
+// compile-flags: -Z borrowck=mir -Z nll
+
+#![allow(warnings)]
+
+struct S<'p>(&'p u32);
+
+impl<'p> S<'p> {
+    fn method<'q>(&self, q: &'q u32) -> u32 {
+        let f = || {
+            let g = || {
+                fn nested<'a, 'b>(_x: &'a u32, y: &'b u32) -> &'a u32 {
+                    y //~ ERROR free region `'b` does not outlive free region `'a`
+                }
+                *nested(self.0, q)
+            };
+            g()
+        };
+        f()
+    }
+}
+
+fn main() { }
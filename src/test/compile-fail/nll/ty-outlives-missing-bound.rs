@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that MIR borrowck reports a generic type that is required to
+// outlive a lifetime but has no bound saying so.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+struct Holder<'a, U: 'a>(Option<&'a U>);
+
+fn hold<'a, U: 'a>() -> Holder<'a, U> {
+    Holder(None)
+}
+
+fn no_bound<'a, T>(_: &'a ()) -> Holder<'a, T> {
+    hold()
+    //~^ ERROR the type `T` may not live long enough
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that when a closure passes on to its creator the requirement
+// that a type outlive one of the creator's lifetimes, the creator
+// still reports an error if it has no bound saying so -- including
+// when the requirement comes from a nested closure.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+use std::fmt::Debug;
+
+fn with_signature<'a, T, F>(x: Box<T>, op: F) -> Box<Debug + 'a>
+    where F: FnOnce(Box<T>) -> Box<Debug + 'a>
+{
+    op(x)
+}
+
+fn no_bound<'a, T: Debug>(x: Box<T>) -> Box<Debug + 'a> {
+    with_signature(x, |y| y)
+    //~^ ERROR the type `T` may not live long enough
+}
+
+fn nested_no_bound<'a, T: Debug>(x: Box<T>) -> Box<Debug + 'a> {
+    with_signature(x, |y| with_signature(y, |z| z))
+    //~^ ERROR the type `T` may not live long enough
+}
+
+fn wrong_bound<'a, 'b, T: Debug + 'b>(x: Box<T>) -> Box<Debug + 'a> {
+    with_signature(x, |y| y)
+    //~^ ERROR the type `T` may not live long enough
+}
+
+fn main() {}
@@ -0,0 +1,40 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that changing the body of a function re-runs its NLL borrow
+// check but leaves the result, and the cached universal regions
+// summary of the closure in an unrelated function, green.
+
+// revisions:cfail1 cfail2
+// compile-flags: -Z query-dep-graph -Z nll -Z borrowck=mir
+// must-compile-successfully
+
+#![feature(rustc_attrs)]
+#![allow(dead_code)]
+#![crate_type = "rlib"]
+
+#[rustc_dirty(label="HirBody", cfg="cfail2")]
+#[rustc_clean(label="MirBorrowCheck", cfg="cfail2")]
+pub fn changed<'a>(x: &'a u32) -> u32 {
+    #[cfg(cfail1)]
+    return *x;
+
+    #[cfg(cfail2)]
+    return *x + 1;
+}
+
+// The closure requires `'b: 'a` of its creator, so borrow-checking
+// `unchanged` reads the summary of the closure.
+#[rustc_clean(label="HirBody", cfg="cfail2")]
+#[rustc_clean(label="MirBorrowCheck", cfg="cfail2")]
+pub fn unchanged<'a, 'b: 'a>(x: &mut &'a u32, y: &'b u32) {
+    let mut f = |p: &mut &'a u32| *p = y;
+    f(x);
+}
@@ -53,6 +53,7 @@ fn main() {
 //  bb0: {
 //      ...
 //      _2 = std::option::Option<i32>::Some(const 42i32,);
+//      FakeRead(ForMatch, _2);
 //      _5 = discriminant(_2);
 //      switchInt(move _5) -> [0isize: bb6, 1isize: bb4, otherwise: bb8];
 //  }
@@ -116,6 +117,7 @@ fn main() {
 //  bb0: {
 //      ...
 //      _2 = std::option::Option<i32>::Some(const 42i32,);
+//      FakeRead(ForMatch, _2);
 //      _5 = discriminant(_2);
 //      switchInt(move _5) -> [0isize: bb5, 1isize: bb4, otherwise: bb8];
 //  }
@@ -179,6 +181,7 @@ fn main() {
 // bb0: {
 //     ...
 //     _2 = std::option::Option<i32>::Some(const 1i32,);
+//     FakeRead(ForMatch, _2);
 //     _7 = discriminant(_2);
 //     switchInt(move _7) -> [1isize: bb4, otherwise: bb5];
 // }
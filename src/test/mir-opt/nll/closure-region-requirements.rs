@@ -0,0 +1,50 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check the region requirements of a closure in the format that the
+// NLL MIR dump uses for them. This is the setup of
+// `ui/nll/closure-requirements/propagate-approximated-ref.rs`, with
+// the bound that the caller needs to satisfy the requirement.
+
+// compile-flags:-Znll -Zborrowck=mir -Zverbose
+// ignore-tidy-linelength
+
+#![allow(warnings)]
+
+use std::cell::Cell;
+
+fn establish_relationships<'a, 'b, F>(_cell_a: &Cell<&'a u32>, _cell_b: &Cell<&'b u32>, _closure: F)
+where
+    F: for<'x, 'y> FnMut(
+        &Cell<&'a &'x u32>, // shows that 'x: 'a
+        &Cell<&'y &'b u32>, // shows that 'b: 'y
+        &Cell<&'x u32>,
+        &Cell<&'y u32>,
+    ),
+{
+}
+
+fn demand_y<'x, 'y>(_cell_x: &Cell<&'x u32>, _cell_y: &Cell<&'y u32>, _y: &'y u32) {}
+
+fn supply<'a: 'b, 'b>(cell_a: Cell<&'a u32>, cell_b: Cell<&'b u32>) {
+    establish_relationships(&cell_a, &cell_b, |_outlives1, _outlives2, x, y| {
+        demand_y(x, y, x.get())
+    });
+}
+
+fn main() {}
+
+// END RUST SOURCE
+// START rustc.supply-{{closure}}.nll.0.mir
+// | Free Region Constraints
+// | external vids: 3
+// | '_#1r: '_#2r @ $DIR/closure-region-requirements.rs:38:21: 38:22
+// ...
+// END rustc.supply-{{closure}}.nll.0.mir
@@ -0,0 +1,47 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a region-bound pair that is implied by more than one
+// argument type is only listed once, with the first origin, and that
+// an outlives relation that is both declared and implied is only
+// recorded once.
+
+// compile-flags:-Znll -Zverbose -Znll-dump-mir-regions=1
+//                     ^^^^^^^^^ force compiler to dump more region information
+// ignore-tidy-linelength
+
+#![allow(warnings)]
+
+fn use_t<'a, T>(x: &'a T, y: &'a T) -> bool { true }
+
+fn nested<'a, 'b: 'a>(x: &'a &'b u32) -> bool { true }
+
+fn main() {
+}
+
+// END RUST SOURCE
+// START rustc.use_t.nll.0.mir
+// | Region Bound Pairs
+// | T/#1: '_#1r (ImpliedByInput(0))
+// |
+// ...
+// fn use_t(_1: &'_#1r T, _2: &'_#1r T) -> bool {
+// END rustc.use_t.nll.0.mir
+// START rustc.nested.nll.0.mir
+// | Known Outlives Relations
+// | '_#0r: '_#1r
+// | '_#0r: '_#2r
+// | '_#2r: '_#1r
+// |
+// | Region Bound Pairs
+// |
+// ...
+// fn nested(_1: &'_#1r &'_#2r u32) -> bool {
+// END rustc.nested.nll.0.mir
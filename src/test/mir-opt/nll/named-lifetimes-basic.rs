@@ -13,7 +13,7 @@
 // suitable variables and that we setup the outlives relationship
 // between R0 and R1 properly.
 
-// compile-flags:-Znll -Zverbose
+// compile-flags:-Znll -Zverbose -Znll-dump-mir-regions=3
 //                     ^^^^^^^^^ force compiler to dump more region information
 // ignore-tidy-linelength
 
@@ -27,9 +27,9 @@ fn main() {
 // END RUST SOURCE
 // START rustc.use_x.nll.0.mir
 // | Free Region Mapping
-// | '_#0r    | Global   | ['_#2r, '_#1r, '_#0r, '_#3r]
+// | '_#0r    | Global   | ['_#0r, '_#1r, '_#2r, '_#3r]
 // | '_#1r    | External | ['_#1r]
-// | '_#2r    | External | ['_#2r, '_#1r]
+// | '_#2r    | External | ['_#1r, '_#2r]
 // | '_#3r    | Local    | ['_#3r]
 // |
 // | Inferred Region Values
@@ -12,7 +12,7 @@
 // in the type of `r_a` must outlive the region (`R7`) that appears in
 // the type of `r_b`
 
-// compile-flags:-Znll -Zverbose
+// compile-flags:-Znll -Zverbose -Znll-dump-mir-regions=3
 //                     ^^^^^^^^^ force compiler to dump more region information
 
 #![allow(warnings)]
@@ -12,7 +12,7 @@
 // in the type of `p` includes the points after `&v[0]` up to (but not
 // including) the call to `use_x`. The `else` branch is not included.
 
-// compile-flags:-Znll -Zverbose
+// compile-flags:-Znll -Zverbose -Znll-dump-mir-regions=3
 //                     ^^^^^^^^^ force compiler to dump more region information
 
 #![allow(warnings)]
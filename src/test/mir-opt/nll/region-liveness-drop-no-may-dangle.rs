@@ -13,7 +13,7 @@
 // including) the call to `use_x`. The `else` branch is not included.
 
 // ignore-tidy-linelength
-// compile-flags:-Znll -Zverbose
+// compile-flags:-Znll -Zverbose -Znll-dump-mir-regions=3
 //                     ^^^^^^^^^ force compiler to dump more region information
 
 #![allow(warnings)]
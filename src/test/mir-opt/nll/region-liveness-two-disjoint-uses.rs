@@ -14,7 +14,7 @@
 // but only at a particular point, and hence they wind up including
 // distinct regions.
 
-// compile-flags:-Znll -Zverbose
+// compile-flags:-Znll -Zverbose -Znll-dump-mir-regions=3
 //                     ^^^^^^^^^ force compiler to dump more region information
 
 #![allow(warnings)]
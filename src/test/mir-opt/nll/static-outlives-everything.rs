@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that `'static` outlives every universal region, and that a
+// region bounded by `'static` does too, even though the relations do
+// not store the edges out of `'static`.
+
+// compile-flags:-Znll -Zverbose -Znll-dump-mir-regions=3
+//                     ^^^^^^^^^ force compiler to dump more region information
+// ignore-tidy-linelength
+
+#![allow(warnings)]
+
+fn use_x<'a, 'b: 'static, 'c>(x: &'a u32, y: &'b u32, z: &'c u32) -> bool { true }
+
+fn main() {
+}
+
+// END RUST SOURCE
+// START rustc.use_x.nll.0.mir
+// | Free Region Mapping
+// | '_#0r    | Global   | ['_#0r, '_#1r, '_#2r, '_#3r]
+// | '_#1r    | External | ['_#1r]
+// | '_#2r    | External | ['_#0r, '_#1r, '_#2r, '_#3r]
+// | '_#3r    | External | ['_#3r]
+// |
+// ...
+// fn use_x(_1: &'_#1r u32, _2: &'_#2r u32, _3: &'_#3r u32) -> bool {
+// END rustc.use_x.nll.0.mir
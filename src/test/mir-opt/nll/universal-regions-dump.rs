@@ -0,0 +1,42 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check the table of universal regions that `-Znll-dump-mir-regions=1`
+// (the default) includes in the NLL MIR dump.
+
+// compile-flags:-Znll -Zverbose -Znll-dump-mir-regions=1
+//                     ^^^^^^^^^ force compiler to dump more region information
+// ignore-tidy-linelength
+
+#![allow(warnings)]
+
+fn use_x<'a, 'b: 'a>(x: &'a u32, y: &'b u32) -> bool { true }
+
+fn main() {
+}
+
+// END RUST SOURCE
+// START rustc.use_x.nll.0.mir
+// | Universal Regions
+// | '_#0r    | Global   | ReStatic
+// | '_#1r    | External | ReEarlyBound(0, 'a)
+// | '_#2r    | External | ReEarlyBound(1, 'b)
+// | first_extern_index=1 first_local_index=3 num_universals=3
+// |
+// | Known Outlives Relations
+// | '_#0r: '_#1r
+// | '_#0r: '_#2r
+// | '_#2r: '_#1r
+// |
+// | Region Bound Pairs
+// |
+// ...
+// fn use_x(_1: &'_#1r u32, _2: &'_#2r u32) -> bool {
+// END rustc.use_x.nll.0.mir
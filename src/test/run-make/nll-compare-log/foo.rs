@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+
+pub fn first<'a, T>(v: &'a [T]) -> &'a T {
+    &v[0]
+}
+
+#[cfg(not(bad))]
+pub fn pick<'a, 'b: 'a>(x: &'a u32, y: &'b u32) -> &'a u32 {
+    if *x > *y { x } else { y }
+}
+
+#[cfg(bad)]
+pub fn pick<'a, 'b>(x: &'a u32, y: &'b u32) -> &'a u32 {
+    if *x > *y { x } else { y }
+}
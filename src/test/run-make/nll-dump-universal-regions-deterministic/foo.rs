@@ -0,0 +1,21 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+
+pub fn foo<'a, 'b, 'c, 'd, 'e: 'd, 'f>(
+    _a: &'a u32,
+    _b: &'b u32,
+    _c: &'c u32,
+    _d: &'d u32,
+    _e: &'e u32,
+    _f: &'f u32,
+) {
+}
@@ -0,0 +1,20 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+
+pub fn alpha<'a, 'b: 'a>(x: &'a u32, _y: &'b u32) -> &'a u32 {
+    x
+}
+
+// A region error, which `-Z nll-skip-unmatched` keeps us from seeing.
+pub fn beta<'a>(x: &'a u32) -> &'static u32 {
+    x
+}
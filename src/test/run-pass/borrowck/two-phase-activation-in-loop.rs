@@ -0,0 +1,31 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// revisions: lxl nll
+//[lxl]compile-flags: -Z borrowck=mir -Z two-phase-borrows
+//[nll]compile-flags: -Z borrowck=mir -Z two-phase-borrows -Z nll
+
+// Two-phase borrows inside a loop, where the activating use of a
+// borrow may be visited before the borrow itself. The activations
+// precomputed for the borrow set must agree with the ones found by
+// the dataflow.
+
+fn main() {
+    let mut v = vec![0];
+    let mut i = 0;
+    while i < 3 {
+        v.push(v.len());
+        if v.len() > 2 {
+            v.push(v[0] + v.len());
+        }
+        i += 1;
+    }
+    assert_eq!(v, [0, 1, 2, 3, 4, 5]);
+}
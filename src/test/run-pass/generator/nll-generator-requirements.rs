@@ -0,0 +1,41 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a generator can propagate region requirements to its
+// creator: storing the captured `&'a u32` into the captured
+// `Cell<&'b u32>` across a yield needs `'a: 'b`, which only the
+// creator knows, from the type of its argument `x`.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(generators, generator_trait)]
+
+use std::cell::Cell;
+use std::ops::{Generator, GeneratorState};
+
+fn store<'a, 'b>(x: &'b &'a u32, cell: Cell<&'b u32>) -> &'b u32 {
+    let x: &'a u32 = *x;
+    let mut gen = move || {
+        yield;
+        cell.set(x);
+        cell.get()
+    };
+    gen.resume();
+    match gen.resume() {
+        GeneratorState::Complete(r) => r,
+        GeneratorState::Yielded(()) => panic!("generator yielded twice"),
+    }
+}
+
+fn main() {
+    let x = 22;
+    let y = 44;
+    assert_eq!(*store(&&x, Cell::new(&y)), 22);
+}
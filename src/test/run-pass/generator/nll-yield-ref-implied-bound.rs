@@ -0,0 +1,34 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the yield type of a generator contributes implied
+// bounds: yielding a `&'a T` taken from a captured `&'a [T]` requires
+// `T: 'a`, which only follows from the yield type itself.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(generators, generator_trait)]
+
+use std::ops::{Generator, GeneratorState};
+
+fn first<'a, T>(items: &'a [T]) -> Option<&'a T> {
+    let mut gen = move || {
+        yield &items[0];
+    };
+    match gen.resume() {
+        GeneratorState::Yielded(x) => Some(x),
+        GeneratorState::Complete(()) => None,
+    }
+}
+
+fn main() {
+    let v = vec![String::from("a"), String::from("b")];
+    assert_eq!(first(&v).map(|s| &s[..]), Some("a"));
+}
@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the result of a `yield` is initialized when the
+// generator resumes, so that reading it afterwards is accepted.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(generators, generator_trait)]
+
+use std::ops::{Generator, GeneratorState};
+
+fn main() {
+    let mut g = || {
+        let r = yield 1;
+        let s = r;
+        yield 2;
+        s
+    };
+
+    assert_eq!(g.resume(), GeneratorState::Yielded(1));
+    assert_eq!(g.resume(), GeneratorState::Yielded(2));
+    assert_eq!(g.resume(), GeneratorState::Complete(()));
+}
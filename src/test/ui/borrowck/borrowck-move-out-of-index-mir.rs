@@ -0,0 +1,41 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check the MIR borrowck errors for moves out of indexed places, and
+// the suggestions that come with them.
+
+// compile-flags: -Z borrowck=mir
+
+fn vec_of_strings(v: Vec<String>) {
+    let s = v[0]; //~ ERROR cannot move out of indexed content
+    drop(s);
+}
+
+fn array_of_strings(a: [String; 2]) {
+    let s = a[1]; //~ ERROR cannot move out of type `[std::string::String; 2]`
+    drop(s);
+}
+
+fn slice_of_options(s: &mut [Option<String>]) {
+    let o = s[0]; //~ ERROR cannot move out of type `[std::option::Option<std::string::String>]`
+    drop(o);
+}
+
+fn vec_of_copies(v: Vec<u32>) -> u32 {
+    let x = v[0];
+    x
+}
+
+fn main() {
+    vec_of_strings(vec![]);
+    array_of_strings([String::new(), String::new()]);
+    slice_of_options(&mut []);
+    vec_of_copies(vec![]);
+}
@@ -0,0 +1,38 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that, when an assignment `*x = val` through a `&mut`
+// reference fails region checking, the error says whether it is the
+// assigned value or the reference `x` that does not live long enough,
+// and that it points at the assignment that needs the value to live
+// long enough rather than at an earlier one.
+
+// compile-flags:-Znll -Zborrowck=mir -Zverbose
+
+fn value_too_short<'a, 'b>(x: &'a mut &'b u32, y: &'a u32) {
+    *x = y;
+    //~^ WARN not reporting region error due to -Znll
+    //~| ERROR free region `'a` does not outlive free region `'b`
+}
+
+fn reference_too_short<'a, 'b>(x: &'b mut &'b u32, y: &'a u32) {
+    *x = y;
+    //~^ WARN not reporting region error due to -Znll
+    //~| ERROR free region `'a` does not outlive free region `'b`
+}
+
+fn blame_the_later_assignment<'a, 'b>(x: &'a mut &'b u32, y: &'a u32, z: &'b u32) {
+    *x = z;
+    *x = y;
+    //~^ WARN not reporting region error due to -Znll
+    //~| ERROR free region `'a` does not outlive free region `'b`
+}
+
+fn main() { }
@@ -0,0 +1,36 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that when the only thing keeping a borrow alive is a later
+// explicit `drop(guard)`, the conflict error points at that call. An
+// implicit drop at the end of the scope gets no such note.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+struct Guard<'a>(&'a mut u32);
+
+impl<'a> Drop for Guard<'a> {
+    fn drop(&mut self) { }
+}
+
+fn explicit_drop() {
+    let mut x = 22;
+    let guard = Guard(&mut x);
+    let _r = &x; //~ ERROR cannot borrow `x` as immutable
+    drop(guard);
+}
+
+fn implicit_drop() {
+    let mut x = 22;
+    let _guard = Guard(&mut x);
+    let _r = &x; //~ ERROR cannot borrow `x` as immutable
+}
+
+fn main() { }
@@ -0,0 +1,25 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a borrow created inside the expansion of `write!` is
+// reported at the `write!` invocation, rather than somewhere inside
+// the definition of the macro.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+use std::fmt::Write;
+
+fn main() {
+    let mut buf = String::new();
+    write!(buf, "{}", {
+        buf.push('x'); //~ ERROR cannot borrow `buf` as mutable more than once
+        1
+    }).unwrap();
+}
@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that when a borrow held by a closure conflicts with a later
+// access, the error points at the closure holding the borrow, and at
+// the closure the access occurs in, if any.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn sibling_closures() {
+    let mut x = 0;
+    let mut first = || x += 1;
+    let mut second = || x += 2;
+    //~^ ERROR cannot borrow `x` as mutable more than once at a time [E0499]
+    first();
+    second();
+}
+
+fn fn_and_closure() {
+    let mut x = 0;
+    let mut inc = || x += 1;
+    let r = &mut x;
+    //~^ ERROR cannot borrow `x` as mutable more than once at a time [E0499]
+    inc();
+    *r += 1;
+}
+
+fn main() { }
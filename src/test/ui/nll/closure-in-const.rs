@@ -0,0 +1,45 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that closures defined in the initializers of constants and
+// statics borrow-check, together with the initializers themselves,
+// including when the constant belongs to a generic impl or trait
+// whose lifetime and type parameters the closure inherits.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+const ID: fn(&u32) -> &u32 = |x| x;
+static FIRST: fn(&u32, &u32) -> &u32 = |x, _| x;
+
+struct Wrapper<'a, T: 'a>(&'a T);
+
+impl<'a, T: 'a> Wrapper<'a, T> {
+    const C: fn(&u32) -> &u32 = |x| x;
+    const GET: fn(&Wrapper<'a, T>) -> &'a T = |w| w.0;
+}
+
+trait Pick<'a> {
+    const PICK: fn(&'a u32, &'a u32) -> &'a u32 = |x, _| x;
+}
+
+impl<'a> Pick<'a> for () {}
+
+fn main() {
+    let x = 22;
+    let y = 44;
+    assert_eq!(*ID(&x), 22);
+    assert_eq!(*FIRST(&x, &y), 22);
+    assert_eq!(*Wrapper::<'static, u8>::C(&x), 22);
+
+    let w = Wrapper(&y);
+    assert_eq!(*Wrapper::GET(&w), 44);
+    assert_eq!(*<() as Pick>::PICK(&x, &y), 22);
+}
@@ -0,0 +1,38 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a closure inside a default trait method can rely on a
+// region bound (`'a: 'b`) declared on the method, even though only
+// the closure needs it.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+use std::cell::Cell;
+
+trait Store {
+    fn store<'a, 'b>(&self, x: &'a u32, y: &Cell<&'b u32>)
+        where 'a: 'b, Self: Sized
+    {
+        let f = || y.set(x);
+        f();
+    }
+}
+
+struct S;
+
+impl Store for S {}
+
+fn main() {
+    let x = 1;
+    let c = Cell::new(&0);
+    S.store(&x, &c);
+    assert_eq!(*c.get(), 1);
+}
@@ -0,0 +1,40 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a closure which needs a type to outlive one of its
+// creator's lifetimes, but cannot prove it itself, leaves the proof
+// to its creator. Here only the creator knows that `T: 'a`, from the
+// implied bounds of its own signature, which the closure does not
+// share.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+use std::fmt::Debug;
+
+fn with_signature<'a, T, F>(x: Box<T>, op: F) -> Box<Debug + 'a>
+    where F: FnOnce(Box<T>) -> Box<Debug + 'a>
+{
+    op(x)
+}
+
+fn implied_bound<'a, T: Debug>(_: &'a T, x: Box<T>) -> Box<Debug + 'a> {
+    with_signature(x, |y| y)
+}
+
+fn nested_implied_bound<'a, T: Debug>(_: &'a T, x: Box<T>) -> Box<Debug + 'a> {
+    with_signature(x, |y| with_signature(y, |z| z))
+}
+
+fn main() {
+    let value = 22;
+    assert_eq!(format!("{:?}", implied_bound(&value, Box::new(value))), "22");
+    assert_eq!(format!("{:?}", nested_implied_bound(&value, Box::new(value))), "22");
+}
@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that, when a region of the creator is only mentioned by the
+// return type of a closure, a region error in the closure labels that
+// return type, or, if it is elided, the closure body.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn expect_sig<'a, F>(_anchor: &'a u32, f: F) -> F
+    where F: Fn(&u32) -> &'a u32
+{
+    f
+}
+
+fn explicit<'a>(_anchor: &'a u32) {
+    let _f = |x: &u32| -> &'a u32 { x };
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR borrowed data escapes the closure
+}
+
+fn elided<'a>(anchor: &'a u32) {
+    expect_sig(anchor, |x| x);
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR borrowed data escapes the closure
+}
+
+fn main() { }
@@ -0,0 +1,33 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that when data borrowed within a `move` closure has to outlive
+// `'static`, the error does not suggest making it a `move` closure:
+// it already owns its environment.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+use std::cell::Cell;
+
+fn foo<F>(_cell: Cell<&'static u32>, _f: F)
+where
+    F: for<'x> FnOnce(Cell<&'static u32>, Cell<&'x u32>),
+{
+}
+
+fn supply(cell: Cell<&'static u32>) {
+    foo(cell, move |cell_static, cell_x| {
+        //~^ WARNING not reporting region error due to -Znll
+        cell_static.set(cell_x.get()); // forces 'x: 'static, error in closure
+        //~^ ERROR borrowed data escapes the closure
+    });
+}
+
+fn main() {}
@@ -55,7 +55,7 @@ fn supply<'a, 'b, 'c>(cell_a: Cell<&'a u32>, cell_b: Cell<&'b u32>, cell_c: Cell
             let p = x.get();
             //~^ WARN not reporting region error due to -Znll
             demand_y(x, y, p)
-            //~^ ERROR free region `'_#5r` does not outlive free region `'_#6r`
+            //~^ ERROR free region `'1` does not outlive free region `'2`
         },
     );
 }
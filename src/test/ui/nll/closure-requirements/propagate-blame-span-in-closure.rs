@@ -0,0 +1,29 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that when the creator of a closure cannot satisfy the
+// requirements that the closure propagates to it, the error points
+// into the closure body, at the statement that gave rise to the
+// requirement, and not only at the closure expression as a whole.
+
+// compile-flags:-Znll -Zborrowck=mir -Zverbose
+
+use std::cell::Cell;
+
+fn test<'a, 'b>(cell_a: Cell<&'a u32>, cell_b: Cell<&'b u32>) {
+    let closure = || {
+        //~^ ERROR free region `'a` does not outlive free region `'b`
+        let x = cell_a.get();
+        cell_b.set(x); //~ WARNING not reporting region error due to -Znll
+    };
+    closure();
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that when a closure passes on to its creator the requirement
+// that a type outlive one of the creator's regions, and the creator
+// cannot prove it, the error points at the statement inside the
+// closure body that gave rise to the requirement.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+use std::fmt::Debug;
+
+fn with_signature<'a, T, F>(x: Box<T>, op: F) -> Box<Debug + 'a>
+    where F: FnOnce(Box<T>) -> Box<Debug + 'a>
+{
+    op(x)
+}
+
+fn no_bound<'a, T: Debug>(x: Box<T>) -> Box<Debug + 'a> {
+    with_signature(x, |y| {
+        //~^ ERROR the type `T` may not live long enough
+        let z = y;
+        z //~ WARNING not reporting region error due to -Znll
+    })
+}
+
+fn main() {}
@@ -50,7 +50,7 @@ fn supply<'a, 'b>(cell_a: Cell<&'a u32>, cell_b: Cell<&'b u32>) {
         // Only works if 'x: 'y:
         demand_y(x, y, x.get())
         //~^ WARN not reporting region error due to -Znll
-        //~| ERROR free region `'_#5r` does not outlive free region `'_#7r`
+        //~| ERROR free region `'2` does not outlive free region `'4`
     });
 }
 
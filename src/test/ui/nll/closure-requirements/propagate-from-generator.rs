@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the requirements a generator propagates to its creator
+// are enforced there, just like those of a closure, and that the
+// error points at the yield inside the generator that needs them.
+
+// compile-flags:-Znll -Zborrowck=mir -Zverbose
+
+#![feature(generators, generator_trait)]
+
+use std::cell::Cell;
+use std::ops::Generator;
+
+fn test<'a, 'b>(cell_a: Cell<&'a u32>, cell_b: Cell<&'b u32>) {
+    let mut gen = move || {
+        //~^ ERROR free region `'a` does not outlive free region `'b`
+        let x = cell_a.get();
+        yield cell_b.set(x); //~ WARNING not reporting region error due to -Znll
+    };
+    gen.resume();
+}
+
+fn main() {}
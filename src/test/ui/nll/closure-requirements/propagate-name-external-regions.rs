@@ -0,0 +1,39 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that when data borrowed within a closure has to outlive one
+// of its external regions, the error says that the data escapes the
+// closure and names that region after the lifetime of the enclosing
+// function (`'a` or `'b`) that it stands for.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+use std::cell::Cell;
+
+fn foo<'a, F>(_cell: Cell<&'a u32>, _f: F)
+where
+    F: for<'x> FnOnce(Cell<&'a u32>, Cell<&'x u32>),
+{
+}
+
+fn supply<'a, 'b>(cell_a: Cell<&'a u32>, cell_b: Cell<&'b u32>) {
+    foo(cell_a, |cell_a, cell_x| {
+        //~^ WARNING not reporting region error due to -Znll
+        cell_a.set(cell_x.get()); // forces 'x: 'a, error in closure
+        //~^ ERROR borrowed data escapes the closure
+    });
+    foo(cell_b, |cell_b, cell_x| {
+        //~^ WARNING not reporting region error due to -Znll
+        cell_b.set(cell_x.get()); // forces 'x: 'b, error in closure
+        //~^ ERROR borrowed data escapes the closure
+    });
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the requirements a closure propagates to its creator are
+// applied only once, where the closure is created, so that failing to
+// satisfy them is reported once no matter how often the closure is
+// used.
+
+// compile-flags:-Znll -Zborrowck=mir -Zverbose
+
+use std::cell::Cell;
+
+fn test<'a, 'b>(cell_a: Cell<&'a u32>, cell_b: Cell<&'b u32>) {
+    let closure = || {
+        //~^ ERROR free region `'a` does not outlive free region `'b`
+        let x = cell_a.get();
+        cell_b.set(x); //~ WARNING not reporting region error due to -Znll
+    };
+    closure();
+    closure();
+}
+
+fn main() {}
@@ -0,0 +1,59 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the requirements of a closure nested in another closure
+// reach the enclosing fn. The innermost closure needs `'a: 'b`, which
+// neither closure can prove, so the middle closure must forward the
+// requirement it gets from the innermost one to its own creator.
+
+// compile-flags:-Znll -Zborrowck=mir -Zverbose
+
+use std::cell::Cell;
+
+fn correct<'a: 'b, 'b>(cell_a: Cell<&'a u32>, cell_b: Cell<&'b u32>) {
+    let middle = || {
+        let inner = || {
+            let x = cell_a.get();
+            cell_b.set(x);
+        };
+        inner();
+    };
+    middle();
+}
+
+fn incorrect<'a, 'b>(cell_a: Cell<&'a u32>, cell_b: Cell<&'b u32>) {
+    let middle = || {
+        //~^ ERROR free region `'a` does not outlive free region `'b`
+        let inner = || {
+            let x = cell_a.get();
+            cell_b.set(x); //~ WARNING not reporting region error due to -Znll
+        };
+        inner();
+    };
+    middle();
+}
+
+// The innermost closure needs both `'a: 'b` and `'a: 'c`. The middle
+// closure must forward both requirements on `'a`, not just the first,
+// so the missing `'a: 'c` is still reported in the creator.
+fn second<'a: 'b, 'b, 'c>(cell_a: Cell<&'a u32>, cell_b: Cell<&'b u32>, cell_c: Cell<&'c u32>) {
+    let middle = || {
+        //~^ ERROR free region `'a` does not outlive free region `'c`
+        let inner = || {
+            let x = cell_a.get();
+            cell_b.set(x);
+            cell_c.set(x); //~ WARNING not reporting region error due to -Znll
+        };
+        inner();
+    };
+    middle();
+}
+
+fn main() {}
@@ -18,7 +18,7 @@
 fn foo<'a>(x: &'a u32) -> &'static u32 {
     &*x
         //~^ WARN not reporting region error due to -Znll
-        //~| ERROR free region `'_#1r` does not outlive free region `ReStatic`
+        //~| ERROR free region `'a` does not outlive free region `ReStatic`
 }
 
 fn main() { }
@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a closure may assume the bounds implied by the types of
+// its upvars, just like those implied by its parameters: capturing
+// a `&'a &'a T` implies `T: 'a` inside the closure.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+struct Wrap<'a, T: 'a>(&'a T);
+
+fn wrap<'a, T>(x: &'a T) -> Wrap<'a, T> {
+    Wrap(x)
+}
+
+fn with_captured_ref<'a, T>(x: &'a &'a T) -> usize {
+    let c = || {
+        let w: Wrap<'a, T> = wrap(*x);
+        ::std::mem::size_of_val(&w)
+    };
+    c()
+}
+
+fn main() {
+    let v = 22u32;
+    let r = &v;
+    assert_eq!(with_captured_ref(&r), ::std::mem::size_of::<&u32>());
+}
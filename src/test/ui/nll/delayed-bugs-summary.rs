@@ -0,0 +1,25 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Bugs delayed by the MIR borrow checker in a crate that has no errors
+// are reported together, with a note giving the number of bugs and the
+// first one for each category.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(rustc_attrs)]
+
+#[rustc_nll_delay_bug]
+fn first() { } //~ ERROR the MIR borrow checker delayed 2 bugs in 1 category
+
+#[rustc_nll_delay_bug]
+fn second() { }
+
+fn main() { }
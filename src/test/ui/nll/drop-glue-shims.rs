@@ -0,0 +1,53 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the shims built during translation -- here the drop
+// glue of a generic struct with a borrowing `Drop` impl, a `Clone`
+// shim for an array, and a fn-pointer shim -- can be run through the
+// NLL region inference, even though they have no HIR of their own.
+
+// compile-flags:-Znll -Zborrowck=mir -Znll-shims
+// must-compile-successfully
+
+use std::cell::Cell;
+
+struct Guard<'a, T: 'a> {
+    counter: &'a Cell<usize>,
+    value: T,
+}
+
+impl<'a, T> Drop for Guard<'a, T> {
+    fn drop(&mut self) {
+        self.counter.set(self.counter.get() + 1);
+    }
+}
+
+fn call<F: Fn(&u32) -> u32>(f: F, x: &u32) -> u32 {
+    f(x)
+}
+
+fn double(x: &u32) -> u32 {
+    *x * 2
+}
+
+fn main() {
+    let counter = Cell::new(0);
+    {
+        let _a = Guard { counter: &counter, value: 22u32 };
+        let _b = Guard { counter: &counter, value: vec![Guard { counter: &counter, value: () }] };
+    }
+    assert_eq!(counter.get(), 3);
+
+    let refs = [&1u32, &2u32];
+    let copies = refs.clone();
+    assert_eq!(*copies[1], 2);
+
+    assert_eq!(call(double as fn(&u32) -> u32, &21), 42);
+}
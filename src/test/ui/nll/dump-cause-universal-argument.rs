@@ -0,0 +1,23 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that `-Znll-dump-cause` explains why the universal regions
+// involved in an error are live, by pointing at the arguments whose
+// types mention them.
+
+// compile-flags:-Znll -Zborrowck=mir -Zverbose -Znll-dump-cause
+
+fn store<'a, 'b>(x: &'a u32, y: &mut &'b u32) {
+    *y = x;
+    //~^ WARN not reporting region error due to -Znll
+    //~| ERROR free region `'a` does not outlive free region `'b`
+}
+
+fn main() { }
@@ -0,0 +1,22 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that `-Znll-dump-cause` explains why a borrow that flows into
+// the return value has to outlive the body, by pointing at the
+// argument and the return type that mention its universal region.
+
+// compile-flags:-Znll -Zborrowck=mir -Zverbose -Znll-dump-cause
+
+fn local_ref<'a>(x: &'a u32) -> &'a u32 {
+    let y = *x;
+    &y //~ ERROR `y` does not live long enough [E0597]
+}
+
+fn main() { }
@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that `ReEmpty` in the constraints of a body is handled. The
+// hidden type of `gen` holds a reference across a yield; the region
+// of that reference is local to `gen`, so writeback replaces it with
+// the empty region. Proving `Send` for the `impl Trait` in `main`
+// looks at the hidden type, and matching the impl of `Send` for
+// `&T` equates a region variable with the empty region.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+#![feature(generators, generator_trait, conservative_impl_trait)]
+
+use std::ops::{Generator, GeneratorState};
+
+fn is_send<T: Send>(t: T) -> T {
+    t
+}
+
+fn gen() -> impl Generator<Yield = u32, Return = ()> {
+    || {
+        let x = 22;
+        let r = &x;
+        yield *r;
+    }
+}
+
+fn main() {
+    let mut g = is_send(gen());
+    match g.resume() {
+        GeneratorState::Yielded(x) => assert_eq!(x, 22),
+        GeneratorState::Complete(()) => panic!(),
+    }
+}
@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a region that is left unconstrained by trait selection,
+// and thus comes back as the empty region, does not make NLL choke
+// when it shows up in the constraints of the body.
+
+// compile-flags: -Z borrowck=mir -Z nll
+// must-compile-successfully
+
+trait Foo<'a> {
+    fn foo(&self) -> u32 { 22 }
+}
+
+impl<'a, T> Foo<'a> for T {}
+
+fn main() {
+    let x = 44;
+    assert_eq!(Foo::foo(&x), 22);
+}
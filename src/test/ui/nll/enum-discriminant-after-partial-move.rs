@@ -0,0 +1,46 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that moving out of the field of a variant still lets us read
+// the discriminant of the enum, but not use the enum as a whole, and
+// that the error then names the field that was moved.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn discriminant_only(opt: Option<String>) -> bool {
+    if let Some(s) = opt {
+        drop(s);
+    }
+    match opt {
+        Some(_) => true,
+        None => false,
+    }
+}
+
+fn full_use_after_partial_move(opt: Option<String>) -> Option<String> {
+    if let Some(s) = opt {
+        drop(s);
+    }
+    opt //~ ERROR use of moved value: `opt`
+}
+
+fn reinit_after_partial_move(mut opt: Option<String>) -> Option<String> {
+    if let Some(s) = opt {
+        drop(s);
+    }
+    opt = None;
+    opt
+}
+
+fn main() {
+    discriminant_only(None);
+    full_use_after_partial_move(None);
+    reinit_after_partial_move(None);
+}
@@ -0,0 +1,24 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that lifetimes made equal by a cycle of bounds are all named
+// after one representative, the first one declared, in errors.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn cycle<'a, 'b, 'c, 'd, T>(x: &'c T, _y: &'d T) -> &'d T
+    where 'a: 'b, 'b: 'c, 'c: 'a, T: 'c + 'd
+{
+    x
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'a` does not outlive free region `'d`
+}
+
+fn main() { }
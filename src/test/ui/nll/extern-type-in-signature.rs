@@ -0,0 +1,49 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that extern types in the signature of a function, or in the
+// upvars of a closure, do not confuse the computation of its
+// universal regions.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+#![feature(extern_types)]
+
+extern {
+    type Opaque;
+}
+
+struct Handle<'a> {
+    opaque: &'a Opaque,
+}
+
+fn id<'a>(x: &'a Opaque) -> &'a Opaque {
+    x
+}
+
+fn first<'a, 'b>(x: &'a Opaque, _y: &'b Opaque) -> Handle<'a> {
+    Handle { opaque: id(x) }
+}
+
+fn unwrap<'a>(h: Handle<'a>) -> &'a Opaque {
+    h.opaque
+}
+
+fn through_closure<'a>(x: &'a Opaque) -> &'a Opaque {
+    let get = || x;
+    get()
+}
+
+fn main() {
+    let _: for<'a, 'b> fn(&'a Opaque, &'b Opaque) -> Handle<'a> = first;
+    let _: for<'a> fn(Handle<'a>) -> &'a Opaque = unwrap;
+    let _: for<'a> fn(&'a Opaque) -> &'a Opaque = through_closure;
+}
@@ -0,0 +1,45 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that matching on a place, binding it with a `let` whose
+// pattern binds nothing, or capturing it by unique immutable borrow in
+// a closure, still counts as a shallow read of that place while it is
+// mutably borrowed.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn match_scrutinee() {
+    let mut x = 0;
+    let r = &mut x;
+    match x { _ => {} } //~ ERROR cannot use `x` because it was mutably borrowed
+    *r = 1;
+}
+
+fn let_wildcard() {
+    let mut y = 0;
+    let r = &mut y;
+    let _ = y; //~ ERROR cannot use `y` because it was mutably borrowed
+    *r = 1;
+}
+
+fn closure_capture() {
+    let mut v = 0;
+    let mut x = &mut v;
+    let r = &mut x;
+    let mut c = || *x = 1; //~ ERROR cannot use `x` because it was mutably borrowed
+    **r = 2;
+    c();
+}
+
+fn main() {
+    match_scrutinee();
+    let_wildcard();
+    closure_capture();
+}
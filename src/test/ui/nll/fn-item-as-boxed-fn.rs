@@ -0,0 +1,39 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a generic fn item with lifetime parameters, coerced to a
+// `Box<dyn Fn>` and stored inside another generic fn, is not mistaken
+// for a closure with region requirements to apply. Its free regions
+// would not line up with those of any closure.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+use std::fmt::Debug;
+
+fn longest<'a, 'b: 'a, T: Debug + ?Sized>(x: &'a T, _y: &'b T) -> &'a T {
+    x
+}
+
+fn store<'c, T: Debug + ?Sized + 'c>() -> Box<dyn Fn(&'c T, &'c T) -> &'c T + 'c> {
+    let f: Box<dyn Fn(&'c T, &'c T) -> &'c T + 'c> = Box::new(longest::<T>);
+    f
+}
+
+fn apply<'d, T: Debug + ?Sized>(x: &'d T, y: &'d T) -> String {
+    let f = store::<T>();
+    let closure = |a: &'d T| f(a, y);
+    format!("{:?}", closure(x))
+}
+
+fn main() {
+    assert_eq!(apply("a", "b"), "\"a\"");
+    assert_eq!(apply(&1, &2), "1");
+}
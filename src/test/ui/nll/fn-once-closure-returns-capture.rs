@@ -0,0 +1,33 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a `move` closure that gives away its captured data, and
+// hence is only `FnOnce`, takes its environment by value: there is
+// no reference to the environment whose region the returned data
+// would have to outlive.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+fn call_once<F: FnOnce() -> Vec<String>>(f: F) -> Vec<String> {
+    f()
+}
+
+fn first<'a>(v: &'a [String]) -> &'a str {
+    let give_back = move || v;
+    &give_back()[0]
+}
+
+fn main() {
+    let names = vec!["a".to_string(), "b".to_string()];
+    let c = move || names;
+    let names = call_once(c);
+    assert_eq!(first(&names), "a");
+}
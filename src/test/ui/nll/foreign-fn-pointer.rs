@@ -0,0 +1,60 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the regions in the signatures of foreign fns, which we
+// only ever see as values inside the body being checked, are treated
+// like those of any other fn item: calling the fn, or reifying it to
+// a fn pointer, does not tie them to the regions of the body.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+pub mod export {
+    #[no_mangle]
+    pub extern "C" fn nll_foreign_first(x: &u8, _y: &u8) -> u8 { *x }
+
+    #[no_mangle]
+    pub extern "C" fn nll_foreign_pick<'a>(x: &'a u8, _y: &u8) -> &'a u8 { x }
+}
+
+extern "C" {
+    fn nll_foreign_first(x: &u8, y: &u8) -> u8;
+    fn nll_foreign_pick<'a>(x: &'a u8, y: &u8) -> &'a u8;
+}
+
+fn call_directly(a: &u8) -> u8 {
+    let b = 2;
+    unsafe { nll_foreign_first(a, &b) }
+}
+
+fn call_through_pointer(a: &u8) -> u8 {
+    let f: unsafe extern "C" fn(&u8, &u8) -> u8 = nll_foreign_first;
+    let b = 2;
+    unsafe { f(&b, a) }
+}
+
+fn pick_through_pointer<'a>(a: &'a u8) -> &'a u8 {
+    let f: unsafe extern "C" fn(&'a u8, &u8) -> &'a u8 = nll_foreign_pick;
+    let b = 3;
+    unsafe { f(a, &b) }
+}
+
+fn compare_pointers() -> bool {
+    let f: for<'x> unsafe extern "C" fn(&'x u8, &u8) -> &'x u8 = nll_foreign_pick;
+    let g: unsafe extern "C" fn(&u8, &u8) -> &u8 = nll_foreign_pick;
+    f as usize == g as usize
+}
+
+fn main() {
+    assert_eq!(call_directly(&1), 1);
+    assert_eq!(call_through_pointer(&1), 2);
+    assert_eq!(*pick_through_pointer(&4), 4);
+    assert!(compare_pointers());
+}
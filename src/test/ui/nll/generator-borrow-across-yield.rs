@@ -0,0 +1,46 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a borrow of data owned by a generator body, held across
+// a `yield`, is reported at the borrow and the yield, and that one
+// that ends before the `yield` is accepted.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(generators)]
+#![allow(warnings)]
+
+fn held_across_yield() {
+    let _gen = || {
+        let x = 22;
+        let r = &x; //~ ERROR borrow may still be in use when the generator is suspended
+        yield;
+        println!("{}", r);
+    };
+}
+
+fn temporary_held_across_yield() {
+    let _gen = || {
+        let r = &String::new(); //~ ERROR borrow may still be in use when the generator is suspended
+        yield;
+        println!("{}", r);
+    };
+}
+
+fn ends_before_yield() {
+    let _gen = || {
+        let x = 22;
+        let r = &x;
+        println!("{}", r);
+        yield;
+    };
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that resuming a generator writes the result of the `yield`
+// into its destination, which conflicts with a borrow of the
+// destination that is still live.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(generators)]
+#![allow(warnings)]
+
+fn main() {
+    let mut x = ();
+    let captured = &mut x;
+    let mut g = move || {
+        let out = &mut *captured;
+        let p = &*out;
+        *out = yield 1; //~ ERROR cannot assign to `*out` because it is borrowed [E0506]
+        drop(p);
+    };
+}
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the regions bound by a higher-ranked fn pointer in the
+// signature of a fn are left alone when computing its universal
+// regions.
+
+// compile-flags: -Z borrowck=mir
+// must-compile-successfully
+
+fn f<'a>(g: for<'x> fn(&'x u32) -> &'x u32, d: &'a u32) -> &'a u32 {
+    g(d)
+}
+
+fn id(x: &u32) -> &u32 {
+    x
+}
+
+fn main() {
+    let d = 22;
+    assert_eq!(*f(id, &d), 22);
+}
@@ -0,0 +1,65 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the higher-ranked obligations and subtyping that the MIR
+// type-checker proves again, and which skolemize the bound regions,
+// are accepted under the MIR borrow checker. Unlike
+// `compile-fail/nll/hrtb-placeholder-regions.rs`, everything here is
+// well-typed, so borrow checking does run on these bodies.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+trait Foo<X> {
+    fn foo(&self, _x: X) { }
+}
+
+struct AnyInt;
+impl<'a> Foo<&'a u32> for AnyInt { }
+
+fn want_hrtb<T>(t: T, x: &u32)
+where
+    T: for<'a> Foo<&'a u32>,
+{
+    t.foo(x);
+}
+
+fn want_hrtb_closure<F>(f: F) -> u32
+where
+    F: for<'a> Fn(&'a u32) -> &'a u32,
+{
+    let v = 22;
+    *f(&v)
+}
+
+fn id<'a>(x: &'a u32) -> &'a u32 {
+    x
+}
+
+fn give_any(x: &u32) {
+    want_hrtb(AnyInt, x);
+}
+
+fn give_closure() -> u32 {
+    want_hrtb_closure(|x| x)
+}
+
+fn give_fn_ptr(x: &'static u32) -> u32 {
+    // A higher-ranked fn pointer is a subtype of each of its instances.
+    let f: for<'a> fn(&'a u32) -> &'a u32 = id;
+    let g: fn(&'static u32) -> &'static u32 = f;
+    *g(x)
+}
+
+fn main() {
+    give_any(&1);
+    assert_eq!(give_closure(), 22);
+    assert_eq!(give_fn_ptr(&44), 44);
+}
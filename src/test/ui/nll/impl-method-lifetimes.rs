@@ -0,0 +1,63 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that within methods, errors name the lifetimes of the impl
+// and those of the method correctly, and say which is which.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+struct Foo<'a> {
+    x: &'a u32,
+}
+
+impl<'a> Foo<'a> {
+    fn inherent<'m>(&self, y: &'m u32) -> &'m u32 {
+        self.x
+        //~^ WARNING not reporting region error due to -Znll
+        //~| ERROR free region `'a` does not outlive free region `'m`
+    }
+}
+
+trait Get<'t> {
+    fn get<'m>(&self, y: &'m u32) -> &'m u32;
+}
+
+impl<'a> Get<'a> for Foo<'a> {
+    fn get<'m>(&self, y: &'m u32) -> &'m u32 {
+        self.x
+        //~^ WARNING not reporting region error due to -Znll
+        //~| ERROR free region `'a` does not outlive free region `'m`
+    }
+}
+
+trait Relate<'t> {
+    fn shorten<'m>(&self, y: &'m u32) -> &'m u32 where 't: 'm;
+    fn widen<'m>(&self, y: &'m u32) -> &'t u32 where 't: 'm;
+}
+
+impl<'a> Relate<'a> for Foo<'a> {
+    // OK: the where-clause says that `'a` outlives `'m`.
+    fn shorten<'m>(&self, y: &'m u32) -> &'m u32 where 'a: 'm {
+        self.x
+    }
+
+    fn widen<'m>(&self, y: &'m u32) -> &'a u32
+    where
+        'a: 'm,
+    {
+        y
+        //~^ WARNING not reporting region error due to -Znll
+        //~| ERROR free region `'m` does not outlive free region `'a`
+    }
+}
+
+fn main() { }
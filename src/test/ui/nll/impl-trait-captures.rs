@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the hidden type of an `impl Trait` may only capture the
+// lifetimes that appear in its bounds.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(conservative_impl_trait)]
+#![allow(warnings)]
+
+use std::fmt::Debug;
+
+fn capture<'a>(x: &'a u32, y: &u32) -> impl Debug + 'a {
+    //~^ WARNING not reporting region error due to -Znll
+    (x, y)
+    //~^ ERROR hidden type for `impl Trait` captures lifetime that does not appear in bounds
+}
+
+fn ok<'a>(x: &'a u32, y: &'a u32) -> impl Debug + 'a {
+    (x, y)
+}
+
+fn main() {}
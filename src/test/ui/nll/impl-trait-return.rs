@@ -0,0 +1,33 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the regions captured by an `impl Trait` return type are
+// mapped to the universal regions of the fn that they stand for when
+// building the free regions of the fn.
+
+// compile-flags: -Z borrowck=mir -Z nll
+// must-compile-successfully
+
+#![feature(conservative_impl_trait)]
+
+fn capture<'a>(x: &'a u32) -> impl Fn() -> u32 + 'a {
+    move || *x
+}
+
+fn capture_two<'a, 'b: 'a>(x: &'a u32, y: &'b u32) -> impl Fn() -> u32 + 'a {
+    move || *x + *y
+}
+
+fn main() {
+    let x = 22;
+    let y = 44;
+    assert_eq!(capture(&x)(), 22);
+    assert_eq!(capture_two(&x, &y)(), 66);
+}
@@ -0,0 +1,46 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the where-clauses of the ADTs in the types of the
+// arguments are implied bounds, even when the ADT is nested in
+// another one or is an enum: each function here needs `T: 'a` to call
+// `require`, which it only knows from `Wrapper` (or `Either`).
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+struct Wrapper<'a, T: 'a>(&'a T);
+
+enum Either<'a, T: 'a> {
+    Left(Wrapper<'a, T>),
+    Right(Option<&'a T>),
+}
+
+fn require<'a, T: 'a>() { }
+
+fn unwrap<'a, T>(_: Wrapper<'a, T>) {
+    require::<'a, T>();
+}
+
+fn unwrap_all<'a, T>(_: Vec<Wrapper<'a, T>>) {
+    require::<'a, T>();
+}
+
+fn either<'a, T>(_: Either<'a, T>) {
+    require::<'a, T>();
+}
+
+fn main() {
+    let x = 22;
+    unwrap(Wrapper(&x));
+    unwrap_all(vec![Wrapper(&x), Wrapper(&x)]);
+    either(Either::Left(Wrapper(&x)));
+    either::<u32>(Either::Right(None));
+}
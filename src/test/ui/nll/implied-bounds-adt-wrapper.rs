@@ -0,0 +1,42 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// The wrapper pattern: a function that takes a `Wrapper<'a, T>`, even
+// nested in another ADT, knows `T: 'a` from the where-clause of
+// `Wrapper`, and need not repeat it. Without the implied bound, each
+// call to `require` below would be an error.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+#![allow(dead_code)]
+
+struct Wrapper<'a, T: 'a>(&'a T);
+
+enum Slot<'a, T: 'a> {
+    Full(Wrapper<'a, T>),
+    Empty,
+}
+
+fn require<'a, T: 'a>() { }
+
+fn wrapper<'a, T>(_: Wrapper<'a, T>) {
+    require::<'a, T>();
+}
+
+fn nested<'a, T>(_: Option<Vec<Wrapper<'a, T>>>) {
+    require::<'a, T>();
+}
+
+fn in_enum<'a, T>(_: &[Slot<'a, T>]) {
+    require::<'a, T>();
+}
+
+fn main() { }
@@ -0,0 +1,43 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the implied bounds of all the inputs of a fn, which are
+// computed together, each reach the right regions: here `T: 'a`
+// comes only from `t`, `'b: 'c` only from `u`, and several inputs
+// share a type. The same goes for the inputs of a closure.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+fn many<'a, 'b, 'c, T, U>(
+    t: &'a T,
+    first: &'c u32,
+    u: &'c &'b U,
+    second: &'c u32,
+) -> u32 {
+    let unused: Option<&'a T> = None;
+    let inner: &'c U = *u;
+    let _ = (t, unused, inner);
+    *first + *second
+}
+
+fn in_closure<'a, 'b>(x: &'a u32, y: &'b &'a u32) -> u32 {
+    let f = |p: &'a u32, q: &'b &'a u32, r: &'a u32| {
+        let inner: &'b u32 = *q;
+        *p + *inner + *r
+    };
+    f(x, y, x)
+}
+
+fn main() {
+    let u = 3u8;
+    assert_eq!(many(&1u16, &2, &&u, &4), 6);
+    assert_eq!(in_closure(&1, &&2), 4);
+}
@@ -0,0 +1,66 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that computing the implied bounds of a deeply nested
+// projection does not take time exponential in its depth. Each
+// `Xn<T>` mentions `Xn-1<T>` twice, once as the self type and once
+// as the parameter of `Two`, so that its well-formedness requires
+// that of `Xn-1<T>` twice over.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+trait Two<U> {
+    type Out;
+}
+
+struct Wrapper<'a, T: 'a>(&'a T);
+
+type X1<T> = <T as Two<T>>::Out;
+type X2<T> = <X1<T> as Two<X1<T>>>::Out;
+type X3<T> = <X2<T> as Two<X2<T>>>::Out;
+type X4<T> = <X3<T> as Two<X3<T>>>::Out;
+type X5<T> = <X4<T> as Two<X4<T>>>::Out;
+type X6<T> = <X5<T> as Two<X5<T>>>::Out;
+type X7<T> = <X6<T> as Two<X6<T>>>::Out;
+type X8<T> = <X7<T> as Two<X7<T>>>::Out;
+type X9<T> = <X8<T> as Two<X8<T>>>::Out;
+type X10<T> = <X9<T> as Two<X9<T>>>::Out;
+type X11<T> = <X10<T> as Two<X10<T>>>::Out;
+type X12<T> = <X11<T> as Two<X11<T>>>::Out;
+
+fn require<'a, T: 'a>() { }
+
+fn bomb<'a, T>(_: Wrapper<'a, T>, _: Option<X12<T>>)
+where
+    T: Two<T>,
+    X1<T>: Two<X1<T>>,
+    X2<T>: Two<X2<T>>,
+    X3<T>: Two<X3<T>>,
+    X4<T>: Two<X4<T>>,
+    X5<T>: Two<X5<T>>,
+    X6<T>: Two<X6<T>>,
+    X7<T>: Two<X7<T>>,
+    X8<T>: Two<X8<T>>,
+    X9<T>: Two<X9<T>>,
+    X10<T>: Two<X10<T>>,
+    X11<T>: Two<X11<T>>,
+{
+    require::<'a, T>();
+}
+
+impl Two<u32> for u32 {
+    type Out = u32;
+}
+
+fn main() {
+    let x = 22;
+    bomb::<u32>(Wrapper(&x), None);
+}
@@ -0,0 +1,39 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that normalizing a projection in the signature while
+// computing the implied bounds, which instantiates the regions of the
+// impl with fresh variables, does not disturb the universal regions.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+trait Trait {
+    type Out;
+}
+
+impl<'a, T: 'a> Trait for &'a T {
+    type Out = &'a T;
+}
+
+fn id<'x>(x: <&'x u32 as Trait>::Out) -> &'x u32 {
+    x
+}
+
+fn both<'x, 'y: 'x>(x: <&'x u32 as Trait>::Out, y: <&'y u32 as Trait>::Out) -> &'x u32 {
+    if *x > *y { x } else { y }
+}
+
+fn main() {
+    let a = 22;
+    let b = 44;
+    assert_eq!(*id(&a), 22);
+    assert_eq!(*both(&a, &b), 44);
+}
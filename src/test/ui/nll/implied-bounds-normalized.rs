@@ -0,0 +1,47 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the implied bounds of an argument whose type is an
+// associated type are those of its normalized form: here, that an
+// argument of type `<Option<T> as Trait<'a>>::Assoc`, i.e. `&'a T`,
+// implies `T: 'a`. `wrap` has no other source of that bound, but its
+// body needs it to call `require`.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+trait Trait<'a> {
+    type Assoc;
+}
+
+impl<'a> Trait<'a> for &'a u32 {
+    type Assoc = &'a u32;
+}
+
+impl<'a, T: 'a> Trait<'a> for Option<T> {
+    type Assoc = &'a T;
+}
+
+fn get<'a>(x: <&'a u32 as Trait<'a>>::Assoc) -> &'a u32 {
+    x
+}
+
+fn require<'a, T: 'a>() { }
+
+fn wrap<'a, T>(x: <Option<T> as Trait<'a>>::Assoc) -> bool {
+    require::<'a, T>();
+    Some(x).is_some()
+}
+
+fn main() {
+    let value = 22;
+    assert_eq!(*get(&value), 22);
+    assert!(wrap::<u32>(&value));
+}
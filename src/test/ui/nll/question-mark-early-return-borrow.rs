@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that when a borrow is kept alive because the error that a `?`
+// returns early holds on to it, the conflict error points at the `?`.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn first_zero(v: &Vec<u32>) -> Result<(), &u32> {
+    match v.first() {
+        Some(x) if *x == 0 => Err(x),
+        _ => Ok(()),
+    }
+}
+
+fn push_checked(v: &mut Vec<u32>, x: u32) -> Result<(), &u32> {
+    first_zero(v)?;
+    v.push(x); //~ ERROR cannot borrow `*v` as mutable
+    Ok(())
+}
+
+fn push_unchecked(v: &mut Vec<u32>, x: u32) -> Result<(), &u32> {
+    let _ = first_zero(v).is_ok();
+    v.push(x);
+    Ok(())
+}
+
+fn main() { }
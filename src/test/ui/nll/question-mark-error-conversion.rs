@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that when the conversion of the error returned by a `?`
+// requires it to live longer, the region error points at the `?`.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+struct ParseError(&'static str);
+
+impl From<&'static str> for ParseError {
+    fn from(msg: &'static str) -> Self {
+        ParseError(msg)
+    }
+}
+
+fn check(input: &str) -> Result<(), &str> {
+    if input.is_empty() { Err(input) } else { Ok(()) }
+}
+
+fn parse(input: &str) -> Result<u32, ParseError> {
+    check(input)?;
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'1` does not outlive free region `'static`
+    Ok(0)
+}
+
+fn main() { }
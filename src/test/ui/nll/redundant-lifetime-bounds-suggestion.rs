@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check the suggestion of the `redundant_lifetime_bounds` lint to
+// remove a bound that the argument types already imply, and that a
+// bound they do not imply is not linted.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![warn(redundant_lifetime_bounds)]
+#![allow(dead_code)]
+
+// `'b: 'a` is implied by the type of `x`.
+fn implied_in_where_clause<'a, 'b, T>(_x: &'a &'b T) where T: Copy, 'b: 'a {}
+//~^ WARNING implied by other bounds
+
+// The argument types imply nothing about `'a` and `'b`.
+fn not_implied<'a, 'b>(x: &'a u32, y: &'b u32) -> &'a u32 where 'b: 'a {
+    if *x > *y { x } else { y }
+}
+
+fn main() {}
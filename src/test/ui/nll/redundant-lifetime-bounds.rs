@@ -0,0 +1,37 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check the `redundant_lifetime_bounds` lint: a declared lifetime
+// bound that follows from the other bounds, or from the types of the
+// arguments, is linted; a bound that is actually needed is not.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![deny(redundant_lifetime_bounds)]
+#![allow(dead_code)]
+
+// `'a: 'c` follows from the other two bounds.
+fn transitive<'a, 'b, 'c>(_x: &'a u32, _y: &'b u32, _z: &'c u32)
+where
+    'a: 'b,
+    'b: 'c,
+    'a: 'c, //~ ERROR implied by other bounds
+{
+}
+
+// `'b: 'a` is implied by the type of `x`.
+fn implied_by_argument<'a, 'b: 'a>(_x: &'a &'b u32) {} //~ ERROR implied by other bounds
+
+// Nothing else says that `'a: 'b`, so this bound is needed.
+fn needed<'a: 'b, 'b>(x: &'a u32) -> &'b u32 {
+    x
+}
+
+fn main() {}
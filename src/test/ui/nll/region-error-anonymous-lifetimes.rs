@@ -0,0 +1,40 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the anonymous lifetimes of a signature are called `'1`,
+// `'2`, etc. in region errors, numbered in the order in which the
+// parameters mention them, and that the reference whose lifetime is
+// meant is pointed out.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+struct Store;
+
+impl Store {
+    fn choose(&self, x: &u32) -> &u32 {
+        x
+        //~^ WARNING not reporting region error due to -Znll
+        //~| ERROR free region `'2` does not outlive free region `'1`
+    }
+}
+
+fn push(v: &mut Vec<&u32>, x: &u32) {
+    v.push(x);
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'3` does not outlive free region `'2`
+}
+
+fn mixed<'a>(x: &'a u32, v: &mut Vec<&u32>) {
+    v.push(x);
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'a` does not outlive free region `'2`
+}
+
+fn main() { }
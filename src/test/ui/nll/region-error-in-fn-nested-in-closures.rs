@@ -0,0 +1,31 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a region error in a fn nested two closures deep names the
+// lifetimes of the nested fn, and not those of the enclosing item.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn outer<'x, 'y>(x: &'x u32, y: &'y u32) {
+    let f = || {
+        let g = || {
+            fn nested<'a, 'b, T>(_x: &'a T, y: &'b T) -> &'a T where T: 'a + 'b {
+                y
+                //~^ WARNING not reporting region error due to -Znll
+                //~| ERROR free region `'b` does not outlive free region `'a`
+            }
+            nested(x, y)
+        };
+        g()
+    };
+    f();
+}
+
+fn main() { }
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a region error points at the parameter whose type
+// mentions the shorter of the two regions, naming the parameter if
+// it is a simple binding.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn first<'a, 'b>(x: &'a u32, y: &'b u32) -> &'b u32 {
+    x
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'a` does not outlive free region `'b`
+}
+
+fn second<'a, 'b>(x: &'b u32, (y, _): (&'a u32, u32)) -> &'b u32 {
+    y
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'a` does not outlive free region `'b`
+}
+
+fn main() { }
@@ -0,0 +1,22 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that, when the longer region of a region error is only
+// mentioned by the return type, the return type is labeled.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn get<'a, 'b>(x: &'b str) -> &'a str {
+    x
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'b` does not outlive free region `'a`
+}
+
+fn main() { }
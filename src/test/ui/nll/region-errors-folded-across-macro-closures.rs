@@ -0,0 +1,32 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the region errors of identical closures stamped out by a
+// macro are reported once, with a note saying how many were folded.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(unused_variables)]
+
+use std::cell::Cell;
+
+macro_rules! setters {
+    ($($name:ident),*) => {
+        $( let $name = |x: &u32, cell: &Cell<&u32>| cell.set(x); )*
+        //~^ WARNING not reporting region error due to -Znll
+        //~| WARNING not reporting region error due to -Znll
+        //~| WARNING not reporting region error due to -Znll
+        //~| ERROR free region `'1` does not outlive free region `'3`
+    };
+}
+
+fn main() {
+    setters!(first, second, third);
+}
@@ -0,0 +1,58 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that region-free types are not folded needlessly, but that
+// the types the type flags do not tell apart from them -- those that
+// mention only `'static`, or only regions bound within them -- still
+// are. `-Z nll-verify-fold-fast-path` checks the former.
+
+// compile-flags:-Znll -Zborrowck=mir -Znll-verify-fold-fast-path
+// must-compile-successfully
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Sign {
+    Negative,
+    Zero,
+    Positive,
+}
+
+fn sign(x: i64) -> Sign {
+    if x < 0 {
+        Sign::Negative
+    } else if x == 0 {
+        Sign::Zero
+    } else {
+        Sign::Positive
+    }
+}
+
+fn sum(xs: &[u32]) -> u64 {
+    let mut total = 0u64;
+    for i in 0..xs.len() {
+        total += xs[i] as u64;
+    }
+    total
+}
+
+static NAMES: [&'static str; 3] = ["negative", "zero", "positive"];
+
+fn name(sign: Sign) -> &'static str {
+    NAMES[sign as usize]
+}
+
+fn apply(f: for<'a> fn(&'a [u32]) -> u64, xs: &[u32]) -> u64 {
+    f(xs)
+}
+
+fn main() {
+    assert_eq!(sign(-3), Sign::Negative);
+    assert_eq!(name(sign(0)), "zero");
+    assert_eq!(apply(sum, &[1, 2, 3]), 6);
+}
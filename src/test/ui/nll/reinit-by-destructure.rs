@@ -0,0 +1,46 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that fully reinitializing a previously borrowed local -- be it
+// through a whole assignment or through a sequence of field
+// assignments -- kills the borrows of its old value, so that a later
+// mutable borrow does not conflict with them.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+struct Pair(u32, u32);
+
+fn reinit_whole() {
+    let mut p = Pair(0, 0);
+    for i in 0..3 {
+        let r = &p;
+        assert_eq!(r.0, i);
+        p = Pair(i + 1, i + 1);
+        let m = &mut p;
+        m.1 += 1;
+    }
+}
+
+fn reinit_by_destructure() {
+    let mut t = (1, 2);
+    let r = &t;
+    let (a, b) = *r;
+    t.0 = b;
+    t.1 = a;
+    let m = &mut t;
+    m.0 += 1;
+    assert_eq!(t, (3, 1));
+}
+
+fn main() {
+    reinit_whole();
+    reinit_by_destructure();
+}
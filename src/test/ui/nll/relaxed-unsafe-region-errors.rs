@@ -0,0 +1,46 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that, under `-Znll-relaxed-unsafe`, a region error that is
+// entirely due to code within an `unsafe` block is reported through
+// the `unsafe_region_errors` lint, which can be allowed, whereas one
+// that is partly due to code outside of it remains a hard error.
+
+// compile-flags:-Znll -Zborrowck=mir -Znll-relaxed-unsafe
+
+#![allow(dead_code)]
+
+struct Pair<'a, 'b> {
+    x: &'a u32,
+    y: &'b u32,
+}
+
+impl<'a, 'b> Pair<'a, 'b> {
+    fn within_unsafe(&self) -> &'a u32 {
+        unsafe { self.y }
+        //~^ WARNING not reporting region error due to -Znll
+        //~| ERROR free region `'b` does not outlive free region `'a`
+    }
+
+    fn partly_within_unsafe(&self) -> &'a u32 {
+        let y = self.y;
+        //~^ WARNING not reporting region error due to -Znll
+        unsafe { y }
+        //~^ ERROR free region `'b` does not outlive free region `'a`
+    }
+
+    #[allow(unsafe_region_errors)]
+    fn within_unsafe_allowed(&self) -> &'a u32 {
+        unsafe { self.y }
+        //~^ WARNING not reporting region error due to -Znll
+    }
+}
+
+fn main() {}
@@ -0,0 +1,38 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a local dropped while still borrowed by a local declared
+// before it in the same block is reported as a single error, with a
+// suggestion to reorder the declarations when their initializers are
+// independent.
+
+// compile-flags: -Znll -Zborrowck=mir
+
+#![allow(warnings)]
+
+struct Foo<'x>(Option<&'x u32>, u32);
+
+impl<'x> Drop for Foo<'x> {
+    fn drop(&mut self) {}
+}
+
+fn reorderable() {
+    let mut a = Foo(None, 0);
+    let b = 22;
+    a.0 = Some(&b);
+} //~ ERROR `b` does not live long enough
+
+fn not_reorderable() {
+    let mut a = Foo(None, 22);
+    let b = a.1;
+    a.0 = Some(&b);
+} //~ ERROR `b` does not live long enough
+
+fn main() { }
@@ -0,0 +1,46 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the MIR borrow checker matches the inputs of a fn item
+// with the `rust-call` ABI, here a hand-written `FnOnce` impl, with
+// its arguments: the body takes its tupled arguments as one argument,
+// so unlike for a closure, the tuple must not be flattened.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+#![feature(fn_traits, unboxed_closures)]
+
+struct Prefix<'a> {
+    prefix: &'a str,
+}
+
+impl<'a, 'b> FnOnce<(&'b str, usize)> for Prefix<'a> {
+    type Output = String;
+
+    extern "rust-call" fn call_once(self, args: (&'b str, usize)) -> String {
+        let (name, count) = args;
+        let mut result = String::from(self.prefix);
+        for _ in 0..count {
+            result.push_str(name);
+        }
+        result
+    }
+}
+
+fn apply<F: FnOnce(&str, usize) -> String>(f: F) -> String {
+    f("ab", 2)
+}
+
+fn main() {
+    let prefix = String::from("> ");
+    assert_eq!(apply(Prefix { prefix: &prefix }), "> abab");
+    assert_eq!(Prefix { prefix: &prefix }.call_once(("c", 3)), "> ccc");
+}
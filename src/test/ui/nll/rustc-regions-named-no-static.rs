@@ -0,0 +1,24 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the regions declared with a name are just the lifetime
+// parameters of the fn: `'static`, although a universal region with a
+// name, is not among them.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(rustc_attrs)]
+
+#[rustc_regions(named)]
+fn identity<'a>(x: &'a u32) -> &'a u32 {
+    x
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that unknown or malformed sections in `#[rustc_regions]` are
+// reported.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(rustc_attrs)]
+
+#[rustc_regions(requirements, bogus, values(3))]
+fn foo() {}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test the output of `-Znll-signature-borrows-report`: the borrow in
+// `get` is returned, and so has to outlive `'a`, whereas the one in
+// `owned` ends within the body.
+
+// compile-flags:-Znll -Zborrowck=mir -Znll-signature-borrows-report
+// must-compile-successfully
+
+#![allow(dead_code)]
+
+fn get<'a>(pair: &'a (u32, u32)) -> &'a u32 {
+    &pair.0
+}
+
+fn owned(pair: &(u32, u32)) -> u32 {
+    let second = &pair.1;
+    *second
+}
+
+fn main() {}
@@ -0,0 +1,48 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the initializers of constants and statics are checked
+// against the declared type of the item, in which `'static` is the
+// universal `'static` region, including when the value comes out of
+// a const fn whose result is tied to the region of its argument.
+
+// compile-flags: -Z borrowck=mir -Z nll
+// must-compile-successfully
+
+#![feature(const_fn)]
+
+struct Foo<'a> {
+    x: &'a u32,
+}
+
+const fn id<'a>(x: &'a u32) -> &'a u32 {
+    x
+}
+
+const fn first<'a, 'b>(x: &'a u32, _y: &'b u32) -> &'a u32 {
+    x
+}
+
+static A: &'static u32 = id(&22);
+static B: &'static Foo<'static> = &Foo { x: first(&1, &2) };
+const C: [u32; 3] = [0; 3];
+
+struct Holder<'a>(&'a u32);
+
+impl<'a> Holder<'a> {
+    const SHORT: &'a u32 = &44;
+}
+
+fn main() {
+    assert_eq!(*A, 22);
+    assert_eq!(*B.x, 1);
+    assert_eq!(C.len(), 3);
+    assert_eq!(*Holder::SHORT, 44);
+}
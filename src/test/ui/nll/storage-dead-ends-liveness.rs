@@ -0,0 +1,35 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a local declared in an inner scope is not live past the
+// end of that scope, even when it is only conditionally initialized
+// in a loop: its `StorageDead` ends its liveness, so the borrow it
+// holds does not conflict with the uses of the borrowed data after
+// the scope or in the next iteration.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+fn main() {
+    let mut x = 0;
+    let mut i = 0;
+    while i < 4 {
+        {
+            let p;
+            if i % 2 == 0 {
+                p = &mut x;
+                *p += 1;
+            }
+        }
+        x += 10;
+        i += 1;
+    }
+    assert_eq!(x, 42);
+}
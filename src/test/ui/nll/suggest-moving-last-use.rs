@@ -0,0 +1,43 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that we suggest moving the last use of a mutable borrow
+// before a conflicting shared borrow, but only when that last use
+// does not depend on anything in between, and does not write through
+// the borrow (which would change what the shared borrow observes).
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn read(x: &u32) -> u32 { *x }
+
+fn movable() {
+    let mut x = 0;
+    let y;
+    let r = &mut x;
+    read(&x); //~ ERROR cannot borrow `x` as immutable
+    y = *r;
+    read(&y);
+}
+
+fn writes_through_borrow() {
+    let mut x = 0;
+    let r = &mut x;
+    read(&x); //~ ERROR cannot borrow `x` as immutable
+    *r = 1;
+}
+
+fn depends_on_result() {
+    let mut x = 0;
+    let r = &mut x;
+    let y = read(&x); //~ ERROR cannot borrow `x` as immutable
+    *r = y;
+}
+
+fn main() { }
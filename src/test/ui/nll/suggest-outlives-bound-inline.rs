@@ -0,0 +1,46 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that, absent a where-clause, we suggest adding a missing bound
+// between two named lifetimes to the declaration of the longer one
+// (here `impl<'a, 'b: 'a>`), appending to any bounds it already has.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![allow(dead_code)]
+
+struct Pair<'a, 'b> {
+    x: &'a u32,
+    y: &'b u32,
+}
+
+impl<'a, 'b> Pair<'a, 'b> {
+    fn second(&self) -> &'a u32 {
+        self.y
+        //~^ WARNING not reporting region error due to -Znll
+        //~| ERROR free region `'b` does not outlive free region `'a`
+    }
+}
+
+struct Triple<'a, 'b, 'c> {
+    x: &'a u32,
+    y: &'b u32,
+    z: &'c u32,
+}
+
+impl<'a, 'b, 'c: 'b> Triple<'a, 'b, 'c> {
+    fn third(&self) -> &'a u32 {
+        self.z
+        //~^ WARNING not reporting region error due to -Znll
+        //~| ERROR free region `'c` does not outlive free region `'a`
+    }
+}
+
+fn main() { }
@@ -0,0 +1,29 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that we suggest adding a missing bound between two named
+// lifetimes to the existing where-clause, except for a bound on
+// `'static`, which goes on the declaration of the lifetime instead.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn pick<'a, 'b, T>(_x: &'a T, y: &'b T) -> &'a T where T: 'a + 'b {
+    y
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'b` does not outlive free region `'a`
+}
+
+fn to_static<'a, T>(x: &'a T) -> &'static T where T: 'static + 'a {
+    x
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'a` does not outlive free region `'static`
+}
+
+fn main() { }
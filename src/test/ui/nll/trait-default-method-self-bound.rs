@@ -0,0 +1,36 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the default body of a trait method can use the bounds
+// on `Self` -- here the supertrait bound `Self: 'a` -- to prove that
+// `Self` outlives the trait's lifetime parameter.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+struct Holder<'a, U: ?Sized + 'a>(&'a U);
+
+fn hold<'a, U: ?Sized + 'a>(u: &'a U) -> Holder<'a, U> {
+    Holder(u)
+}
+
+trait T<'a>: 'a {
+    fn m(&'a self) -> &'a Self {
+        let h: Holder<'a, Self> = hold(self);
+        h.0
+    }
+}
+
+impl<'a> T<'a> for u32 {}
+
+fn main() {
+    let v = 22u32;
+    assert_eq!(*v.m(), 22);
+}
@@ -0,0 +1,49 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that when a region has to outlive `'static` because of the
+// implicit `'static` bound of a trait object type in the type of an
+// argument, the error points at that type, as it does for the return
+// type (see `trait-object-default-static.rs`).
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(dyn_trait)]
+#![allow(warnings)]
+
+use std::fmt::Debug;
+
+fn store(x: &u32, slot: &mut Box<dyn Debug>) {
+    *slot = Box::new(x);
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'1` does not outlive free region `'static`
+}
+
+fn store_named<'a>(x: &'a u32, slot: &mut Box<dyn Debug>) {
+    *slot = Box::new(x);
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'a` does not outlive free region `'static`
+}
+
+fn store_bounded<'a>(x: &'a u32, slot: &mut Box<dyn Debug + 'a>) {
+    // With the bound written out, this is fine.
+    *slot = Box::new(x);
+}
+
+fn store_second(x: &u32, first: &mut Box<dyn Debug>, second: &mut Box<dyn Debug>) {
+    // Only `second` is related to `'static` where `x` is stored, so
+    // the error points at its trait object, not at that of `first`.
+    std::mem::swap(first, second);
+    *second = Box::new(x);
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'1` does not outlive free region `'static`
+}
+
+fn main() { }
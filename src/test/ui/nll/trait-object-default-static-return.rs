@@ -0,0 +1,41 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that when a region has to outlive `'static` because of the
+// implicit `'static` bound of a boxed trait object nested in the
+// return type, the error points at that trait object type, as it does
+// for an argument (see `trait-object-default-static-argument.rs`).
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(dyn_trait)]
+#![feature(underscore_lifetimes)]
+#![allow(warnings)]
+
+use std::fmt::Debug;
+
+fn some(x: &u32) -> Option<Box<dyn Debug>> {
+    Some(Box::new(x))
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'1` does not outlive free region `'static`
+}
+
+fn some_named<'a>(x: &'a u32) -> Option<Box<dyn Debug>> {
+    Some(Box::new(x))
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'a` does not outlive free region `'static`
+}
+
+fn some_bounded<'a>(x: &'a u32) -> Option<Box<dyn Debug + 'a>> {
+    // With the bound written out, this is fine.
+    Some(Box::new(x))
+}
+
+fn main() { }
@@ -0,0 +1,53 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that when a region has to outlive `'static` because of the
+// implicit `'static` bound of a trait object type, the error points
+// at that type.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+#![feature(dyn_trait)]
+#![feature(underscore_lifetimes)]
+#![allow(warnings)]
+
+use std::fmt::Debug;
+
+struct Holder {
+    value: Box<dyn Debug>,
+}
+
+fn hold(x: &u32) -> Holder {
+    Holder { value: Box::new(x) }
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'1` does not outlive free region `'static`
+}
+
+fn boxed(x: &u32) -> Box<dyn Debug> {
+    Box::new(x)
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'1` does not outlive free region `'static`
+}
+
+fn boxed_named<'a>(x: &'a u32) -> Box<dyn Debug> {
+    Box::new(x)
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'a` does not outlive free region `'static`
+}
+
+// With two reference parameters, `'_` in the return type would be an
+// elision error, so there is nothing to suggest.
+fn boxed_either(x: &u32, y: &u32) -> Box<dyn Debug> {
+    Box::new(x)
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'1` does not outlive free region `'static`
+}
+
+fn main() { }
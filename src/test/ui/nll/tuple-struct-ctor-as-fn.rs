@@ -0,0 +1,51 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the synthesized bodies of the constructors of tuple
+// structs and tuple variants with lifetime parameters borrow-check,
+// which they do once they are used as fn values.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+struct Foo<'a>(&'a u32);
+
+enum Either<'a, 'b> {
+    Left(&'a u32),
+    Right(&'b u32),
+}
+
+fn wrap_all<'a>(xs: &'a [u32]) -> Vec<Foo<'a>> {
+    xs.iter().map(Foo).collect()
+}
+
+fn apply<'a, F: Fn(&'a u32) -> Foo<'a>>(f: F, x: &'a u32) -> Foo<'a> {
+    f(x)
+}
+
+fn main() {
+    let xs = [1, 2, 3];
+    let foos = wrap_all(&xs);
+    assert_eq!(foos.iter().map(|foo| *foo.0).sum::<u32>(), 6);
+
+    let x = 22;
+    assert_eq!(*apply(Foo, &x).0, 22);
+
+    let left = Either::Left;
+    match left(&x) {
+        Either::Left(y) => assert_eq!(*y, 22),
+        Either::Right(_) => panic!(),
+    }
+    let rights: Vec<Either> = xs.iter().map(Either::Right).collect();
+    match rights[2] {
+        Either::Right(y) => assert_eq!(*y, 3),
+        Either::Left(_) => panic!(),
+    }
+}
@@ -0,0 +1,26 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a tuple struct constructed through its constructor used
+// as a fn value cannot hold a borrow that does not live long enough.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+struct Foo<'a>(&'a u32);
+
+fn too_short<'a>() -> Foo<'a> {
+    let x = 22;
+    let f = Foo;
+    f(&x) //~ ERROR `x` does not live long enough [E0597]
+}
+
+fn main() {
+    too_short();
+}
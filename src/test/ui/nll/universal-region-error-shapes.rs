@@ -0,0 +1,27 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a borrow living too long and a missing bound between two
+// universal regions in the same function get their own wording: the
+// former is reported as "does not live long enough", the latter as a
+// missing bound, with a suggestion.
+
+// compile-flags:-Znll -Zborrowck=mir
+
+fn both<'a, 'b>(x: &'a u32, v: &mut Vec<&'b u32>) -> &'b u32 {
+    let local = 22;
+    v.push(&local);
+    //~^ ERROR `local` does not live long enough [E0597]
+    x
+    //~^ WARNING not reporting region error due to -Znll
+    //~| ERROR free region `'a` does not outlive free region `'b`
+}
+
+fn main() { }
@@ -0,0 +1,56 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that bodies whose signatures mention no regions get the same
+// universal regions from the fast path as from the full computation.
+// The flag makes NLL take the slow path and assert that it agrees.
+
+// compile-flags: -Znll -Zborrowck=mir -Znll-verify-universal-regions-fast-path
+// must-compile-successfully
+
+fn add(x: u32, y: u32) -> u32 {
+    x + y
+}
+
+fn sum(v: Vec<u64>) -> u64 {
+    let mut total = 0;
+    for x in &v {
+        total += *x;
+    }
+    total
+}
+
+fn pick<T: Clone>(a: T, b: T, first: bool) -> T {
+    if first { a } else { b.clone() }
+}
+
+fn with_closure(n: i32) -> i32 {
+    let double = |x: i32| x * 2;
+    let offset = move |x: i32| x + n;
+    offset(double(n))
+}
+
+// Not region-free: these still take the full path.
+fn static_str() -> &'static str {
+    "static"
+}
+
+fn bounded<T: 'static>(t: T) -> T {
+    t
+}
+
+fn main() {
+    assert_eq!(add(1, 2), 3);
+    assert_eq!(sum(vec![1, 2, 3]), 6);
+    assert_eq!(pick(1, 2, false), 2);
+    assert_eq!(with_closure(3), 9);
+    assert_eq!(static_str(), "static");
+    assert_eq!(bounded(5), 5);
+}
@@ -0,0 +1,30 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a function that forwards to a variadic foreign function,
+// passing along borrowed data, borrow-checks cleanly under MIR
+// borrowck.
+
+// compile-flags:-Znll -Zborrowck=mir
+// must-compile-successfully
+
+// The foreign fn is only declared, so the test is built as an rlib,
+// which does not link it.
+#![crate_type = "rlib"]
+
+extern {
+    fn rust_interesting_average(_: u64, ...) -> f64;
+}
+
+pub fn average_of_pairs<'a>(pairs: &'a [(i64, f64); 2]) -> f64 {
+    let (ref x1, ref x2) = pairs[0];
+    let (x3, x4) = pairs[1];
+    unsafe { rust_interesting_average(2, *x1, *x2, x3, x4) }
+}
@@ -49,6 +49,7 @@ pub mod common;
 pub mod errors;
 mod raise_fd_limit;
 mod read2;
+mod region_requirements;
 
 fn main() {
     env_logger::init().unwrap();
@@ -0,0 +1,164 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parser for the textual form of closure region requirements that
+//! the NLL MIR dump writes under `| Free Region Constraints`:
+//!
+//! ```text
+//! external vids: 3
+//! '_#1r: '_#2r @ $DIR/foo.rs:12:5: 12:10
+//! ```
+//!
+//! It is used to check that the expected lines of a mir-opt reference
+//! are well formed before they are compared against the dump, so that
+//! a typo in a reference file is reported as such rather than as a
+//! mismatch.
+
+/// The header line that introduces the requirements in the dump.
+pub const HEADER: &str = "Free Region Constraints";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Requirement {
+    pub free_region: usize,
+    pub outlived_free_region: usize,
+    pub blame_span: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegionRequirements {
+    pub num_external_vids: usize,
+    pub requirements: Vec<Requirement>,
+}
+
+/// Parses the lines that follow the header, without their leading
+/// `| `. The requirement lines must be sorted, and must only name
+/// external vids.
+pub fn parse<'a, I>(lines: I) -> Result<RegionRequirements, String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut lines = lines.into_iter();
+
+    let first = lines
+        .next()
+        .ok_or_else(|| "missing `external vids: N` line".to_string())?;
+    let first = first.trim();
+    if !first.starts_with("external vids:") {
+        return Err(format!("expected `external vids: N`, found `{}`", first));
+    }
+    let num_external_vids = first["external vids:".len()..]
+        .trim()
+        .parse()
+        .map_err(|_| format!("bad number of external vids in `{}`", first))?;
+
+    let mut requirements: Vec<Requirement> = vec![];
+    for line in lines {
+        let requirement = parse_requirement(line.trim())?;
+        for &vid in &[requirement.free_region, requirement.outlived_free_region] {
+            if vid >= num_external_vids {
+                return Err(format!(
+                    "`{}` names '_#{}r, which is not one of the {} external vids",
+                    line,
+                    vid,
+                    num_external_vids
+                ));
+            }
+        }
+        if let Some(previous) = requirements.last() {
+            let key = |r: &Requirement| (r.free_region, r.outlived_free_region);
+            if key(previous) > key(&requirement) {
+                return Err(format!("`{}` is out of order", line));
+            }
+        }
+        requirements.push(requirement);
+    }
+
+    Ok(RegionRequirements {
+        num_external_vids,
+        requirements,
+    })
+}
+
+/// Parses `'_#1r: '_#3r @ span`.
+fn parse_requirement(line: &str) -> Result<Requirement, String> {
+    let bad = || format!("expected `'_#Nr: '_#Mr @ span`, found `{}`", line);
+
+    let (regions, blame_span) = match line.find(" @ ") {
+        Some(idx) => (&line[..idx], line[idx + " @ ".len()..].trim()),
+        None => return Err(bad()),
+    };
+    if blame_span.is_empty() {
+        return Err(bad());
+    }
+
+    let mut regions = regions.splitn(2, ':');
+    let free_region = regions.next().and_then(parse_vid).ok_or_else(|| bad())?;
+    let outlived_free_region = regions.next().and_then(parse_vid).ok_or_else(|| bad())?;
+
+    Ok(Requirement {
+        free_region,
+        outlived_free_region,
+        blame_span: blame_span.to_string(),
+    })
+}
+
+/// Parses the `Debug` form of a `RegionVid`, e.g. `'_#3r`.
+fn parse_vid(s: &str) -> Option<usize> {
+    let s = s.trim();
+    if s.starts_with("'_#") && s.ends_with('r') {
+        s["'_#".len()..s.len() - 1].parse().ok()
+    } else {
+        None
+    }
+}
+
+#[test]
+fn test_parse_region_requirements() {
+    let parsed = parse(vec![
+        "external vids: 3",
+        "'_#1r: '_#2r @ $DIR/foo.rs:4:5: 4:10",
+        "'_#2r: '_#1r @ $DIR/foo.rs:5:5: 5:10",
+    ]).unwrap();
+    assert_eq!(parsed.num_external_vids, 3);
+    assert_eq!(
+        parsed.requirements[0],
+        Requirement {
+            free_region: 1,
+            outlived_free_region: 2,
+            blame_span: "$DIR/foo.rs:4:5: 4:10".to_string(),
+        }
+    );
+    assert_eq!(parsed.requirements[1].free_region, 2);
+
+    assert_eq!(parse(vec!["external vids: 1"]).unwrap().requirements, vec![]);
+}
+
+#[test]
+fn test_parse_region_requirements_errors() {
+    // No header line.
+    assert!(parse(vec![]).is_err());
+    assert!(parse(vec!["external vids: x"]).is_err());
+
+    // Malformed requirements.
+    assert!(parse(vec!["external vids: 3", "'_#1r '_#2r @ $DIR/foo.rs:4:5: 4:10"]).is_err());
+    assert!(parse(vec!["external vids: 3", "'_#1r: '_#2r"]).is_err());
+    assert!(parse(vec!["external vids: 3", "'_#1r: '_#2r @ "]).is_err());
+    assert!(parse(vec!["external vids: 3", "where '_#1r: '_#2r"]).is_err());
+
+    // A vid that is not external.
+    assert!(parse(vec!["external vids: 2", "'_#1r: '_#2r @ $DIR/foo.rs:4:5: 4:10"]).is_err());
+
+    // Requirements out of order.
+    assert!(parse(vec![
+        "external vids: 3",
+        "'_#2r: '_#1r @ $DIR/foo.rs:5:5: 5:10",
+        "'_#1r: '_#2r @ $DIR/foo.rs:4:5: 4:10",
+    ]).is_err());
+}
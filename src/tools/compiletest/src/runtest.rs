@@ -18,6 +18,7 @@ use errors::{self, Error, ErrorKind};
 use filetime::FileTime;
 use json;
 use header::TestProps;
+use region_requirements;
 use test::TestPaths;
 use util::logv;
 use regex::Regex;
@@ -2488,6 +2489,7 @@ impl<'test> TestCx<'test> {
                     if Some(t) != curr_test {
                         panic!("mismatched START END test name");
                     }
+                    self.check_region_requirements_lines(curr_test.unwrap(), &curr_test_contents);
                     self.compare_mir_test_output(curr_test.unwrap(), &curr_test_contents);
                     curr_test = None;
                     curr_test_contents.clear();
@@ -2504,6 +2506,40 @@ impl<'test> TestCx<'test> {
         }
     }
 
+    /// Checks that the expected lines following `| Free Region Constraints`,
+    /// if any, are in the format in which the NLL dump writes closure
+    /// region requirements. The check stops at the first elision.
+    fn check_region_requirements_lines(&self, test_name: &str, expected: &[ExpectedLine<&str>]) {
+        let header = format!("| {}", region_requirements::HEADER);
+        let mut lines = expected.iter();
+        while let Some(line) = lines.next() {
+            match *line {
+                ExpectedLine::Text(t) if t.trim() == header => {}
+                _ => continue,
+            }
+
+            let block: Vec<&str> = lines
+                .clone()
+                .map(|l| match *l {
+                    ExpectedLine::Text(t) => t.trim(),
+                    ExpectedLine::Elision => "",
+                })
+                .take_while(|t| t.starts_with("| "))
+                .map(|t| &t["| ".len()..])
+                .collect();
+            if block.is_empty() {
+                continue;
+            }
+            if let Err(msg) = region_requirements::parse(block) {
+                self.fatal(&format!(
+                    "malformed free region constraints in `{}`: {}",
+                    test_name,
+                    msg
+                ));
+            }
+        }
+    }
+
     fn check_mir_test_timestamp(&self, test_name: &str, output_file: &Path) {
         let t = |file| FileTime::from_last_modification_time(&fs::metadata(file).unwrap());
         let source_file = &self.testpaths.file;
@@ -2540,6 +2576,10 @@ impl<'test> TestCx<'test> {
         let mut dumped_file = fs::File::open(output_file.clone()).unwrap();
         let mut dumped_string = String::new();
         dumped_file.read_to_string(&mut dumped_string).unwrap();
+        // Spans in the dump (e.g. the blame spans of closure region
+        // requirements) name the test file by its full path.
+        let parent_dir = self.testpaths.file.parent().unwrap().display().to_string();
+        let dumped_string = dumped_string.replace(&parent_dir, "$DIR");
         let mut dumped_lines = dumped_string
             .lines()
             .map(|l| nocomment_mir_line(l))